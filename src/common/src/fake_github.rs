@@ -0,0 +1,763 @@
+use crate::mock_server::{MockRequest, MockResponse, MockServer};
+use crate::types::{
+    GithubLabel, TrackerComment, TrackerIssue, TrackerIssueEvent, TrackerIssueEventKind,
+    TrackerIssueState,
+};
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+/// A fake Github API for one repository, to test against without a network connection.
+///
+/// It keeps the labels and issues in memory, answers the REST routes that `Github` uses and
+/// records every request it receives. It also has a project board, numbered 1 and owned by the
+/// repository owner, that can be reached through the GraphQL API.
+pub struct FakeGithub {
+    server: MockServer,
+    state: Arc<Mutex<FakeGithubState>>,
+}
+
+#[derive(Default)]
+struct FakeGithubState {
+    owner: String,
+    repo: String,
+    labels: Vec<GithubLabel>,
+    issues: Vec<TrackerIssue>,
+    comments: HashMap<u64, Vec<TrackerComment>>,
+    events: HashMap<u64, Vec<TrackerIssueEvent>>,
+    milestones: Vec<FakeMilestone>,
+    /// The number of upcoming requests to answer with a secondary rate limit error.
+    rate_limited_requests: usize,
+    /// The number of upcoming requests to apply and then answer with a bad gateway error.
+    bad_gateway_requests: usize,
+    /// Whether the user or app can only read the repository.
+    is_read_only: bool,
+    /// The fields of the project board, with the options of single select fields.
+    project_fields: Vec<(String, Vec<String>)>,
+    project_items: Vec<FakeProjectItem>,
+    pinned_issues: Vec<u64>,
+    locked_issues: Vec<u64>,
+}
+
+/// A milestone of the fake Github.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FakeMilestone {
+    pub number: u64,
+    pub title: String,
+    /// When the milestone is due, as Github reports it.
+    pub due_on: Option<String>,
+}
+
+/// An issue on the project board of the fake Github.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FakeProjectItem {
+    pub issue_number: u64,
+    /// The values of the fields, by field name.
+    pub fields: BTreeMap<String, String>,
+}
+
+impl FakeGithub {
+    pub async fn start(owner: &str, repo: &str) -> Result<Self> {
+        let state = Arc::new(Mutex::new(FakeGithubState {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            ..Default::default()
+        }));
+        let handler_state = state.clone();
+        let server =
+            MockServer::start(move |request| handler_state.lock().unwrap().handle(request)).await?;
+        Ok(Self { server, state })
+    }
+
+    /// The address to use as the Github API URL.
+    pub fn url(&self) -> &str {
+        &self.server.url
+    }
+
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.server.requests()
+    }
+
+    pub fn labels(&self) -> Vec<GithubLabel> {
+        self.state.lock().unwrap().labels.clone()
+    }
+
+    pub fn issues(&self) -> Vec<TrackerIssue> {
+        self.state.lock().unwrap().issues.clone()
+    }
+
+    pub fn comments(&self, issue_id: u64) -> Vec<String> {
+        let state = self.state.lock().unwrap();
+        let comments = state.comments.get(&issue_id).cloned().unwrap_or_default();
+        comments.into_iter().map(|c| c.body).collect()
+    }
+
+    /// Comment on an issue as another user.
+    pub fn add_comment(&self, issue_id: u64, author: &str, body: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.add_comment(issue_id, author, body);
+    }
+
+    /// Add a label to an issue as another user.
+    pub fn label_issue(&self, issue_id: u64, actor: &str, label: &str) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(issue) = state.issues.iter_mut().find(|i| i.number == issue_id) {
+            issue.labels.push(label.to_string());
+        }
+        state.add_event(
+            issue_id,
+            actor,
+            TrackerIssueEventKind::Labeled(label.to_string()),
+        );
+    }
+
+    /// Close an issue as another user.
+    pub fn close_issue(&self, issue_id: u64, actor: &str) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(issue) = state.issues.iter_mut().find(|i| i.number == issue_id) {
+            issue.state = TrackerIssueState::Closed;
+            issue.closed_at = Some(chrono::Utc::now().to_rfc3339());
+        }
+        state.add_event(issue_id, actor, TrackerIssueEventKind::Closed);
+    }
+
+    pub fn milestones(&self) -> Vec<FakeMilestone> {
+        self.state.lock().unwrap().milestones.clone()
+    }
+
+    pub fn add_label(&self, name: &str) {
+        self.state.lock().unwrap().labels.push(GithubLabel {
+            name: name.to_string(),
+            color: "ffffff".to_string(),
+            description: None,
+        });
+    }
+
+    /// Add an open issue and return its number.
+    pub fn add_issue(&self, title: &str, body: &str, labels: &[&str]) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        let labels = labels.iter().map(|l| l.to_string()).collect();
+        state.create_issue(title.to_string(), body.to_string(), labels)
+    }
+
+    /// Change an issue the way someone editing it on Github would.
+    pub fn edit_issue(&self, issue_id: u64, edit: impl FnOnce(&mut TrackerIssue)) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(issue) = state.issues.iter_mut().find(|i| i.number == issue_id) {
+            edit(issue);
+        }
+    }
+
+    /// Add a field to the project board, a single select field if it has options.
+    pub fn add_project_field(&self, name: &str, options: &[&str]) {
+        self.state.lock().unwrap().project_fields.push((
+            name.to_string(),
+            options.iter().map(|o| o.to_string()).collect(),
+        ));
+    }
+
+    pub fn project_items(&self) -> Vec<FakeProjectItem> {
+        self.state.lock().unwrap().project_items.clone()
+    }
+
+    /// The numbers of the pinned issues, in the order they were pinned.
+    pub fn pinned_issues(&self) -> Vec<u64> {
+        self.state.lock().unwrap().pinned_issues.clone()
+    }
+
+    /// The numbers of the locked issues, in the order they were locked.
+    pub fn locked_issues(&self) -> Vec<u64> {
+        self.state.lock().unwrap().locked_issues.clone()
+    }
+
+    /// Give the user or app read access to the repository only.
+    pub fn set_read_only(&self, is_read_only: bool) {
+        self.state.lock().unwrap().is_read_only = is_read_only;
+    }
+
+    /// Answer the next requests with the error Github sends for its secondary rate limit.
+    pub fn rate_limit_next_requests(&self, count: usize) {
+        self.state.lock().unwrap().rate_limited_requests = count;
+    }
+
+    /// Apply the next requests but answer them with a bad gateway error, the way Github does
+    /// when it times out after doing what was asked.
+    pub fn fail_next_requests(&self, count: usize) {
+        self.state.lock().unwrap().bad_gateway_requests = count;
+    }
+}
+
+impl FakeGithubState {
+    fn handle(&mut self, request: &MockRequest) -> MockResponse {
+        if self.rate_limited_requests > 0 {
+            self.rate_limited_requests -= 1;
+            return MockResponse::json(
+                403,
+                &json!({ "message": "You have exceeded a secondary rate limit." }),
+            )
+            .with_header("retry-after", "0");
+        }
+
+        let response = self.handle_request(request);
+        if self.bad_gateway_requests > 0 {
+            self.bad_gateway_requests -= 1;
+            return MockResponse::json(502, &json!({ "message": "Server Error" }));
+        }
+        response
+    }
+
+    fn handle_request(&mut self, request: &MockRequest) -> MockResponse {
+        let repository = format!("/repos/{}/{}", self.owner, self.repo);
+        match (request.method.as_str(), request.route()) {
+            ("GET", "/user") => return MockResponse::json(200, &json!({ "login": "fake-user" })),
+            ("GET", route) if route == repository => {
+                return MockResponse::json(
+                    200,
+                    &json!({
+                        "full_name": format!("{}/{}", self.owner, self.repo),
+                        "permissions": {
+                            "admin": false,
+                            "maintain": false,
+                            "push": !self.is_read_only,
+                            "triage": !self.is_read_only,
+                            "pull": true,
+                        },
+                    }),
+                )
+            }
+            ("POST", "/graphql") => {
+                let body: Value = serde_json::from_str(&request.body).unwrap_or_default();
+                return self.handle_graphql(
+                    body["query"].as_str().unwrap_or_default(),
+                    &body["variables"],
+                );
+            }
+            ("POST", "/app/installations/1/access_tokens") => {
+                return MockResponse::json(
+                    201,
+                    &json!({ "token": "installation-token", "permissions": {} }),
+                )
+            }
+            _ => {}
+        }
+        let Some(route) = request.route().strip_prefix(&format!("{}/", repository)) else {
+            return not_found();
+        };
+        let segments: Vec<String> = route
+            .split('/')
+            .map(|s| {
+                percent_encoding::percent_decode_str(s)
+                    .decode_utf8_lossy()
+                    .to_string()
+            })
+            .collect();
+        let body: Value = serde_json::from_str(&request.body).unwrap_or_default();
+        let segments: Vec<&str> = segments.iter().map(|s| s.as_str()).collect();
+
+        match (request.method.as_str(), segments.as_slice()) {
+            ("GET", ["installation"]) => MockResponse::json(
+                200,
+                &json!({
+                    "id": 1,
+                    "permissions": {
+                        "issues": if self.is_read_only { "read" } else { "write" },
+                    },
+                }),
+            ),
+            ("GET", ["labels"]) => {
+                let labels: Vec<Value> = self.labels.iter().map(label_to_json).collect();
+                MockResponse::json(200, &Value::from(get_page(request, labels)))
+            }
+            ("POST", ["labels"]) => {
+                let label = GithubLabel {
+                    name: body["name"].as_str().unwrap_or_default().to_string(),
+                    color: body["color"].as_str().unwrap_or_default().to_string(),
+                    description: body["description"].as_str().map(|d| d.to_string()),
+                };
+                // Github compares label names without case
+                if self
+                    .labels
+                    .iter()
+                    .any(|l| l.name.to_lowercase() == label.name.to_lowercase())
+                {
+                    return MockResponse::json(422, &json!({ "message": "Validation Failed" }));
+                }
+                let response = label_to_json(&label);
+                self.labels.push(label);
+                MockResponse::json(201, &response)
+            }
+            ("DELETE", ["labels", name]) => {
+                self.labels.retain(|l| l.name != *name);
+                MockResponse::empty(204)
+            }
+            ("GET", ["milestones"]) => {
+                let milestones: Vec<Value> =
+                    self.milestones.iter().map(milestone_to_json).collect();
+                MockResponse::json(200, &Value::from(get_page(request, milestones)))
+            }
+            ("POST", ["milestones"]) => {
+                let title = body["title"].as_str().unwrap_or_default().to_string();
+                if self.milestones.iter().any(|m| m.title == title) {
+                    return MockResponse::json(422, &json!({ "message": "Validation Failed" }));
+                }
+                self.milestones.push(FakeMilestone {
+                    number: self.milestones.len() as u64 + 1,
+                    title,
+                    due_on: body["due_on"].as_str().map(|d| d.to_string()),
+                });
+                MockResponse::json(201, &milestone_to_json(self.milestones.last().unwrap()))
+            }
+            ("GET", ["issues"]) => {
+                let state = request.get_query("state").unwrap_or("open");
+                let issues: Vec<Value> = self
+                    .issues
+                    .iter()
+                    .filter(|i| match state {
+                        "open" => i.state == TrackerIssueState::Open,
+                        "closed" => i.state == TrackerIssueState::Closed,
+                        _ => true,
+                    })
+                    .map(issue_to_json)
+                    .collect();
+                MockResponse::json(200, &Value::from(get_page(request, issues)))
+            }
+            ("POST", ["issues"]) => {
+                let labels = body["labels"]
+                    .as_array()
+                    .map(|labels| {
+                        labels
+                            .iter()
+                            .filter_map(|l| l.as_str().map(|l| l.to_string()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                self.create_issue(
+                    body["title"].as_str().unwrap_or_default().to_string(),
+                    body["body"].as_str().unwrap_or_default().to_string(),
+                    labels,
+                );
+                MockResponse::json(201, &issue_to_json(self.issues.last().unwrap()))
+            }
+            ("GET", ["issues", number]) => match self.get_issue(number) {
+                Some(issue) => MockResponse::json(200, &issue_to_json(issue)),
+                None => not_found(),
+            },
+            ("PATCH", ["issues", number]) => {
+                let milestone = match &body["milestone"] {
+                    Value::Number(milestone) => {
+                        let milestone = self
+                            .milestones
+                            .iter()
+                            .find(|m| Some(m.number) == milestone.as_u64());
+                        match milestone {
+                            Some(milestone) => Some(Some(milestone.title.clone())),
+                            None => {
+                                return MockResponse::json(
+                                    422,
+                                    &json!({ "message": "Validation Failed" }),
+                                )
+                            }
+                        }
+                    }
+                    Value::Null if body.get("milestone").is_some() => Some(None),
+                    _ => None,
+                };
+                let Some(issue) = self.get_issue_mut(number) else {
+                    return not_found();
+                };
+                if let Some(milestone) = milestone {
+                    issue.milestone = milestone;
+                }
+                if let Some(title) = body["title"].as_str() {
+                    issue.title = title.to_string();
+                }
+                if let Some(issue_body) = body["body"].as_str() {
+                    issue.body = Some(issue_body.to_string());
+                }
+                let event = match body["state"].as_str() {
+                    Some("closed") => {
+                        issue.state = TrackerIssueState::Closed;
+                        issue.closed_at = Some(chrono::Utc::now().to_rfc3339());
+                        Some(TrackerIssueEventKind::Closed)
+                    }
+                    Some("open") => {
+                        issue.state = TrackerIssueState::Open;
+                        issue.closed_at = None;
+                        Some(TrackerIssueEventKind::Reopened)
+                    }
+                    _ => None,
+                };
+                let response = issue_to_json(issue);
+                let number = issue.number;
+                if let Some(event) = event {
+                    self.add_event(number, "fake-user", event);
+                }
+                MockResponse::json(200, &response)
+            }
+            ("POST", ["issues", number, "labels"]) => {
+                let Some(issue) = self.get_issue_mut(number) else {
+                    return not_found();
+                };
+                let mut added_labels = vec![];
+                for label in body["labels"].as_array().into_iter().flatten() {
+                    let label = label.as_str().unwrap_or_default().to_string();
+                    if !issue.labels.contains(&label) {
+                        issue.labels.push(label.clone());
+                        added_labels.push(label);
+                    }
+                }
+                let labels = issue.labels.clone();
+                let issue_number = issue.number;
+                self.create_missing_labels(&labels);
+                for label in added_labels {
+                    self.add_event(
+                        issue_number,
+                        "fake-user",
+                        TrackerIssueEventKind::Labeled(label),
+                    );
+                }
+                MockResponse::json(
+                    200,
+                    &issue_to_json(self.get_issue(number).unwrap())["labels"],
+                )
+            }
+            ("DELETE", ["issues", number, "labels", name]) => {
+                let Some(issue) = self.get_issue_mut(number) else {
+                    return not_found();
+                };
+                if !issue.labels.iter().any(|l| l == name) {
+                    return not_found();
+                }
+                issue.labels.retain(|l| l != name);
+                let response = issue_to_json(issue)["labels"].clone();
+                let number = issue.number;
+                self.add_event(
+                    number,
+                    "fake-user",
+                    TrackerIssueEventKind::Unlabeled(name.to_string()),
+                );
+                MockResponse::json(200, &response)
+            }
+            ("PUT", ["issues", number, "lock"]) => {
+                let Some(issue) = self.get_issue(number) else {
+                    return not_found();
+                };
+                let number = issue.number;
+                if !self.locked_issues.contains(&number) {
+                    self.locked_issues.push(number);
+                }
+                MockResponse::empty(204)
+            }
+            ("POST", ["issues", number, "assignees"]) => {
+                let Some(issue) = self.get_issue_mut(number) else {
+                    return not_found();
+                };
+                for assignee in body["assignees"].as_array().into_iter().flatten() {
+                    let assignee = assignee.as_str().unwrap_or_default().to_string();
+                    if !issue.assignees.contains(&assignee) {
+                        issue.assignees.push(assignee);
+                    }
+                }
+                MockResponse::json(201, &issue_to_json(issue))
+            }
+            ("POST", ["issues", number, "comments"]) => {
+                let Some(issue) = self.get_issue_mut(number) else {
+                    return not_found();
+                };
+                let number = issue.number;
+                self.add_comment(
+                    number,
+                    "fake-user",
+                    body["body"].as_str().unwrap_or_default(),
+                );
+                MockResponse::json(201, &json!({ "body": body["body"] }))
+            }
+            ("GET", ["issues", number, "comments"]) => {
+                let Some(issue) = self.get_issue(number) else {
+                    return not_found();
+                };
+                let comments: Vec<Value> = self
+                    .comments
+                    .get(&issue.number)
+                    .into_iter()
+                    .flatten()
+                    .map(|c| {
+                        json!({
+                            "user": { "login": c.author },
+                            "body": c.body,
+                            "created_at": c.created_at,
+                        })
+                    })
+                    .collect();
+                MockResponse::json(200, &Value::from(get_page(request, comments)))
+            }
+            ("GET", ["issues", number, "events"]) => {
+                let Some(issue) = self.get_issue(number) else {
+                    return not_found();
+                };
+                let events: Vec<Value> = self
+                    .events
+                    .get(&issue.number)
+                    .into_iter()
+                    .flatten()
+                    .map(event_to_json)
+                    .collect();
+                MockResponse::json(200, &Value::from(get_page(request, events)))
+            }
+            _ => not_found(),
+        }
+    }
+
+    /// Answer the GraphQL queries and mutations that `Github` sends for project boards.
+    fn handle_graphql(&mut self, query: &str, variables: &Value) -> MockResponse {
+        let data = if query.contains("projectV2(number:") {
+            if variables["owner"] != self.owner.as_str() || variables["number"] != 1 {
+                return graphql_error("Could not resolve to a ProjectV2 with the number.");
+            }
+            let fields: Vec<Value> = self
+                .project_fields
+                .iter()
+                .enumerate()
+                .map(|(i, (name, options))| {
+                    let options: Vec<Value> = options
+                        .iter()
+                        .enumerate()
+                        .map(|(j, o)| json!({ "id": format!("O_{}_{}", i, j), "name": o }))
+                        .collect();
+                    json!({
+                        "id": format!("F_{}", i),
+                        "name": name,
+                        "dataType": if options.is_empty() { "TEXT" } else { "SINGLE_SELECT" },
+                        "options": options,
+                    })
+                })
+                .collect();
+            json!({ "repositoryOwner": { "projectV2": {
+                "id": "P_1",
+                "fields": { "nodes": fields },
+            } } })
+        } else if query.contains("pinIssue") {
+            let Some(issue_number) = self.get_issue_number(&variables["issue"]) else {
+                return graphql_error("Could not resolve to a node with the global id.");
+            };
+            if !self.pinned_issues.contains(&issue_number) {
+                self.pinned_issues.push(issue_number);
+            }
+            json!({ "pinIssue": { "issue": { "id": format!("I_{}", issue_number) } } })
+        } else if query.contains("addProjectV2ItemById") {
+            let Some(issue_number) = self.get_issue_number(&variables["content"]) else {
+                return graphql_error("Could not resolve to a node with the global id.");
+            };
+            let index = match self
+                .project_items
+                .iter()
+                .position(|i| i.issue_number == issue_number)
+            {
+                Some(index) => index,
+                None => {
+                    self.project_items.push(FakeProjectItem {
+                        issue_number,
+                        fields: BTreeMap::new(),
+                    });
+                    self.project_items.len() - 1
+                }
+            };
+            json!({ "addProjectV2ItemById": { "item": { "id": format!("PVTI_{}", index) } } })
+        } else if query.contains("updateProjectV2ItemFieldValue") {
+            let field = variables["field"]
+                .as_str()
+                .and_then(|f| f.strip_prefix("F_"))
+                .and_then(|f| f.parse::<usize>().ok())
+                .and_then(|f| self.project_fields.get(f));
+            let item = variables["item"]
+                .as_str()
+                .and_then(|i| i.strip_prefix("PVTI_"))
+                .and_then(|i| i.parse::<usize>().ok());
+            let (Some((name, options)), Some(item)) = (field, item) else {
+                return graphql_error("Could not resolve to a node with the global id.");
+            };
+            let value = &variables["value"];
+            let value = match value["singleSelectOptionId"].as_str() {
+                Some(option) => option
+                    .rsplit('_')
+                    .next()
+                    .and_then(|j| j.parse::<usize>().ok())
+                    .and_then(|j| options.get(j))
+                    .cloned()
+                    .unwrap_or_default(),
+                None => value["text"].as_str().unwrap_or_default().to_string(),
+            };
+            let name = name.clone();
+            self.project_items[item].fields.insert(name, value);
+            json!({ "updateProjectV2ItemFieldValue": { "projectV2Item": { "id": format!("PVTI_{}", item) } } })
+        } else {
+            return graphql_error("Unknown query");
+        };
+        MockResponse::json(200, &json!({ "data": data }))
+    }
+
+    /// Get the number of an existing issue from its GraphQL node ID.
+    fn get_issue_number(&self, node_id: &Value) -> Option<u64> {
+        let number = node_id.as_str()?.strip_prefix("I_")?;
+        self.get_issue(number).map(|i| i.number)
+    }
+
+    fn create_issue(&mut self, title: String, body: String, labels: Vec<String>) -> u64 {
+        let number = self.issues.len() as u64 + 1;
+        self.create_missing_labels(&labels);
+        self.issues.push(TrackerIssue {
+            number,
+            title,
+            body: Some(body),
+            labels,
+            state: TrackerIssueState::Open,
+            url: format!(
+                "https://github.com/{}/{}/issues/{}",
+                self.owner, self.repo, number
+            ),
+            is_pull_request: false,
+            milestone: None,
+            closed_at: None,
+            assignees: vec![],
+        });
+        number
+    }
+
+    /// Github creates the labels that issues are given if they don't exist yet.
+    fn create_missing_labels(&mut self, labels: &[String]) {
+        for label in labels {
+            if !self
+                .labels
+                .iter()
+                .any(|l| l.name.to_lowercase() == label.to_lowercase())
+            {
+                self.labels.push(GithubLabel {
+                    name: label.clone(),
+                    color: "ededed".to_string(),
+                    description: None,
+                });
+            }
+        }
+    }
+
+    fn add_comment(&mut self, issue_id: u64, author: &str, body: &str) {
+        self.comments
+            .entry(issue_id)
+            .or_default()
+            .push(TrackerComment {
+                author: Some(author.to_string()),
+                body: body.to_string(),
+                created_at: Some(chrono::Utc::now().to_rfc3339()),
+            });
+    }
+
+    fn add_event(&mut self, issue_id: u64, actor: &str, kind: TrackerIssueEventKind) {
+        self.events
+            .entry(issue_id)
+            .or_default()
+            .push(TrackerIssueEvent {
+                kind,
+                actor: Some(actor.to_string()),
+                created_at: Some(chrono::Utc::now().to_rfc3339()),
+            });
+    }
+
+    fn get_issue(&self, number: &str) -> Option<&TrackerIssue> {
+        let number: u64 = number.parse().ok()?;
+        self.issues.iter().find(|i| i.number == number)
+    }
+
+    fn get_issue_mut(&mut self, number: &str) -> Option<&mut TrackerIssue> {
+        let number: u64 = number.parse().ok()?;
+        self.issues.iter_mut().find(|i| i.number == number)
+    }
+}
+
+/// Github also lists events this fake doesn't make, like mentions, which yatm skips.
+fn event_to_json(event: &TrackerIssueEvent) -> Value {
+    let (name, label) = match &event.kind {
+        TrackerIssueEventKind::Labeled(label) => ("labeled", Some(label)),
+        TrackerIssueEventKind::Unlabeled(label) => ("unlabeled", Some(label)),
+        TrackerIssueEventKind::Closed => ("closed", None),
+        TrackerIssueEventKind::Reopened => ("reopened", None),
+    };
+    json!({
+        "event": name,
+        "actor": { "login": event.actor },
+        "label": label.map(|name| json!({ "name": name, "color": "ededed" })),
+        "created_at": event.created_at,
+    })
+}
+
+fn not_found() -> MockResponse {
+    MockResponse::json(404, &json!({ "message": "Not Found" }))
+}
+
+/// Github answers GraphQL errors with a successful response.
+fn graphql_error(message: &str) -> MockResponse {
+    MockResponse::json(
+        200,
+        &json!({ "data": null, "errors": [{ "message": message }] }),
+    )
+}
+
+/// Get the page of items the request asks for with `page` and `per_page`.
+fn get_page(request: &MockRequest, items: Vec<Value>) -> Vec<Value> {
+    let page: usize = request
+        .get_query("page")
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(1);
+    let per_page: usize = request
+        .get_query("per_page")
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(30);
+    items
+        .into_iter()
+        .skip((page.max(1) - 1) * per_page)
+        .take(per_page)
+        .collect()
+}
+
+fn label_to_json(label: &GithubLabel) -> Value {
+    json!({
+        "name": label.name,
+        "color": label.color,
+        "description": label.description,
+    })
+}
+
+fn milestone_to_json(milestone: &FakeMilestone) -> Value {
+    json!({
+        "number": milestone.number,
+        "title": milestone.title,
+        "due_on": milestone.due_on,
+    })
+}
+
+fn issue_to_json(issue: &TrackerIssue) -> Value {
+    json!({
+        "number": issue.number,
+        "node_id": format!("I_{}", issue.number),
+        "title": issue.title,
+        "body": issue.body,
+        "labels": issue
+            .labels
+            .iter()
+            .map(|l| json!({ "name": l, "color": "ededed", "description": null }))
+            .collect::<Vec<_>>(),
+        "state": match issue.state {
+            TrackerIssueState::Open => "open",
+            TrackerIssueState::Closed => "closed",
+        },
+        "html_url": issue.url,
+        "pull_request": null,
+        "milestone": issue.milestone.as_ref().map(|title| json!({ "title": title })),
+        "closed_at": issue.closed_at,
+        "assignees": issue
+            .assignees
+            .iter()
+            .map(|a| json!({ "login": a }))
+            .collect::<Vec<_>>(),
+    })
+}
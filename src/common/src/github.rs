@@ -1,51 +1,753 @@
-use crate::types::GithubLabel;
+use crate::issue_tracker::IssueTracker;
+use crate::request_retry::{send_with_retries, TrackerResponse};
+use crate::types::{
+    GithubAuth, GithubLabel, TrackerComment, TrackerIssue, TrackerIssueEvent,
+    TrackerIssueEventKind, TrackerIssueState,
+};
 use anyhow::Ok;
 use anyhow::{anyhow, Context, Result};
-use async_recursion::async_recursion;
+use async_trait::async_trait;
 use dotenv::dotenv;
-use octocrab::models::issues::Issue;
-use octocrab::models::IssueState;
-use octocrab::models::Label;
-use octocrab::params::State;
+use http::Method;
+use octocrab::models::{AppId, InstallationId};
+use octocrab::service::middleware::retry::RetryConfig;
 use octocrab::Octocrab;
-use tokio::time::{sleep, Duration};
+use secrecy::ExposeSecret;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tokio::sync::{OnceCell, Semaphore};
+
+/// The address of the Github API when no other one is configured.
+pub const GITHUB_API_URL: &str = "https://api.github.com";
+/// The address of the Github website when no other one is configured.
+pub const GITHUB_WEB_URL: &str = "https://github.com";
 
 pub struct Github {
-    octocrab: Option<Octocrab>,
+    octocrab: Octocrab,
+    /// The address of the API, such as `https://api.github.com`.
+    api_url: String,
+    /// The client for the GraphQL API of Github Enterprise Server, when authenticated with a token.
+    enterprise_graphql_octocrab: Option<Octocrab>,
+    /// The address of the website the issues are shown on, such as `https://github.com`.
+    web_url: String,
     owner: String,
     repo: String,
+    request_slots: Semaphore,
+    /// The Github App installation the requests are made as, when authenticated as an app.
+    app_installation: Option<GithubAppInstallation>,
+    /// The project board that issues are added to.
+    project: Option<GithubProjectTarget>,
+    /// The numbers of the milestones seen so far, by title.
+    milestone_numbers: Mutex<HashMap<String, u64>>,
+}
+
+struct GithubProjectTarget {
+    /// The user or organization that owns the project.
+    owner: String,
+    number: u64,
+    /// The project and its fields, looked up on first use.
+    info: OnceCell<GithubProjectInfo>,
+}
+
+#[derive(Deserialize)]
+struct GithubProjectInfo {
+    id: String,
+    fields: GithubProjectFields,
+}
+
+#[derive(Deserialize)]
+struct GithubProjectFields {
+    nodes: Vec<GithubProjectField>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GithubProjectField {
+    id: String,
+    name: String,
+    data_type: String,
+    /// The choices of single select fields.
+    #[serde(default)]
+    options: Vec<GithubProjectFieldOption>,
+}
+
+#[derive(Deserialize)]
+struct GithubProjectFieldOption {
+    id: String,
+    name: String,
+}
+
+struct GithubAppInstallation {
+    app_id: u64,
+    installation_id: u64,
+    can_write_issues: bool,
+}
+
+/// Who requests to Github are made as, and whether they can change the issues.
+pub struct GithubAuthCheck {
+    pub identity: String,
+    pub can_write_issues: bool,
+}
+
+#[derive(Deserialize)]
+struct GithubApiLabel {
+    name: String,
+    color: String,
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GithubApiIssue {
+    number: u64,
+    title: String,
+    body: Option<String>,
+    labels: Vec<GithubApiLabel>,
+    state: String,
+    html_url: String,
+    pull_request: Option<serde_json::Value>,
+    milestone: Option<GithubApiIssueMilestone>,
+    closed_at: Option<String>,
+    #[serde(default)]
+    assignees: Vec<GithubApiUser>,
+}
+
+#[derive(Deserialize)]
+struct GithubApiUser {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct GithubApiComment {
+    user: Option<GithubApiUser>,
+    body: Option<String>,
+    created_at: String,
+}
+
+#[derive(Deserialize)]
+struct GithubApiIssueEvent {
+    event: String,
+    actor: Option<GithubApiUser>,
+    label: Option<GithubApiIssueEventLabel>,
+    created_at: String,
+}
+
+#[derive(Deserialize)]
+struct GithubApiIssueEventLabel {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct GithubApiIssueMilestone {
+    title: String,
+}
+
+#[derive(Deserialize)]
+struct GithubApiMilestone {
+    number: u64,
+    title: String,
 }
 
 impl Github {
-    pub fn new(owner: &String, repo: &String) -> Result<Self> {
+    /// Connect to the Github API at `api_url` with the token in `GITHUB_TOKEN`.
+    pub fn new(api_url: &str, owner: &str, repo: &str) -> Result<Self> {
         dotenv().ok();
 
-        let token = std::env::var("GITHUB_TOKEN");
+        Self::new_with_env_token(
+            api_url,
+            owner,
+            repo,
+            std::env::var("GITHUB_TOKEN").ok().as_deref(),
+        )
+    }
+
+    /// Connect to the Github API at `api_url` with the token read from `GITHUB_TOKEN`, if it was
+    /// set.
+    fn new_with_env_token(
+        api_url: &str,
+        owner: &str,
+        repo: &str,
+        env_token: Option<&str>,
+    ) -> Result<Self> {
+        let token = env_token
+            .context("GITHUB_TOKEN not set, set it or choose another `github_auth` method")?;
+        Self::new_with_token(api_url, owner, repo, token)
+    }
 
-        let octocrab = match token {
-            std::result::Result::Ok(token) => Some(
-                Octocrab::builder()
-                    .personal_token(token)
-                    .build()
-                    .context("Failed to create octocrab")?,
-            ),
-            Err(_) => None,
+    /// Make a client for the repository that doesn't authenticate.
+    ///
+    /// It's for the commands that only make links and previews, so they work without a token.
+    pub fn new_offline(api_url: &str, owner: &str, repo: &str) -> Result<Self> {
+        let octocrab = Octocrab::builder()
+            .base_uri(api_url.trim_end_matches('/'))
+            .context(format!("Invalid Github API URL: {}", api_url))?
+            .add_retry_config(RetryConfig::None)
+            .build()
+            .context("Failed to create octocrab")?;
+
+        Ok(Self {
+            octocrab,
+            api_url: api_url.trim_end_matches('/').to_string(),
+            enterprise_graphql_octocrab: None,
+            web_url: GITHUB_WEB_URL.to_string(),
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            request_slots: Semaphore::new(1),
+            app_installation: None,
+            project: None,
+            milestone_numbers: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Connect to the Github API at `api_url` with the credentials chosen in `auth`.
+    pub async fn new_with_auth(
+        api_url: &str,
+        owner: &str,
+        repo: &str,
+        auth: &GithubAuth,
+    ) -> Result<Self> {
+        match auth {
+            GithubAuth::Env => Self::new(api_url, owner, repo),
+            GithubAuth::TokenFile { path } => {
+                let token = std::fs::read_to_string(path)
+                    .context(format!("Failed to read the Github token file: {:?}", path))?;
+                Self::new_with_token(api_url, owner, repo, token.trim())
+            }
+            GithubAuth::Command { command } => {
+                let token = get_token_from_command(command)?;
+                Self::new_with_token(api_url, owner, repo, &token)
+            }
+            GithubAuth::App {
+                app_id,
+                private_key_file,
+            } => Self::new_with_app(api_url, owner, repo, *app_id, private_key_file).await,
+        }
+    }
+
+    pub fn new_with_token(api_url: &str, owner: &str, repo: &str, token: &str) -> Result<Self> {
+        let octocrab = Octocrab::builder()
+            .base_uri(api_url.trim_end_matches('/'))
+            .context(format!("Invalid Github API URL: {}", api_url))?
+            // `request_with` retries the requests that are safe to send again
+            .add_retry_config(RetryConfig::None)
+            .personal_token(token.to_string())
+            .build()
+            .context("Failed to create octocrab")?;
+        let enterprise_graphql_octocrab = match get_enterprise_host(api_url) {
+            Some(host) => Some(get_enterprise_graphql_octocrab(host, token)?),
+            None => None,
         };
 
         Ok(Self {
             octocrab,
-            owner: owner.clone(),
-            repo: repo.clone(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+            enterprise_graphql_octocrab,
+            web_url: GITHUB_WEB_URL.to_string(),
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            request_slots: Semaphore::new(1),
+            app_installation: None,
+            project: None,
+            milestone_numbers: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Connect as the installation of a Github App on the repository.
+    async fn new_with_app(
+        api_url: &str,
+        owner: &str,
+        repo: &str,
+        app_id: u64,
+        private_key_file: &PathBuf,
+    ) -> Result<Self> {
+        let private_key = std::fs::read(private_key_file).context(format!(
+            "Failed to read the Github App private key: {:?}",
+            private_key_file
+        ))?;
+        let private_key =
+            jsonwebtoken::EncodingKey::from_rsa_pem(&private_key).context(format!(
+                "Failed to parse the Github App private key: {:?}",
+                private_key_file
+            ))?;
+        let app = Octocrab::builder()
+            .base_uri(api_url.trim_end_matches('/'))
+            .context(format!("Invalid Github API URL: {}", api_url))?
+            .add_retry_config(RetryConfig::None)
+            .app(AppId(app_id), private_key)
+            .build()
+            .context("Failed to create octocrab")?;
+
+        let installation: serde_json::Value = app
+            .get(
+                format!("/repos/{}/{}/installation", owner, repo),
+                None::<&()>,
+            )
+            .await
+            .context(format!(
+                "Failed to find the installation of Github App {} on {}/{}",
+                app_id, owner, repo
+            ))?;
+        let installation_id = installation["id"].as_u64().context(format!(
+            "Github didn't return the installation of Github App {} on {}/{}",
+            app_id, owner, repo
+        ))?;
+        let octocrab = app
+            .installation(InstallationId(installation_id))
+            .context("Failed to create octocrab")?;
+
+        Ok(Self {
+            octocrab,
+            api_url: api_url.trim_end_matches('/').to_string(),
+            enterprise_graphql_octocrab: None,
+            web_url: GITHUB_WEB_URL.to_string(),
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            request_slots: Semaphore::new(1),
+            app_installation: Some(GithubAppInstallation {
+                app_id,
+                installation_id,
+                can_write_issues: installation["permissions"]["issues"] == "write",
+            }),
+            project: None,
+            milestone_numbers: Mutex::new(HashMap::new()),
         })
     }
 
-    fn get_octocrab(&self) -> Result<&Octocrab> {
-        self.octocrab.as_ref().context("GITHUB_TOKEN not set")
+    /// Set the address of the website the issues are shown on, for Github Enterprise Server.
+    pub fn with_web_url(mut self, web_url: &str) -> Self {
+        self.web_url = web_url.trim_end_matches('/').to_string();
+        self
     }
 
-    pub async fn get_labels(&self) -> Result<Vec<Label>> {
+    /// Set how many requests can be sent to Github at the same time, one by default.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.request_slots = Semaphore::new(concurrency.max(1));
+        self
+    }
+
+    /// Add the issues to the project board with the given number, owned by a user or organization.
+    pub fn with_project(mut self, owner: &str, number: u64) -> Self {
+        self.project = Some(GithubProjectTarget {
+            owner: owner.to_string(),
+            number,
+            info: OnceCell::new(),
+        });
+        self
+    }
+
+    /// Check who the requests are made as and whether they can change the issues.
+    pub async fn check_auth(&self) -> Result<GithubAuthCheck> {
+        let repository: serde_json::Value = self
+            .request(Method::GET, &self.repo_route(""), None::<&()>)
+            .await
+            .context(format!(
+                "Failed to access the repository {}/{}",
+                self.owner, self.repo
+            ))?;
+
+        if let Some(installation) = &self.app_installation {
+            return Ok(GithubAuthCheck {
+                identity: format!(
+                    "Github App {} (installation {})",
+                    installation.app_id, installation.installation_id
+                ),
+                can_write_issues: installation.can_write_issues,
+            });
+        }
+
+        let user: serde_json::Value = self
+            .request(Method::GET, "/user", None::<&()>)
+            .await
+            .context("Failed to get the authenticated user")?;
+        // Triage is the lowest role that can label and close issues
+        let permissions = &repository["permissions"];
+        Ok(GithubAuthCheck {
+            identity: user["login"].as_str().unwrap_or("unknown user").to_string(),
+            can_write_issues: ["admin", "maintain", "push", "triage"]
+                .iter()
+                .any(|role| permissions[role] == true),
+        })
+    }
+
+    fn repo_route(&self, route: &str) -> String {
+        format!("/repos/{}/{}{}", self.owner, self.repo, route)
+    }
+
+    /// Send a query to the GraphQL API and deserialize the data of the response.
+    async fn graphql<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+    ) -> Result<T> {
+        let body = json!({ "query": query, "variables": variables });
+        let response: serde_json::Value = match get_enterprise_host(&self.api_url) {
+            None => self.request(Method::POST, "/graphql", Some(&body)).await?,
+            // Github Enterprise Server serves GraphQL from `/api/graphql`, outside of the REST API
+            // path that the client puts in front of every route
+            Some(host) => match &self.enterprise_graphql_octocrab {
+                Some(octocrab) => {
+                    self.request_with(octocrab, Method::POST, "/api/graphql", Some(&body))
+                        .await?
+                }
+                None => {
+                    // The installation token of a Github App is renewed as it expires
+                    let token = self
+                        .octocrab
+                        .installation_token()
+                        .await
+                        .context("Failed to get the Github App installation token")?;
+                    let octocrab = get_enterprise_graphql_octocrab(host, token.expose_secret())?;
+                    self.request_with(&octocrab, Method::POST, "/api/graphql", Some(&body))
+                        .await?
+                }
+            },
+        };
+        // GraphQL reports errors in the body of successful responses
+        if let Some(errors) = response["errors"].as_array().filter(|e| !e.is_empty()) {
+            anyhow::bail!(
+                "Github responded with errors: {}",
+                errors
+                    .iter()
+                    .map(|e| e["message"].as_str().unwrap_or("unknown error"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        serde_json::from_value(response["data"].clone())
+            .context("Failed to parse the GraphQL response")
+    }
+
+    async fn get_milestones_helper(&self, page: u32) -> Result<Vec<GithubApiMilestone>> {
+        self.request(
+            Method::GET,
+            &self.repo_route(&format!("/milestones?state=all&per_page=100&page={}", page)),
+            None::<&()>,
+        )
+        .await
+        .context(format!(
+            "Failed to list milestones in {}/{}",
+            self.owner, self.repo
+        ))
+    }
+
+    /// Get the number of a milestone from its title.
+    async fn get_milestone_number(&self, title: &str) -> Result<u64> {
+        if let Some(number) = self.milestone_numbers.lock().unwrap().get(title) {
+            return Ok(*number);
+        }
+        self.get_milestones().await?;
+        self.milestone_numbers
+            .lock()
+            .unwrap()
+            .get(title)
+            .copied()
+            .context(format!(
+                "Milestone '{}' not found in {}/{}",
+                title, self.owner, self.repo
+            ))
+    }
+
+    /// Get the GraphQL node ID of an issue.
+    async fn get_issue_node_id(&self, issue_id: u64) -> Result<String> {
+        let issue: serde_json::Value = self
+            .request(
+                Method::GET,
+                &self.repo_route(&format!("/issues/{}", issue_id)),
+                None::<&()>,
+            )
+            .await
+            .context(format!(
+                "Failed to get issue #{} in {}/{}",
+                issue_id, self.owner, self.repo
+            ))?;
+        issue["node_id"]
+            .as_str()
+            .map(|id| id.to_string())
+            .context(format!(
+                "Github didn't return the node ID of issue #{}",
+                issue_id
+            ))
+    }
+
+    /// Look up the project board and its fields.
+    async fn get_project_info(&self, project: &GithubProjectTarget) -> Result<GithubProjectInfo> {
+        let data: serde_json::Value = self
+            .graphql(
+                "query($owner: String!, $number: Int!) {
+                    repositoryOwner(login: $owner) {
+                        ... on ProjectV2Owner {
+                            projectV2(number: $number) {
+                                id
+                                fields(first: 100) {
+                                    nodes {
+                                        ... on ProjectV2FieldCommon { id name dataType }
+                                        ... on ProjectV2SingleSelectField { options { id name } }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }",
+                json!({ "owner": project.owner, "number": project.number }),
+            )
+            .await
+            .context(format!(
+                "Failed to find project {} of {}",
+                project.number, project.owner
+            ))?;
+        let info = &data["repositoryOwner"]["projectV2"];
+        if info.is_null() {
+            anyhow::bail!("Project {} of {} not found", project.number, project.owner);
+        }
+        serde_json::from_value(info.clone()).context(format!(
+            "Failed to parse project {} of {}",
+            project.number, project.owner
+        ))
+    }
+
+    /// Get the value to give a project field in the GraphQL API.
+    fn get_project_field_value(
+        field: &GithubProjectField,
+        value: &str,
+    ) -> Result<serde_json::Value> {
+        match field.data_type.as_str() {
+            "TEXT" => Ok(json!({ "text": value })),
+            "NUMBER" => Ok(json!({
+                "number": value.parse::<f64>().context(format!(
+                    "Project field '{}' needs a number, got '{}'",
+                    field.name, value
+                ))?
+            })),
+            "DATE" => Ok(json!({ "date": value })),
+            "SINGLE_SELECT" => {
+                let option = field
+                    .options
+                    .iter()
+                    .find(|o| o.name == value)
+                    .context(format!(
+                        "Project field '{}' has no option '{}', choose from: {}",
+                        field.name,
+                        value,
+                        field
+                            .options
+                            .iter()
+                            .map(|o| o.name.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ))?;
+                Ok(json!({ "singleSelectOptionId": option.id }))
+            }
+            data_type => anyhow::bail!(
+                "Project field '{}' is a {} field, which can't be set from a test case",
+                field.name,
+                data_type
+            ),
+        }
+    }
+
+    /// Send a request to the Github API and deserialize the response.
+    ///
+    /// Requests that hit a rate limit, fail with a server error or don't get a response are
+    /// retried. How long to wait comes from the `Retry-After` and `X-RateLimit-*` headers when
+    /// Github sends them, and from a bounded exponential backoff otherwise.
+    async fn request<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        route: &str,
+        body: Option<&B>,
+    ) -> Result<T> {
+        self.request_with(&self.octocrab, method, route, body).await
+    }
+
+    /// Send a request with the given client, the way `request` does.
+    async fn request_with<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        octocrab: &Octocrab,
+        method: Method,
+        route: &str,
+        body: Option<&B>,
+    ) -> Result<T> {
+        let method = &method;
+        send_with_retries(
+            "Github",
+            &self.request_slots,
+            method,
+            route,
+            || async move {
+                let request = octocrab
+                    .build_request(
+                        http::Request::builder().method(method.clone()).uri(route),
+                        body,
+                    )
+                    .context(format!("Failed to build the request {} {}", method, route))?;
+                let response = match octocrab.execute(request).await {
+                    std::result::Result::Ok(response) => response,
+                    Err(error) => return Ok(Err(anyhow!(error))),
+                };
+                let status = response.status();
+                let headers = response.headers().clone();
+                let text = octocrab.body_to_string(response).await.context(format!(
+                    "Failed to read the response to {} {}",
+                    method, route
+                ))?;
+                Ok(Ok(TrackerResponse {
+                    status,
+                    headers,
+                    text,
+                }))
+            },
+        )
+        .await
+    }
+
+    async fn get_labels_helper(&self, page: u32) -> Result<Vec<GithubLabel>> {
+        let labels: Vec<GithubApiLabel> = self
+            .request(
+                Method::GET,
+                &self.repo_route(&format!("/labels?per_page=100&page={}", page)),
+                None::<&()>,
+            )
+            .await
+            .context(format!(
+                "Failed to list labels for {}/{}",
+                self.owner, self.repo
+            ))?;
+        Ok(labels
+            .into_iter()
+            .map(|l| GithubLabel {
+                name: l.name,
+                color: l.color,
+                description: l.description,
+            })
+            .collect())
+    }
+
+    async fn get_issue_comments_helper(
+        &self,
+        issue_id: u64,
+        page: u32,
+    ) -> Result<Vec<TrackerComment>> {
+        let comments: Vec<GithubApiComment> = self
+            .request(
+                Method::GET,
+                &self.repo_route(&format!(
+                    "/issues/{}/comments?per_page=100&page={}",
+                    issue_id, page
+                )),
+                None::<&()>,
+            )
+            .await
+            .context(format!(
+                "Failed to list the comments on issue #{} in {}/{}",
+                issue_id, self.owner, self.repo
+            ))?;
+        Ok(comments
+            .into_iter()
+            .map(|c| TrackerComment {
+                author: c.user.map(|u| u.login),
+                body: c.body.unwrap_or_default(),
+                created_at: Some(c.created_at),
+            })
+            .collect())
+    }
+
+    async fn get_issue_events_helper(
+        &self,
+        issue_id: u64,
+        page: u32,
+    ) -> Result<Option<Vec<TrackerIssueEvent>>> {
+        let events: Vec<GithubApiIssueEvent> = self
+            .request(
+                Method::GET,
+                &self.repo_route(&format!(
+                    "/issues/{}/events?per_page=100&page={}",
+                    issue_id, page
+                )),
+                None::<&()>,
+            )
+            .await
+            .context(format!(
+                "Failed to list the events of issue #{} in {}/{}",
+                issue_id, self.owner, self.repo
+            ))?;
+        if events.is_empty() {
+            return Ok(None);
+        }
+        // Only the label and state changes are kept, out of the many kinds of events
+        Ok(Some(
+            events
+                .into_iter()
+                .filter_map(|e| {
+                    let kind = match (e.event.as_str(), e.label) {
+                        ("labeled", Some(label)) => TrackerIssueEventKind::Labeled(label.name),
+                        ("unlabeled", Some(label)) => TrackerIssueEventKind::Unlabeled(label.name),
+                        ("closed", _) => TrackerIssueEventKind::Closed,
+                        ("reopened", _) => TrackerIssueEventKind::Reopened,
+                        _ => return None,
+                    };
+                    Some(TrackerIssueEvent {
+                        kind,
+                        actor: e.actor.map(|a| a.login),
+                        created_at: Some(e.created_at),
+                    })
+                })
+                .collect(),
+        ))
+    }
+
+    async fn get_issues_helper(
+        &self,
+        page: u32,
+        state: Option<TrackerIssueState>,
+    ) -> Result<Vec<TrackerIssue>> {
+        let state = match state {
+            Some(TrackerIssueState::Open) => "open",
+            Some(TrackerIssueState::Closed) => "closed",
+            None => "all",
+        };
+        let issues: Vec<GithubApiIssue> = self
+            .request(
+                Method::GET,
+                &self.repo_route(&format!(
+                    "/issues?state={}&per_page=100&page={}",
+                    state, page
+                )),
+                None::<&()>,
+            )
+            .await
+            .context(format!(
+                "Failed to list issues in {}/{}",
+                self.owner, self.repo
+            ))?;
+        Ok(issues
+            .into_iter()
+            .map(|i| TrackerIssue {
+                number: i.number,
+                title: i.title,
+                body: i.body,
+                labels: i.labels.into_iter().map(|l| l.name).collect(),
+                state: match i.state.as_str() {
+                    "closed" => TrackerIssueState::Closed,
+                    _ => TrackerIssueState::Open,
+                },
+                url: i.html_url,
+                is_pull_request: i.pull_request.is_some(),
+                milestone: i.milestone.map(|m| m.title),
+                closed_at: i.closed_at,
+                assignees: i.assignees.into_iter().map(|u| u.login).collect(),
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl IssueTracker for Github {
+    async fn get_labels(&self) -> Result<Vec<GithubLabel>> {
         let mut page: u32 = 1;
-        let mut labels: Vec<Label> = Vec::new();
+        let mut labels: Vec<GithubLabel> = Vec::new();
         loop {
             let page_labels = self.get_labels_helper(page).await?;
             if page_labels.is_empty() {
@@ -56,23 +758,8 @@ impl Github {
         }
         Ok(labels)
     }
-    async fn get_labels_helper(&self, page: u32) -> Result<Vec<Label>> {
-        let page = self
-            .get_octocrab()?
-            .issues(&self.owner, &self.repo)
-            .list_labels_for_repo()
-            .per_page(100)
-            .page(page)
-            .send()
-            .await
-            .context(format!(
-                "Failed to list labels for {}/{}",
-                self.owner, self.repo
-            ))?;
-        Ok(page.items)
-    }
 
-    pub async fn delete_labels(&self) -> Result<()> {
+    async fn delete_labels(&self) -> Result<()> {
         let labels = self.get_labels().await?;
 
         for label in labels {
@@ -81,47 +768,54 @@ impl Github {
                 percent_encoding::NON_ALPHANUMERIC,
             )
             .to_string();
-            self.get_octocrab()?
-                .issues(&self.owner, &self.repo)
-                .delete_label(&label_name)
-                .await
-                .context(format!(
-                    "Failed to delete label '{}' in {}/{}",
-                    &label_name, self.owner, self.repo
-                ))?;
+            self.request::<_, ()>(
+                Method::DELETE,
+                &self.repo_route(&format!("/labels/{}", label_name)),
+                None::<&()>,
+            )
+            .await
+            .context(format!(
+                "Failed to delete label '{}' in {}/{}",
+                &label.name, self.owner, self.repo
+            ))?;
         }
 
         Ok(())
     }
 
-    pub async fn create_labels(&self, labels: Vec<GithubLabel>) -> Result<()> {
+    async fn create_labels(&self, labels: Vec<GithubLabel>) -> Result<()> {
         let existing_labels = self.get_labels().await?;
 
         for label in &labels {
-            if existing_labels.iter().any(|l| l.name == label.name) {
+            if existing_labels
+                .iter()
+                .any(|l| l.name.to_lowercase() == label.name.to_lowercase())
+            {
                 println!("Skipping '{}' because it already exists", label.name);
                 continue;
             }
-            self.get_octocrab()?
-                .issues(&self.owner, &self.repo)
-                .create_label(
-                    &label.name,
-                    &label.color,
-                    label.description.clone().unwrap_or_default(),
-                )
-                .await
-                .context(format!(
-                    "Failed to create label '{}' in {}/{}",
-                    &label.name, self.owner, self.repo
-                ))?;
+            self.request::<_, serde_json::Value>(
+                Method::POST,
+                &self.repo_route("/labels"),
+                Some(&json!({
+                    "name": label.name,
+                    "color": label.color,
+                    "description": label.description.clone().unwrap_or_default(),
+                })),
+            )
+            .await
+            .context(format!(
+                "Failed to create label '{}' in {}/{}",
+                &label.name, self.owner, self.repo
+            ))?;
             println!("Created label '{}'", label.name);
         }
         Ok(())
     }
 
-    pub async fn get_issues(&self, state: Option<State>) -> Result<Vec<Issue>> {
+    async fn get_issues(&self, state: Option<TrackerIssueState>) -> Result<Vec<TrackerIssue>> {
         let mut page: u32 = 1;
-        let mut issues: Vec<Issue> = Vec::new();
+        let mut issues: Vec<TrackerIssue> = Vec::new();
         loop {
             let page_issues = self.get_issues_helper(page, state).await?;
             if page_issues.is_empty() {
@@ -133,129 +827,699 @@ impl Github {
         Ok(issues)
     }
 
-    async fn get_issues_helper(&self, page: u32, state: Option<State>) -> Result<Vec<Issue>> {
-        let page = self
-            .get_octocrab()?
-            .issues(&self.owner, &self.repo)
-            .list()
-            .per_page(100)
-            .state(state.unwrap_or(State::All))
-            .page(page)
-            .send()
+    /// Create an issue and return its number.
+    async fn create_issue(&self, title: String, body: String, labels: Vec<String>) -> Result<u64> {
+        let issue: GithubApiIssue = self
+            .request(
+                Method::POST,
+                &self.repo_route("/issues"),
+                Some(&json!({ "title": title, "body": body, "labels": labels })),
+            )
             .await
             .context(format!(
-                "Failed to list issues in {}/{}",
-                self.owner, self.repo
+                "Failed to create issue '{}' in {}/{}",
+                title, self.owner, self.repo
             ))?;
-        Ok(page.items)
+        Ok(issue.number)
     }
 
-    #[async_recursion]
-    pub async fn create_issue(
-        &self,
-        title: String,
-        body: String,
-        labels: Vec<String>,
-    ) -> Result<()> {
-        let result = self
-            .get_octocrab()?
-            .issues(&self.owner, &self.repo)
-            .create(&title)
-            .body(&body)
-            .labels(labels.clone())
-            .send()
-            .await;
+    async fn update_issue(&self, issue_id: u64, title: String, body: String) -> Result<()> {
+        self.request::<_, serde_json::Value>(
+            Method::PATCH,
+            &self.repo_route(&format!("/issues/{}", issue_id)),
+            Some(&json!({ "title": title, "body": body })),
+        )
+        .await
+        .context(format!(
+            "Failed to update issue #{} in {}/{}",
+            issue_id, self.owner, self.repo
+        ))?;
+        Ok(())
+    }
 
-        if result.is_ok() {
-            return Ok(());
-        }
+    async fn add_labels_to_issue(&self, issue_id: u64, labels: &[String]) -> Result<()> {
+        self.request::<_, serde_json::Value>(
+            Method::POST,
+            &self.repo_route(&format!("/issues/{}/labels", issue_id)),
+            Some(&json!({ "labels": labels })),
+        )
+        .await
+        .context(format!(
+            "Failed to add labels to issue #{} in {}/{}",
+            issue_id, self.owner, self.repo
+        ))?;
+        Ok(())
+    }
 
-        let error = result.unwrap_err();
-        if let octocrab::Error::GitHub { source, .. } = &error {
-            if source
-                .message
-                .contains("You have exceeded a secondary rate limit")
-            {
-                println!("Secondary rate limit exceeded, waiting 60 seconds and retrying");
-                sleep(Duration::from_secs(60)).await;
-                // Retry the request by calling the function recursively.
-                return self.create_issue(title, body, labels).await;
+    async fn remove_label_from_issue(&self, issue_id: u64, label: &str) -> Result<()> {
+        let label_name =
+            percent_encoding::utf8_percent_encode(label, percent_encoding::NON_ALPHANUMERIC)
+                .to_string();
+        self.request::<_, serde_json::Value>(
+            Method::DELETE,
+            &self.repo_route(&format!("/issues/{}/labels/{}", issue_id, label_name)),
+            None::<&()>,
+        )
+        .await
+        .context(format!(
+            "Failed to remove label '{}' from issue #{} in {}/{}",
+            label, issue_id, self.owner, self.repo
+        ))?;
+        Ok(())
+    }
+
+    async fn comment_on_issue(&self, issue_id: u64, body: &str) -> Result<()> {
+        self.request::<_, serde_json::Value>(
+            Method::POST,
+            &self.repo_route(&format!("/issues/{}/comments", issue_id)),
+            Some(&json!({ "body": body })),
+        )
+        .await
+        .context(format!(
+            "Failed to comment on issue #{} in {}/{}",
+            issue_id, self.owner, self.repo
+        ))?;
+        Ok(())
+    }
+
+    async fn close_issue(&self, issue_id: u64) -> Result<()> {
+        self.request::<_, serde_json::Value>(
+            Method::PATCH,
+            &self.repo_route(&format!("/issues/{}", issue_id)),
+            Some(&json!({ "state": "closed" })),
+        )
+        .await
+        .context(format!(
+            "Failed to close issue #{} in {}/{}",
+            issue_id, self.owner, self.repo
+        ))?;
+        Ok(())
+    }
+
+    async fn reopen_issue(&self, issue_id: u64) -> Result<()> {
+        self.request::<_, serde_json::Value>(
+            Method::PATCH,
+            &self.repo_route(&format!("/issues/{}", issue_id)),
+            Some(&json!({ "state": "open" })),
+        )
+        .await
+        .context(format!(
+            "Failed to reopen issue #{} in {}/{}",
+            issue_id, self.owner, self.repo
+        ))?;
+        Ok(())
+    }
+
+    async fn get_issue_comments(&self, issue_id: u64) -> Result<Vec<TrackerComment>> {
+        let mut page: u32 = 1;
+        let mut comments: Vec<TrackerComment> = Vec::new();
+        loop {
+            let page_comments = self.get_issue_comments_helper(issue_id, page).await?;
+            if page_comments.is_empty() {
+                break;
             }
+            comments.extend(page_comments);
+            page += 1;
         }
-        Err(anyhow!(error))
+        Ok(comments)
     }
 
-    #[async_recursion]
-    pub async fn update_issue(
-        &self,
-        issue_id: u64,
-        title: String,
-        body: String,
-        // labels: Vec<String>,  //TODO(tfoote) Restore label setting
-    ) -> Result<()> {
-        let result = self
-            .get_octocrab()?
-            .issues(&self.owner, &self.repo)
-            .update(issue_id)
-            .title(&title)
-            .body(&body)
-            // .labels(labels.clone())
-            .send()
-            .await;
-
-        if result.is_ok() {
-            return Ok(());
+    async fn get_issue_events(&self, issue_id: u64) -> Result<Vec<TrackerIssueEvent>> {
+        let mut page: u32 = 1;
+        let mut events: Vec<TrackerIssueEvent> = Vec::new();
+        while let Some(page_events) = self.get_issue_events_helper(issue_id, page).await? {
+            events.extend(page_events);
+            page += 1;
         }
+        Ok(events)
+    }
 
-        let error = result.unwrap_err();
-        if let octocrab::Error::GitHub { source, .. } = &error {
-            if source
-                .message
-                .contains("You have exceeded a secondary rate limit")
-            {
-                println!("Secondary rate limit exceeded, waiting 60 seconds and retrying");
-                sleep(Duration::from_secs(60)).await;
-                // Retry the request by calling the function recursively.
-                return self.update_issue(issue_id, title, body).await; //, labels
+    async fn get_milestones(&self) -> Result<Vec<String>> {
+        let mut page: u32 = 1;
+        let mut titles: Vec<String> = Vec::new();
+        loop {
+            let milestones = self.get_milestones_helper(page).await?;
+            if milestones.is_empty() {
+                break;
+            }
+            let mut milestone_numbers = self.milestone_numbers.lock().unwrap();
+            for milestone in milestones {
+                milestone_numbers.insert(milestone.title.clone(), milestone.number);
+                titles.push(milestone.title);
             }
+            page += 1;
         }
-        Err(anyhow!(error))
+        Ok(titles)
     }
 
-    pub async fn close_all_issues(&self) -> Result<()> {
-        let issues = self.get_issues(Some(State::Open)).await.context(format!(
-            "Failed to get issues in {}/{}",
-            self.owner, self.repo
+    async fn create_milestone(&self, title: &str, due_on: Option<&str>) -> Result<()> {
+        let milestone: GithubApiMilestone = self
+            .request(
+                Method::POST,
+                &self.repo_route("/milestones"),
+                Some(&json!({
+                    "title": title,
+                    "due_on": due_on.map(|d| format!("{}T00:00:00Z", d)),
+                })),
+            )
+            .await
+            .context(format!(
+                "Failed to create milestone '{}' in {}/{}",
+                title, self.owner, self.repo
+            ))?;
+        self.milestone_numbers
+            .lock()
+            .unwrap()
+            .insert(milestone.title, milestone.number);
+        Ok(())
+    }
+
+    async fn set_issue_milestone(&self, issue_id: u64, milestone: &str) -> Result<()> {
+        let number = self.get_milestone_number(milestone).await?;
+        self.request::<_, serde_json::Value>(
+            Method::PATCH,
+            &self.repo_route(&format!("/issues/{}", issue_id)),
+            Some(&json!({ "milestone": number })),
+        )
+        .await
+        .context(format!(
+            "Failed to set the milestone of issue #{} in {}/{}",
+            issue_id, self.owner, self.repo
         ))?;
+        Ok(())
+    }
 
-        for issue in issues {
-            self.close_issue(&issue).await?;
-        }
+    async fn assign_issue(&self, issue_id: u64, assignees: &[String]) -> Result<()> {
+        self.request::<_, serde_json::Value>(
+            Method::POST,
+            &self.repo_route(&format!("/issues/{}/assignees", issue_id)),
+            Some(&json!({ "assignees": assignees })),
+        )
+        .await
+        .context(format!(
+            "Failed to assign issue #{} in {}/{}",
+            issue_id, self.owner, self.repo
+        ))?;
         Ok(())
     }
 
-    pub async fn close_issue(&self, issue: &Issue) -> Result<()> {
-        self.get_octocrab()?
-            .issues(&self.owner, &self.repo)
-            .update(issue.number)
-            .state(IssueState::Closed)
-            .send()
+    async fn lock_issue(&self, issue_id: u64) -> Result<()> {
+        self.request::<_, serde_json::Value>(
+            Method::PUT,
+            &self.repo_route(&format!("/issues/{}/lock", issue_id)),
+            None::<&()>,
+        )
+        .await
+        .context(format!(
+            "Failed to lock issue #{} in {}/{}",
+            issue_id, self.owner, self.repo
+        ))?;
+        Ok(())
+    }
+
+    async fn pin_issue(&self, issue_id: u64) -> Result<()> {
+        let issue_node_id = self.get_issue_node_id(issue_id).await?;
+        self.graphql::<serde_json::Value>(
+            "mutation($issue: ID!) { pinIssue(input: { issueId: $issue }) { issue { id } } }",
+            json!({ "issue": issue_node_id }),
+        )
+        .await
+        .context(format!(
+            "Failed to pin issue #{} in {}/{}",
+            issue_id, self.owner, self.repo
+        ))?;
+        Ok(())
+    }
+
+    async fn add_issue_to_project(
+        &self,
+        issue_id: u64,
+        fields: &BTreeMap<String, String>,
+    ) -> Result<()> {
+        let project = self
+            .project
+            .as_ref()
+            .context("No project set to add the issues to")?;
+        let info = project
+            .info
+            .get_or_try_init(|| self.get_project_info(project))
+            .await?;
+
+        let issue_node_id = self.get_issue_node_id(issue_id).await?;
+        let data: serde_json::Value = self
+            .graphql(
+                "mutation($project: ID!, $content: ID!) {
+                    addProjectV2ItemById(input: { projectId: $project, contentId: $content }) {
+                        item { id }
+                    }
+                }",
+                json!({ "project": info.id, "content": issue_node_id }),
+            )
             .await
             .context(format!(
-                "Failed to delete issue #{} {} in {}/{}",
-                issue.number, issue.title, self.owner, self.repo
+                "Failed to add issue #{} to project {} of {}",
+                issue_id, project.number, project.owner
             ))?;
+        let item_id = &data["addProjectV2ItemById"]["item"]["id"];
+
+        for (name, value) in fields {
+            let field = info
+                .fields
+                .nodes
+                .iter()
+                .find(|f| &f.name == name)
+                .context(format!(
+                    "Project {} of {} has no field named '{}'",
+                    project.number, project.owner, name
+                ))?;
+            self.graphql::<serde_json::Value>(
+                "mutation($project: ID!, $item: ID!, $field: ID!, $value: ProjectV2FieldValue!) {
+                    updateProjectV2ItemFieldValue(
+                        input: { projectId: $project, itemId: $item, fieldId: $field, value: $value }
+                    ) {
+                        projectV2Item { id }
+                    }
+                }",
+                json!({
+                    "project": info.id,
+                    "item": item_id,
+                    "field": field.id,
+                    "value": Self::get_project_field_value(field, value)?,
+                }),
+            )
+            .await
+            .context(format!(
+                "Failed to set project field '{}' of issue #{}",
+                name, issue_id
+            ))?;
+        }
         Ok(())
     }
 
-    pub fn prepend_github_info(&self, content: &String) -> String {
-        let header = format!(
-            "# Github Target\n\nrepository: [{}/{}](https://github.com/{}/{})",
-            self.owner, self.repo, self.owner, self.repo
+    fn get_label_link(&self, labels: &[String]) -> String {
+        let mut url = format!(
+            "{}/{}/{}/issues?q=is:issue+is:open",
+            self.web_url, self.owner, self.repo
         );
-        if header.is_empty() {
-            return content.clone();
+        for label in labels {
+            url += &format!("+label:%22{}%22", label.replace(" ", "+"));
         }
+        url
+    }
+
+    fn prepend_tracker_info(&self, content: &str) -> String {
+        let header = format!(
+            "# Github Target\n\nrepository: [{}/{}]({}/{}/{})",
+            self.owner, self.repo, self.web_url, self.owner, self.repo
+        );
         format!("{}\n\n{}", header, content)
     }
 }
+
+/// Get the address of a Github Enterprise Server from the address of its REST API.
+fn get_enterprise_host(api_url: &str) -> Option<&str> {
+    api_url.trim_end_matches('/').strip_suffix("/api/v3")
+}
+
+fn get_enterprise_graphql_octocrab(host: &str, token: &str) -> Result<Octocrab> {
+    Octocrab::builder()
+        .base_uri(host)
+        .context(format!("Invalid Github Enterprise Server URL: {}", host))?
+        .add_retry_config(RetryConfig::None)
+        .personal_token(token.to_string())
+        .build()
+        .context("Failed to create octocrab")
+}
+
+/// Run a credential helper command and get the token it prints.
+fn get_token_from_command(command: &str) -> Result<String> {
+    let output = std::process::Command::new("sh")
+        .args(["-c", command])
+        .output()
+        .context(format!(
+            "Failed to run the Github token command: {}",
+            command
+        ))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "The Github token command failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let token = String::from_utf8(output.stdout)
+        .context("The Github token command printed something that isn't text")?;
+    let token = token.trim();
+    if token.is_empty() {
+        anyhow::bail!("The Github token command didn't print a token: {}", command);
+    }
+    Ok(token.to_string())
+}
+
+#[cfg(test)]
+mod test_github {
+    use super::*;
+    use crate::fake_github::FakeGithub;
+
+    async fn start() -> (FakeGithub, Github) {
+        let fake_github = FakeGithub::start("owner", "repo").await.unwrap();
+        let github = Github::new_with_token(fake_github.url(), "owner", "repo", "token").unwrap();
+        (fake_github, github)
+    }
+
+    #[tokio::test]
+    async fn creates_only_missing_labels() {
+        let (fake_github, github) = start().await;
+        fake_github.add_label("existing");
+
+        github
+            .create_labels(vec![
+                GithubLabel {
+                    name: "existing".to_string(),
+                    color: "000000".to_string(),
+                    description: None,
+                },
+                GithubLabel {
+                    name: "new".to_string(),
+                    color: "c5def5".to_string(),
+                    description: Some("A new label".to_string()),
+                },
+            ])
+            .await
+            .unwrap();
+
+        let labels = fake_github.labels();
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels[0].color, "ffffff");
+        assert_eq!(labels[1].name, "new");
+        assert_eq!(labels[1].color, "c5def5");
+        assert!(fake_github
+            .requests()
+            .iter()
+            .all(|r| r.get_header("authorization") == Some("Bearer token")));
+    }
+
+    #[tokio::test]
+    async fn pages_through_issues() {
+        let (fake_github, github) = start().await;
+        for i in 0..150 {
+            fake_github.add_issue(&format!("Issue {}", i), "", &["label"]);
+        }
+        github.close_issue(1).await.unwrap();
+
+        assert_eq!(github.get_issues(None).await.unwrap().len(), 150);
+        let open_issues = github
+            .get_issues(Some(TrackerIssueState::Open))
+            .await
+            .unwrap();
+        assert_eq!(open_issues.len(), 149);
+        assert_eq!(open_issues[0].number, 2);
+        assert_eq!(open_issues[0].labels, vec!["label"]);
+    }
+
+    #[tokio::test]
+    async fn edits_issues() {
+        let (fake_github, github) = start().await;
+        let number = github
+            .create_issue(
+                "Title".to_string(),
+                "Body".to_string(),
+                vec!["a".to_string()],
+            )
+            .await
+            .unwrap();
+        github
+            .update_issue(number, "New title".to_string(), "New body".to_string())
+            .await
+            .unwrap();
+        github
+            .add_labels_to_issue(number, &["b".to_string(), "c d".to_string()])
+            .await
+            .unwrap();
+        github.remove_label_from_issue(number, "c d").await.unwrap();
+        github.comment_on_issue(number, "Comment").await.unwrap();
+        github.pin_issue(number).await.unwrap();
+        github.lock_issue(number).await.unwrap();
+
+        let issue = &fake_github.issues()[0];
+        assert_eq!(issue.title, "New title");
+        assert_eq!(issue.body.as_deref(), Some("New body"));
+        assert_eq!(issue.labels, vec!["a", "b"]);
+        assert_eq!(fake_github.comments(number), vec!["Comment"]);
+        assert_eq!(fake_github.pinned_issues(), vec![number]);
+        assert_eq!(fake_github.locked_issues(), vec![number]);
+    }
+
+    #[tokio::test]
+    async fn reads_the_history_of_issues() {
+        let (fake_github, github) = start().await;
+        let number = fake_github.add_issue("Issue", "", &[]);
+        fake_github.add_comment(number, "tester", "Result: pass");
+        fake_github.label_issue(number, "tester", "confirmed: works as expected");
+        github.comment_on_issue(number, "Thanks").await.unwrap();
+        github.close_issue(number).await.unwrap();
+        github.reopen_issue(number).await.unwrap();
+
+        let comments = github.get_issue_comments(number).await.unwrap();
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].author.as_deref(), Some("tester"));
+        assert_eq!(comments[0].body, "Result: pass");
+        assert_eq!(comments[1].author.as_deref(), Some("fake-user"));
+
+        let events = github.get_issue_events(number).await.unwrap();
+        let events: Vec<(TrackerIssueEventKind, Option<String>)> =
+            events.into_iter().map(|e| (e.kind, e.actor)).collect();
+        assert_eq!(
+            events,
+            vec![
+                (
+                    TrackerIssueEventKind::Labeled("confirmed: works as expected".to_string()),
+                    Some("tester".to_string())
+                ),
+                (TrackerIssueEventKind::Closed, Some("fake-user".to_string())),
+                (
+                    TrackerIssueEventKind::Reopened,
+                    Some("fake-user".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn retries_when_rate_limited() {
+        let (fake_github, github) = start().await;
+        fake_github.add_issue("Issue", "", &[]);
+        fake_github.rate_limit_next_requests(2);
+
+        assert_eq!(github.get_issues(None).await.unwrap().len(), 1);
+        // Two rate limited requests, then the first and the empty second page
+        assert_eq!(fake_github.requests().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_creating_an_issue_after_a_server_error() {
+        let (fake_github, github) = start().await;
+        fake_github.fail_next_requests(1);
+
+        // The issue was created even though the request failed, so it isn't created again
+        assert!(github
+            .create_issue("Issue".to_string(), "".to_string(), vec![])
+            .await
+            .is_err());
+        assert_eq!(fake_github.issues().len(), 1);
+        assert_eq!(fake_github.requests().len(), 1);
+
+        // Reading is safe to retry
+        fake_github.fail_next_requests(1);
+        assert_eq!(github.get_issues(None).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn checks_the_authenticated_user() {
+        let (fake_github, github) = start().await;
+        let auth_check = github.check_auth().await.unwrap();
+        assert_eq!(auth_check.identity, "fake-user");
+        assert!(auth_check.can_write_issues);
+
+        fake_github.set_read_only(true);
+        assert!(!github.check_auth().await.unwrap().can_write_issues);
+    }
+
+    #[tokio::test]
+    async fn reads_the_token_from_a_file_or_command() {
+        let fake_github = FakeGithub::start("owner", "repo").await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let token_file = dir.path().join("github-token.txt");
+        std::fs::write(&token_file, "file-token\n").unwrap();
+
+        for (auth, token) in [
+            (
+                GithubAuth::TokenFile {
+                    path: token_file.clone(),
+                },
+                "file-token",
+            ),
+            (
+                GithubAuth::Command {
+                    command: "echo command-token".to_string(),
+                },
+                "command-token",
+            ),
+        ] {
+            let github = Github::new_with_auth(fake_github.url(), "owner", "repo", &auth)
+                .await
+                .unwrap();
+            github.get_labels().await.unwrap();
+            let requests = fake_github.requests();
+            assert_eq!(
+                requests.last().unwrap().get_header("authorization"),
+                Some(format!("Bearer {}", token).as_str())
+            );
+        }
+
+        let error = get_token_from_command("exit 1").unwrap_err();
+        assert!(format!("{:#}", error).contains("failed"));
+    }
+
+    #[tokio::test]
+    async fn authenticates_as_a_github_app() {
+        let fake_github = FakeGithub::start("owner", "repo").await.unwrap();
+        let github = Github::new_with_auth(
+            fake_github.url(),
+            "owner",
+            "repo",
+            &GithubAuth::App {
+                app_id: 7,
+                private_key_file: PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                    .join("src/test_data/github-app-test-key.pem"),
+            },
+        )
+        .await
+        .unwrap();
+
+        let auth_check = github.check_auth().await.unwrap();
+        assert_eq!(auth_check.identity, "Github App 7 (installation 1)");
+        assert!(auth_check.can_write_issues);
+        let requests = fake_github.requests();
+        assert_eq!(
+            requests.last().unwrap().get_header("authorization"),
+            Some("Bearer installation-token")
+        );
+    }
+
+    #[tokio::test]
+    async fn adds_issues_to_a_project() {
+        let (fake_github, github) = start().await;
+        let github = github.with_project("owner", 1);
+        fake_github.add_project_field("Builder", &[]);
+        fake_github.add_project_field("OS", &["Ubuntu", "Windows"]);
+        let issue_id = fake_github.add_issue("Issue", "", &[]);
+
+        let fields = BTreeMap::from([
+            ("Builder".to_string(), "Smoke tests".to_string()),
+            ("OS".to_string(), "Windows".to_string()),
+        ]);
+        github
+            .add_issue_to_project(issue_id, &fields)
+            .await
+            .unwrap();
+        let items = fake_github.project_items();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].issue_number, issue_id);
+        assert_eq!(items[0].fields, fields);
+
+        // Values that the project has no place for are reported
+        let fields = BTreeMap::from([("OS".to_string(), "MacOS".to_string())]);
+        let error = github
+            .add_issue_to_project(issue_id, &fields)
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", error).contains("has no option 'MacOS'"));
+        let fields = BTreeMap::from([("RMW".to_string(), "FastRTPS".to_string())]);
+        let error = github
+            .add_issue_to_project(issue_id, &fields)
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", error).contains("has no field named 'RMW'"));
+
+        let github = Github::new_with_token(fake_github.url(), "owner", "repo", "token")
+            .unwrap()
+            .with_project("owner", 2);
+        let error = github
+            .add_issue_to_project(issue_id, &BTreeMap::new())
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", error).contains("Failed to find project 2 of owner"));
+    }
+
+    #[tokio::test]
+    async fn sends_graphql_requests_outside_of_the_enterprise_api_path() {
+        let fake_github = FakeGithub::start("owner", "repo").await.unwrap();
+        assert_eq!(get_enterprise_host(GITHUB_API_URL), None);
+        assert_eq!(
+            get_enterprise_host(&format!("{}/api/v3/", fake_github.url())),
+            Some(fake_github.url())
+        );
+
+        let github = Github::new_with_token(
+            &format!("{}/api/v3", fake_github.url()),
+            "owner",
+            "repo",
+            "token",
+        )
+        .unwrap();
+        let _ = github
+            .graphql::<serde_json::Value>("query { viewer { login } }", json!({}))
+            .await;
+        let request = fake_github.requests().pop().unwrap();
+        assert_eq!(request.path, "/api/graphql");
+        assert_eq!(request.get_header("authorization"), Some("Bearer token"));
+    }
+
+    #[tokio::test]
+    async fn assigns_issues_to_milestones() {
+        let (fake_github, github) = start().await;
+        let issue_id = fake_github.add_issue("Issue", "", &[]);
+
+        github
+            .create_milestone("1.0.0", Some("2026-11-30"))
+            .await
+            .unwrap();
+        assert_eq!(github.get_milestones().await.unwrap(), vec!["1.0.0"]);
+        assert_eq!(
+            fake_github.milestones()[0].due_on.as_deref(),
+            Some("2026-11-30T00:00:00Z")
+        );
+
+        // A new connection finds the milestone number from its title
+        let github = Github::new_with_token(fake_github.url(), "owner", "repo", "token").unwrap();
+        github.set_issue_milestone(issue_id, "1.0.0").await.unwrap();
+        let issues = github.get_issues(None).await.unwrap();
+        assert_eq!(issues[0].milestone.as_deref(), Some("1.0.0"));
+
+        let error = github
+            .set_issue_milestone(issue_id, "2.0.0")
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", error).contains("Milestone '2.0.0' not found"));
+    }
+
+    #[tokio::test]
+    async fn fails_without_a_token() {
+        let error = Github::new_with_env_token(GITHUB_API_URL, "owner", "repo", None)
+            .err()
+            .unwrap();
+        assert!(format!("{:#}", error).contains("GITHUB_TOKEN not set"));
+    }
+
+    #[tokio::test]
+    async fn makes_links_without_a_token() {
+        let github = Github::new_offline(GITHUB_API_URL, "owner", "repo")
+            .unwrap()
+            .with_web_url("https://github.example.com");
+        assert_eq!(
+            github.get_label_link(&["label 1".to_string()]),
+            "https://github.example.com/owner/repo/issues?q=is:issue+is:open+label:%22label+1%22"
+        );
+    }
+}
@@ -0,0 +1,712 @@
+use crate::issue_tracker::IssueTracker;
+use crate::request_retry::{send_with_retries, TrackerResponse};
+use crate::types::{
+    GithubLabel, TrackerComment, TrackerIssue, TrackerIssueEvent, TrackerIssueEventKind,
+    TrackerIssueState,
+};
+use anyhow::Ok;
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use dotenv::dotenv;
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::Semaphore;
+
+/// The address of Gitlab when no self-hosted instance is configured.
+pub const GITLAB_URL: &str = "https://gitlab.com";
+
+pub struct Gitlab {
+    client: reqwest::Client,
+    token: Option<String>,
+    /// The address of the Gitlab instance, such as `https://gitlab.com`.
+    url: String,
+    owner: String,
+    repo: String,
+    request_slots: Semaphore,
+}
+
+#[derive(Deserialize)]
+struct GitlabLabel {
+    name: String,
+    color: String,
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GitlabIssue {
+    iid: u64,
+    title: String,
+    description: Option<String>,
+    labels: Vec<String>,
+    state: String,
+    web_url: String,
+    milestone: Option<GitlabMilestone>,
+    closed_at: Option<String>,
+    #[serde(default)]
+    assignees: Vec<GitlabUser>,
+}
+
+#[derive(Deserialize)]
+struct GitlabMilestone {
+    title: String,
+}
+
+#[derive(Deserialize)]
+struct GitlabUser {
+    username: String,
+}
+
+#[derive(Deserialize)]
+struct GitlabNote {
+    author: Option<GitlabUser>,
+    body: String,
+    created_at: String,
+    /// Whether Gitlab wrote the note to record a change, rather than a user.
+    system: bool,
+}
+
+#[derive(Deserialize)]
+struct GitlabLabelEvent {
+    user: Option<GitlabUser>,
+    label: Option<GitlabEventLabel>,
+    action: String,
+    created_at: String,
+}
+
+#[derive(Deserialize)]
+struct GitlabEventLabel {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct GitlabStateEvent {
+    user: Option<GitlabUser>,
+    state: String,
+    created_at: String,
+}
+
+impl Gitlab {
+    pub fn new(url: &str, owner: &str, repo: &str) -> Result<Self> {
+        dotenv().ok();
+
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .user_agent("yatm")
+                .build()
+                .context("Failed to create the Gitlab client")?,
+            token: std::env::var("GITLAB_TOKEN").ok(),
+            url: url.trim_end_matches('/').to_string(),
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            request_slots: Semaphore::new(1),
+        })
+    }
+
+    /// Set how many requests can be sent to Gitlab at the same time, one by default.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.request_slots = Semaphore::new(concurrency.max(1));
+        self
+    }
+
+    fn project_url(&self, route: &str) -> String {
+        let project = format!("{}/{}", self.owner, self.repo);
+        format!(
+            "{}/api/v4/projects/{}{}",
+            self.url,
+            percent_encoding::utf8_percent_encode(&project, percent_encoding::NON_ALPHANUMERIC),
+            route
+        )
+    }
+
+    /// Send a request to the Gitlab API and deserialize the response.
+    ///
+    /// Requests are retried like the Github ones, using the `Retry-After` and `RateLimit-*`
+    /// headers when Gitlab sends them.
+    async fn request<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&B>,
+    ) -> Result<T> {
+        let token = self.token.as_ref().context("GITLAB_TOKEN not set")?;
+        let method = &method;
+        send_with_retries("Gitlab", &self.request_slots, method, url, || async move {
+            let mut request = self
+                .client
+                .request(method.clone(), url)
+                .header("PRIVATE-TOKEN", token);
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+            let response = match request.send().await {
+                std::result::Result::Ok(response) => response,
+                Err(error) => return Ok(Err(anyhow!(error))),
+            };
+            let status = response.status();
+            let headers = response.headers().clone();
+            let text = response
+                .text()
+                .await
+                .context(format!("Failed to read the response to {} {}", method, url))?;
+            Ok(Ok(TrackerResponse {
+                status,
+                headers,
+                text,
+            }))
+        })
+        .await
+    }
+
+    /// Get every page of a list of an issue's activity.
+    async fn get_issue_pages<T: DeserializeOwned>(
+        &self,
+        issue_id: u64,
+        list: &str,
+    ) -> Result<Vec<T>> {
+        let mut page: u32 = 1;
+        let mut items: Vec<T> = Vec::new();
+        loop {
+            let page_items: Vec<T> = self
+                .request(
+                    Method::GET,
+                    &self.project_url(&format!(
+                        "/issues/{}/{}?per_page=100&page={}",
+                        issue_id, list, page
+                    )),
+                    None::<&()>,
+                )
+                .await
+                .context(format!(
+                    "Failed to list the {} of issue #{} in {}/{}",
+                    list, issue_id, self.owner, self.repo
+                ))?;
+            if page_items.is_empty() {
+                break;
+            }
+            items.extend(page_items);
+            page += 1;
+        }
+        Ok(items)
+    }
+
+    async fn get_labels_helper(&self, page: u32) -> Result<Vec<GithubLabel>> {
+        let labels: Vec<GitlabLabel> = self
+            .request(
+                Method::GET,
+                &self.project_url(&format!("/labels?per_page=100&page={}", page)),
+                None::<&()>,
+            )
+            .await
+            .context(format!(
+                "Failed to list labels for {}/{}",
+                self.owner, self.repo
+            ))?;
+        Ok(labels
+            .into_iter()
+            .map(|l| GithubLabel {
+                name: l.name,
+                color: l.color.trim_start_matches('#').to_string(),
+                description: l.description,
+            })
+            .collect())
+    }
+
+    async fn get_issues_helper(
+        &self,
+        page: u32,
+        state: Option<TrackerIssueState>,
+    ) -> Result<Vec<TrackerIssue>> {
+        let state = match state {
+            Some(TrackerIssueState::Open) => "opened",
+            Some(TrackerIssueState::Closed) => "closed",
+            None => "all",
+        };
+        let issues: Vec<GitlabIssue> = self
+            .request(
+                Method::GET,
+                &self.project_url(&format!(
+                    "/issues?state={}&per_page=100&page={}",
+                    state, page
+                )),
+                None::<&()>,
+            )
+            .await
+            .context(format!(
+                "Failed to list issues in {}/{}",
+                self.owner, self.repo
+            ))?;
+        Ok(issues
+            .into_iter()
+            .map(|i| TrackerIssue {
+                number: i.iid,
+                title: i.title,
+                body: i.description,
+                labels: i.labels,
+                state: match i.state.as_str() {
+                    "closed" => TrackerIssueState::Closed,
+                    _ => TrackerIssueState::Open,
+                },
+                url: i.web_url,
+                is_pull_request: false,
+                milestone: i.milestone.map(|m| m.title),
+                closed_at: i.closed_at,
+                assignees: i.assignees.into_iter().map(|u| u.username).collect(),
+            })
+            .collect())
+    }
+
+    /// Change an issue with the fields Gitlab accepts when editing issues.
+    async fn edit_issue(&self, issue_id: u64, fields: serde_json::Value) -> Result<()> {
+        self.request::<_, serde_json::Value>(
+            Method::PUT,
+            &self.project_url(&format!("/issues/{}", issue_id)),
+            Some(&fields),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl IssueTracker for Gitlab {
+    async fn get_labels(&self) -> Result<Vec<GithubLabel>> {
+        let mut page: u32 = 1;
+        let mut labels: Vec<GithubLabel> = Vec::new();
+        loop {
+            let page_labels = self.get_labels_helper(page).await?;
+            if page_labels.is_empty() {
+                break;
+            }
+            labels.extend(page_labels);
+            page += 1;
+        }
+        Ok(labels)
+    }
+
+    async fn create_labels(&self, labels: Vec<GithubLabel>) -> Result<()> {
+        let existing_labels = self.get_labels().await?;
+
+        for label in &labels {
+            if existing_labels.iter().any(|l| l.name == label.name) {
+                println!("Skipping '{}' because it already exists", label.name);
+                continue;
+            }
+            self.request::<_, serde_json::Value>(
+                Method::POST,
+                &self.project_url("/labels"),
+                Some(&json!({
+                    "name": label.name,
+                    "color": format!("#{}", label.color),
+                    "description": label.description.clone().unwrap_or_default(),
+                })),
+            )
+            .await
+            .context(format!(
+                "Failed to create label '{}' in {}/{}",
+                &label.name, self.owner, self.repo
+            ))?;
+            println!("Created label '{}'", label.name);
+        }
+        Ok(())
+    }
+
+    async fn delete_labels(&self) -> Result<()> {
+        let labels = self.get_labels().await?;
+
+        for label in labels {
+            let label_name = percent_encoding::utf8_percent_encode(
+                &label.name,
+                percent_encoding::NON_ALPHANUMERIC,
+            )
+            .to_string();
+            self.request::<_, serde_json::Value>(
+                Method::DELETE,
+                &self.project_url(&format!("/labels/{}", label_name)),
+                None::<&()>,
+            )
+            .await
+            .context(format!(
+                "Failed to delete label '{}' in {}/{}",
+                &label.name, self.owner, self.repo
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    async fn get_issues(&self, state: Option<TrackerIssueState>) -> Result<Vec<TrackerIssue>> {
+        let mut page: u32 = 1;
+        let mut issues: Vec<TrackerIssue> = Vec::new();
+        loop {
+            let page_issues = self.get_issues_helper(page, state).await?;
+            if page_issues.is_empty() {
+                break;
+            }
+            issues.extend(page_issues);
+            page += 1;
+        }
+        Ok(issues)
+    }
+
+    async fn create_issue(&self, title: String, body: String, labels: Vec<String>) -> Result<u64> {
+        let issue: GitlabIssue = self
+            .request(
+                Method::POST,
+                &self.project_url("/issues"),
+                Some(&json!({
+                    "title": title,
+                    "description": body,
+                    "labels": labels.join(","),
+                })),
+            )
+            .await
+            .context(format!(
+                "Failed to create issue '{}' in {}/{}",
+                title, self.owner, self.repo
+            ))?;
+        Ok(issue.iid)
+    }
+
+    async fn update_issue(&self, issue_id: u64, title: String, body: String) -> Result<()> {
+        self.edit_issue(issue_id, json!({ "title": title, "description": body }))
+            .await
+            .context(format!(
+                "Failed to update issue #{} in {}/{}",
+                issue_id, self.owner, self.repo
+            ))
+    }
+
+    async fn add_labels_to_issue(&self, issue_id: u64, labels: &[String]) -> Result<()> {
+        self.edit_issue(issue_id, json!({ "add_labels": labels.join(",") }))
+            .await
+            .context(format!(
+                "Failed to add labels to issue #{} in {}/{}",
+                issue_id, self.owner, self.repo
+            ))
+    }
+
+    async fn remove_label_from_issue(&self, issue_id: u64, label: &str) -> Result<()> {
+        self.edit_issue(issue_id, json!({ "remove_labels": label }))
+            .await
+            .context(format!(
+                "Failed to remove label '{}' from issue #{} in {}/{}",
+                label, issue_id, self.owner, self.repo
+            ))
+    }
+
+    async fn comment_on_issue(&self, issue_id: u64, body: &str) -> Result<()> {
+        self.request::<_, serde_json::Value>(
+            Method::POST,
+            &self.project_url(&format!("/issues/{}/notes", issue_id)),
+            Some(&json!({ "body": body })),
+        )
+        .await
+        .context(format!(
+            "Failed to comment on issue #{} in {}/{}",
+            issue_id, self.owner, self.repo
+        ))?;
+        Ok(())
+    }
+
+    async fn close_issue(&self, issue_id: u64) -> Result<()> {
+        self.edit_issue(issue_id, json!({ "state_event": "close" }))
+            .await
+            .context(format!(
+                "Failed to close issue #{} in {}/{}",
+                issue_id, self.owner, self.repo
+            ))
+    }
+
+    async fn reopen_issue(&self, issue_id: u64) -> Result<()> {
+        self.edit_issue(issue_id, json!({ "state_event": "reopen" }))
+            .await
+            .context(format!(
+                "Failed to reopen issue #{} in {}/{}",
+                issue_id, self.owner, self.repo
+            ))
+    }
+
+    async fn lock_issue(&self, issue_id: u64) -> Result<()> {
+        self.edit_issue(issue_id, json!({ "discussion_locked": true }))
+            .await
+            .context(format!(
+                "Failed to lock issue #{} in {}/{}",
+                issue_id, self.owner, self.repo
+            ))
+    }
+
+    async fn get_issue_comments(&self, issue_id: u64) -> Result<Vec<TrackerComment>> {
+        let mut notes: Vec<GitlabNote> = self.get_issue_pages(issue_id, "notes").await?;
+        notes.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        Ok(notes
+            .into_iter()
+            .filter(|n| !n.system)
+            .map(|n| TrackerComment {
+                author: n.author.map(|a| a.username),
+                body: n.body,
+                created_at: Some(n.created_at),
+            })
+            .collect())
+    }
+
+    async fn get_issue_events(&self, issue_id: u64) -> Result<Vec<TrackerIssueEvent>> {
+        let label_events: Vec<GitlabLabelEvent> = self
+            .get_issue_pages(issue_id, "resource_label_events")
+            .await?;
+        let state_events: Vec<GitlabStateEvent> = self
+            .get_issue_pages(issue_id, "resource_state_events")
+            .await?;
+        let label_events = label_events.into_iter().filter_map(|e| {
+            let label = e.label?.name;
+            let kind = match e.action.as_str() {
+                "add" => TrackerIssueEventKind::Labeled(label),
+                "remove" => TrackerIssueEventKind::Unlabeled(label),
+                _ => return None,
+            };
+            Some(TrackerIssueEvent {
+                kind,
+                actor: e.user.map(|u| u.username),
+                created_at: Some(e.created_at),
+            })
+        });
+        let state_events = state_events.into_iter().filter_map(|e| {
+            let kind = match e.state.as_str() {
+                "closed" => TrackerIssueEventKind::Closed,
+                "reopened" => TrackerIssueEventKind::Reopened,
+                _ => return None,
+            };
+            Some(TrackerIssueEvent {
+                kind,
+                actor: e.user.map(|u| u.username),
+                created_at: Some(e.created_at),
+            })
+        });
+        let mut events: Vec<TrackerIssueEvent> = label_events.chain(state_events).collect();
+        events.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        Ok(events)
+    }
+
+    fn get_label_link(&self, labels: &[String]) -> String {
+        let mut url = format!(
+            "{}/{}/{}/-/issues?state=opened",
+            self.url, self.owner, self.repo
+        );
+        for label in labels {
+            url += &format!(
+                "&label_name[]={}",
+                percent_encoding::utf8_percent_encode(label, percent_encoding::NON_ALPHANUMERIC)
+            );
+        }
+        url
+    }
+
+    fn prepend_tracker_info(&self, content: &str) -> String {
+        let header = format!(
+            "# Gitlab Target\n\nrepository: [{}/{}]({}/{}/{})",
+            self.owner, self.repo, self.url, self.owner, self.repo
+        );
+        format!("{}\n\n{}", header, content)
+    }
+}
+
+#[cfg(test)]
+mod test_gitlab {
+    use super::*;
+    use crate::mock_server::{MockResponse, MockServer};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn make_gitlab(url: &str) -> Gitlab {
+        let mut gitlab = Gitlab::new(url, "owner", "repo").unwrap();
+        gitlab.token = Some("token".to_string());
+        gitlab
+    }
+
+    #[tokio::test]
+    async fn pages_through_issues() {
+        let server = MockServer::start(|request| {
+            let page = request.get_query("page").unwrap_or("1");
+            let issues = match page {
+                "1" => json!([{
+                    "iid": 3,
+                    "title": "Test",
+                    "description": "Body",
+                    "labels": ["version: 1.0.0"],
+                    "state": "closed",
+                    "web_url": "https://gitlab.example.com/owner/repo/-/issues/3",
+                }]),
+                _ => json!([]),
+            };
+            MockResponse::json(200, &issues)
+        })
+        .await
+        .unwrap();
+
+        let issues = make_gitlab(&server.url).get_issues(None).await.unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].number, 3);
+        assert_eq!(issues[0].labels, vec!["version: 1.0.0"]);
+        assert_eq!(issues[0].state, TrackerIssueState::Closed);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].route(), "/api/v4/projects/owner%2Frepo/issues");
+        assert_eq!(requests[0].get_query("state"), Some("all"));
+        assert_eq!(requests[0].get_header("private-token"), Some("token"));
+    }
+
+    #[tokio::test]
+    async fn reads_the_history_of_issues() {
+        let server = MockServer::start(|request| {
+            if request.get_query("page") != Some("1") {
+                return MockResponse::json(200, &json!([]));
+            }
+            let items = match request.route().rsplit('/').next().unwrap_or_default() {
+                "notes" => json!([
+                    { "author": { "username": "gitlab" }, "body": "added ~1 label", "created_at": "2024-01-01T00:00:01Z", "system": true },
+                    { "author": { "username": "tester" }, "body": "Result: fail", "created_at": "2024-01-01T00:00:00Z", "system": false },
+                ]),
+                "resource_label_events" => json!([
+                    { "user": { "username": "tester" }, "label": { "name": "confirmed: bug" }, "action": "add", "created_at": "2024-01-01T00:00:01Z" },
+                ]),
+                _ => json!([
+                    { "user": { "username": "lead" }, "state": "closed", "created_at": "2024-01-01T00:00:02Z" },
+                ]),
+            };
+            MockResponse::json(200, &items)
+        })
+        .await
+        .unwrap();
+        let gitlab = make_gitlab(&server.url);
+
+        let comments = gitlab.get_issue_comments(3).await.unwrap();
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].author.as_deref(), Some("tester"));
+        assert_eq!(comments[0].body, "Result: fail");
+
+        let events = gitlab.get_issue_events(3).await.unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[0].kind,
+            TrackerIssueEventKind::Labeled("confirmed: bug".to_string())
+        );
+        assert_eq!(events[1].kind, TrackerIssueEventKind::Closed);
+        assert_eq!(events[1].actor.as_deref(), Some("lead"));
+        assert!(server
+            .requests()
+            .iter()
+            .any(|r| r.route() == "/api/v4/projects/owner%2Frepo/issues/3/resource_state_events"));
+    }
+
+    #[tokio::test]
+    async fn creates_missing_labels_with_colors() {
+        let server = MockServer::start(|request| match request.method.as_str() {
+            "GET" if request.get_query("page") == Some("1") => MockResponse::json(
+                200,
+                &json!([{ "name": "existing", "color": "#ffffff", "description": null }]),
+            ),
+            "GET" => MockResponse::json(200, &json!([])),
+            _ => MockResponse::json(201, &json!({})),
+        })
+        .await
+        .unwrap();
+
+        let gitlab = make_gitlab(&server.url);
+        gitlab
+            .create_labels(vec![
+                GithubLabel {
+                    name: "existing".to_string(),
+                    color: "ffffff".to_string(),
+                    description: None,
+                },
+                GithubLabel {
+                    name: "new".to_string(),
+                    color: "c5def5".to_string(),
+                    description: None,
+                },
+            ])
+            .await
+            .unwrap();
+
+        let created: Vec<serde_json::Value> = server
+            .requests()
+            .iter()
+            .filter(|r| r.method == "POST")
+            .map(|r| serde_json::from_str(&r.body).unwrap())
+            .collect();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0]["name"], "new");
+        assert_eq!(created[0]["color"], "#c5def5");
+    }
+
+    #[tokio::test]
+    async fn creates_updates_and_closes_issues() {
+        let server = MockServer::start(|request| match request.method.as_str() {
+            "POST" => MockResponse::json(
+                201,
+                &json!({
+                    "iid": 7,
+                    "title": "Test",
+                    "description": "Body",
+                    "labels": [],
+                    "state": "opened",
+                    "web_url": "",
+                }),
+            ),
+            _ => MockResponse::json(200, &json!({})),
+        })
+        .await
+        .unwrap();
+
+        let gitlab = make_gitlab(&server.url);
+        let number = gitlab
+            .create_issue(
+                "Test".to_string(),
+                "Body".to_string(),
+                vec!["a".to_string(), "b".to_string()],
+            )
+            .await
+            .unwrap();
+        assert_eq!(number, 7);
+        gitlab
+            .update_issue(7, "New".to_string(), "New body".to_string())
+            .await
+            .unwrap();
+        gitlab.close_issue(7).await.unwrap();
+        gitlab.reopen_issue(7).await.unwrap();
+
+        let requests = server.requests();
+        let bodies: Vec<serde_json::Value> = requests
+            .iter()
+            .map(|r| serde_json::from_str(&r.body).unwrap())
+            .collect();
+        assert_eq!(bodies[0]["labels"], "a,b");
+        assert_eq!(requests[1].method, "PUT");
+        assert_eq!(
+            requests[1].route(),
+            "/api/v4/projects/owner%2Frepo/issues/7"
+        );
+        assert_eq!(bodies[1]["description"], "New body");
+        assert_eq!(bodies[2]["state_event"], "close");
+        assert_eq!(bodies[3]["state_event"], "reopen");
+    }
+
+    #[tokio::test]
+    async fn retries_when_rate_limited() {
+        let attempts = AtomicUsize::new(0);
+        let server = MockServer::start(move |_| {
+            if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                MockResponse::json(429, &json!({})).with_header("retry-after", "0")
+            } else {
+                MockResponse::json(200, &json!([]))
+            }
+        })
+        .await
+        .unwrap();
+
+        let labels = make_gitlab(&server.url).get_labels().await.unwrap();
+        assert!(labels.is_empty());
+        assert_eq!(server.requests().len(), 2);
+    }
+}
@@ -0,0 +1,96 @@
+use crate::types::{
+    GithubLabel, TrackerComment, TrackerIssue, TrackerIssueEvent, TrackerIssueState,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+
+/// The operations yatm needs from the service that hosts the test case issues.
+#[async_trait]
+pub trait IssueTracker: Send + Sync {
+    async fn get_labels(&self) -> Result<Vec<GithubLabel>>;
+
+    /// Create the labels that don't exist yet.
+    async fn create_labels(&self, labels: Vec<GithubLabel>) -> Result<()>;
+
+    async fn delete_labels(&self) -> Result<()>;
+
+    /// Get the issues in the given state, or all of them when no state is given.
+    async fn get_issues(&self, state: Option<TrackerIssueState>) -> Result<Vec<TrackerIssue>>;
+
+    /// Create an issue and return its number.
+    async fn create_issue(&self, title: String, body: String, labels: Vec<String>) -> Result<u64>;
+
+    async fn update_issue(&self, issue_id: u64, title: String, body: String) -> Result<()>;
+
+    async fn add_labels_to_issue(&self, issue_id: u64, labels: &[String]) -> Result<()>;
+
+    async fn remove_label_from_issue(&self, issue_id: u64, label: &str) -> Result<()>;
+
+    async fn comment_on_issue(&self, issue_id: u64, body: &str) -> Result<()>;
+
+    async fn close_issue(&self, issue_id: u64) -> Result<()>;
+
+    async fn reopen_issue(&self, issue_id: u64) -> Result<()>;
+
+    /// Get the comments on an issue, oldest first.
+    async fn get_issue_comments(&self, issue_id: u64) -> Result<Vec<TrackerComment>>;
+
+    /// Get the label and state changes of an issue, oldest first.
+    async fn get_issue_events(&self, _issue_id: u64) -> Result<Vec<TrackerIssueEvent>> {
+        anyhow::bail!("This issue tracker doesn't keep the history of issues")
+    }
+
+    async fn close_all_issues(&self) -> Result<()> {
+        let issues = self.get_issues(Some(TrackerIssueState::Open)).await?;
+        for issue in issues {
+            self.close_issue(issue.number).await?;
+        }
+        Ok(())
+    }
+
+    /// Assign an issue to users, by username, on top of the users it's already assigned to.
+    async fn assign_issue(&self, _issue_id: u64, _assignees: &[String]) -> Result<()> {
+        anyhow::bail!("This issue tracker doesn't support assigning issues")
+    }
+
+    /// Lock the conversation of an issue so only collaborators can comment.
+    async fn lock_issue(&self, _issue_id: u64) -> Result<()> {
+        anyhow::bail!("This issue tracker doesn't support locking issues")
+    }
+
+    /// Pin an issue to the top of the issue list.
+    async fn pin_issue(&self, _issue_id: u64) -> Result<()> {
+        anyhow::bail!("This issue tracker doesn't support pinning issues")
+    }
+
+    /// Add an issue to the project board and set its fields, by field name.
+    async fn add_issue_to_project(
+        &self,
+        _issue_id: u64,
+        _fields: &BTreeMap<String, String>,
+    ) -> Result<()> {
+        anyhow::bail!("This issue tracker doesn't support project boards")
+    }
+
+    /// Get the titles of the milestones, open or closed.
+    async fn get_milestones(&self) -> Result<Vec<String>> {
+        anyhow::bail!("This issue tracker doesn't support milestones")
+    }
+
+    /// Create a milestone, due on a `YYYY-MM-DD` date if one is given.
+    async fn create_milestone(&self, _title: &str, _due_on: Option<&str>) -> Result<()> {
+        anyhow::bail!("This issue tracker doesn't support milestones")
+    }
+
+    /// Assign an issue to the milestone with the given title.
+    async fn set_issue_milestone(&self, _issue_id: u64, _milestone: &str) -> Result<()> {
+        anyhow::bail!("This issue tracker doesn't support milestones")
+    }
+
+    /// Get the address of the issues filtered by all of the labels, to open in a browser.
+    fn get_label_link(&self, labels: &[String]) -> String;
+
+    /// Prepend a header naming the repository the issues are uploaded to.
+    fn prepend_tracker_info(&self, content: &str) -> String;
+}
@@ -1,3 +1,11 @@
+#[cfg(any(test, feature = "mock_server"))]
+pub mod fake_github;
 pub mod github;
+pub mod gitlab;
+pub mod issue_tracker;
+pub mod local_tracker;
 pub mod markdown_toc;
+#[cfg(any(test, feature = "mock_server"))]
+pub mod mock_server;
+mod request_retry;
 pub mod types;
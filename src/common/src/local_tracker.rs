@@ -0,0 +1,343 @@
+use crate::issue_tracker::IssueTracker;
+use crate::types::{
+    GithubLabel, TrackerComment, TrackerIssue, TrackerIssueEvent, TrackerIssueEventKind,
+    TrackerIssueState,
+};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// An issue tracker that keeps the issues and labels as YAML files in a directory.
+///
+/// The labels are stored in `labels.yaml`, the milestones in `milestones.yaml` and each issue in
+/// `issues/<number>.yaml`, so uploads can run without a network connection and their results can
+/// be inspected by hand.
+pub struct LocalTracker {
+    dir: PathBuf,
+    /// Makes the changes to the files one at a time.
+    lock: Mutex<()>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct LocalTrackerIssue {
+    #[serde(flatten)]
+    issue: TrackerIssue,
+    #[serde(default)]
+    comments: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    is_pinned: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    is_locked: bool,
+    /// The label and state changes, which have no user since the files are edited locally.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    events: Vec<TrackerIssueEvent>,
+}
+
+impl LocalTrackerIssue {
+    fn add_event(&mut self, kind: TrackerIssueEventKind) {
+        self.events.push(TrackerIssueEvent {
+            kind,
+            actor: None,
+            created_at: Some(chrono::Utc::now().to_rfc3339()),
+        });
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct LocalTrackerMilestone {
+    title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    due_on: Option<String>,
+}
+
+impl LocalTracker {
+    pub fn new(dir: &PathBuf) -> Result<Self> {
+        std::fs::create_dir_all(dir.join("issues"))
+            .context(format!("Failed to create the issue tracker dir: {:?}", dir))?;
+        Ok(Self {
+            dir: dir.clone(),
+            lock: Mutex::new(()),
+        })
+    }
+
+    fn labels_path(&self) -> PathBuf {
+        self.dir.join("labels.yaml")
+    }
+
+    fn milestones_path(&self) -> PathBuf {
+        self.dir.join("milestones.yaml")
+    }
+
+    fn issue_path(&self, issue_id: u64) -> PathBuf {
+        self.dir.join("issues").join(format!("{}.yaml", issue_id))
+    }
+
+    fn read_labels(&self) -> Result<Vec<GithubLabel>> {
+        let path = self.labels_path();
+        if !path.is_file() {
+            return Ok(vec![]);
+        }
+        let labels =
+            std::fs::read_to_string(&path).context(format!("Failed to read {:?}", path))?;
+        serde_yaml::from_str(&labels).context(format!("Failed to deserialize {:?}", path))
+    }
+
+    fn write_labels(&self, labels: &Vec<GithubLabel>) -> Result<()> {
+        let path = self.labels_path();
+        let labels = serde_yaml::to_string(labels).context("Failed to serialize the labels")?;
+        std::fs::write(&path, labels).context(format!("Failed to write {:?}", path))
+    }
+
+    fn read_milestones(&self) -> Result<Vec<LocalTrackerMilestone>> {
+        let path = self.milestones_path();
+        if !path.is_file() {
+            return Ok(vec![]);
+        }
+        let milestones =
+            std::fs::read_to_string(&path).context(format!("Failed to read {:?}", path))?;
+        serde_yaml::from_str(&milestones).context(format!("Failed to deserialize {:?}", path))
+    }
+
+    fn read_issues(&self) -> Result<Vec<LocalTrackerIssue>> {
+        let dir = self.dir.join("issues");
+        let mut issues: Vec<LocalTrackerIssue> = vec![];
+        for entry in std::fs::read_dir(&dir).context(format!("Failed to read {:?}", dir))? {
+            let path = entry.context(format!("Failed to read {:?}", dir))?.path();
+            if path.extension().is_some_and(|e| e == "yaml") {
+                issues.push(self.read_issue_file(&path)?);
+            }
+        }
+        issues.sort_by_key(|i| i.issue.number);
+        Ok(issues)
+    }
+
+    fn read_issue_file(&self, path: &PathBuf) -> Result<LocalTrackerIssue> {
+        let issue = std::fs::read_to_string(path).context(format!("Failed to read {:?}", path))?;
+        serde_yaml::from_str(&issue).context(format!("Failed to deserialize {:?}", path))
+    }
+
+    fn read_issue(&self, issue_id: u64) -> Result<LocalTrackerIssue> {
+        let path = self.issue_path(issue_id);
+        if !path.is_file() {
+            anyhow::bail!("Issue #{} not found in {:?}", issue_id, self.dir);
+        }
+        self.read_issue_file(&path)
+    }
+
+    fn write_issue(&self, issue: &LocalTrackerIssue) -> Result<()> {
+        let path = self.issue_path(issue.issue.number);
+        let contents = serde_yaml::to_string(issue)
+            .context(format!("Failed to serialize issue #{}", issue.issue.number))?;
+        std::fs::write(&path, contents).context(format!("Failed to write {:?}", path))
+    }
+
+    /// Read an issue, change it and write it back.
+    fn edit_issue(&self, issue_id: u64, edit: impl FnOnce(&mut LocalTrackerIssue)) -> Result<()> {
+        let _lock = self.lock.lock().unwrap();
+        let mut issue = self.read_issue(issue_id)?;
+        edit(&mut issue);
+        self.write_issue(&issue)
+    }
+}
+
+#[async_trait]
+impl IssueTracker for LocalTracker {
+    async fn get_labels(&self) -> Result<Vec<GithubLabel>> {
+        self.read_labels()
+    }
+
+    async fn create_labels(&self, labels: Vec<GithubLabel>) -> Result<()> {
+        let _lock = self.lock.lock().unwrap();
+        let mut existing_labels = self.read_labels()?;
+        for label in labels {
+            if existing_labels.iter().any(|l| l.name == label.name) {
+                println!("Skipping '{}' because it already exists", label.name);
+                continue;
+            }
+            println!("Created label '{}'", label.name);
+            existing_labels.push(label);
+        }
+        self.write_labels(&existing_labels)
+    }
+
+    async fn delete_labels(&self) -> Result<()> {
+        let _lock = self.lock.lock().unwrap();
+        self.write_labels(&vec![])
+    }
+
+    async fn get_issues(&self, state: Option<TrackerIssueState>) -> Result<Vec<TrackerIssue>> {
+        Ok(self
+            .read_issues()?
+            .into_iter()
+            .map(|i| i.issue)
+            .filter(|i| state.is_none_or(|s| i.state == s))
+            .collect())
+    }
+
+    async fn create_issue(&self, title: String, body: String, labels: Vec<String>) -> Result<u64> {
+        let _lock = self.lock.lock().unwrap();
+        let number = self
+            .read_issues()?
+            .iter()
+            .map(|i| i.issue.number)
+            .max()
+            .unwrap_or_default()
+            + 1;
+        self.write_issue(&LocalTrackerIssue {
+            issue: TrackerIssue {
+                number,
+                title,
+                body: Some(body),
+                labels,
+                state: TrackerIssueState::Open,
+                url: format!("file://{}", self.issue_path(number).display()),
+                is_pull_request: false,
+                milestone: None,
+                closed_at: None,
+                assignees: vec![],
+            },
+            comments: vec![],
+            is_pinned: false,
+            is_locked: false,
+            events: vec![],
+        })?;
+        Ok(number)
+    }
+
+    async fn update_issue(&self, issue_id: u64, title: String, body: String) -> Result<()> {
+        self.edit_issue(issue_id, |i| {
+            i.issue.title = title;
+            i.issue.body = Some(body);
+        })
+    }
+
+    async fn add_labels_to_issue(&self, issue_id: u64, labels: &[String]) -> Result<()> {
+        self.edit_issue(issue_id, |i| {
+            for label in labels {
+                if !i.issue.labels.contains(label) {
+                    i.issue.labels.push(label.clone());
+                    i.add_event(TrackerIssueEventKind::Labeled(label.clone()));
+                }
+            }
+        })
+    }
+
+    async fn remove_label_from_issue(&self, issue_id: u64, label: &str) -> Result<()> {
+        self.edit_issue(issue_id, |i| {
+            if i.issue.labels.iter().any(|l| l == label) {
+                i.issue.labels.retain(|l| l != label);
+                i.add_event(TrackerIssueEventKind::Unlabeled(label.to_string()));
+            }
+        })
+    }
+
+    async fn comment_on_issue(&self, issue_id: u64, body: &str) -> Result<()> {
+        self.edit_issue(issue_id, |i| i.comments.push(body.to_string()))
+    }
+
+    async fn close_issue(&self, issue_id: u64) -> Result<()> {
+        self.edit_issue(issue_id, |i| {
+            i.issue.state = TrackerIssueState::Closed;
+            i.issue.closed_at = Some(chrono::Utc::now().to_rfc3339());
+            i.add_event(TrackerIssueEventKind::Closed);
+        })
+    }
+
+    async fn reopen_issue(&self, issue_id: u64) -> Result<()> {
+        self.edit_issue(issue_id, |i| {
+            i.issue.state = TrackerIssueState::Open;
+            i.issue.closed_at = None;
+            i.add_event(TrackerIssueEventKind::Reopened);
+        })
+    }
+
+    async fn get_issue_comments(&self, issue_id: u64) -> Result<Vec<TrackerComment>> {
+        Ok(self
+            .read_issue(issue_id)?
+            .comments
+            .into_iter()
+            .map(|body| TrackerComment {
+                author: None,
+                body,
+                created_at: None,
+            })
+            .collect())
+    }
+
+    async fn get_issue_events(&self, issue_id: u64) -> Result<Vec<TrackerIssueEvent>> {
+        Ok(self.read_issue(issue_id)?.events)
+    }
+
+    async fn assign_issue(&self, issue_id: u64, assignees: &[String]) -> Result<()> {
+        self.edit_issue(issue_id, |i| {
+            for assignee in assignees {
+                if !i.issue.assignees.contains(assignee) {
+                    i.issue.assignees.push(assignee.clone());
+                }
+            }
+        })
+    }
+
+    async fn lock_issue(&self, issue_id: u64) -> Result<()> {
+        self.edit_issue(issue_id, |i| i.is_locked = true)
+    }
+
+    async fn pin_issue(&self, issue_id: u64) -> Result<()> {
+        self.edit_issue(issue_id, |i| i.is_pinned = true)
+    }
+
+    async fn get_milestones(&self) -> Result<Vec<String>> {
+        Ok(self
+            .read_milestones()?
+            .into_iter()
+            .map(|m| m.title)
+            .collect())
+    }
+
+    async fn create_milestone(&self, title: &str, due_on: Option<&str>) -> Result<()> {
+        let _lock = self.lock.lock().unwrap();
+        let mut milestones = self.read_milestones()?;
+        if milestones.iter().any(|m| m.title == title) {
+            anyhow::bail!("Milestone '{}' already exists in {:?}", title, self.dir);
+        }
+        milestones.push(LocalTrackerMilestone {
+            title: title.to_string(),
+            due_on: due_on.map(|d| d.to_string()),
+        });
+        let path = self.milestones_path();
+        let milestones =
+            serde_yaml::to_string(&milestones).context("Failed to serialize the milestones")?;
+        std::fs::write(&path, milestones).context(format!("Failed to write {:?}", path))
+    }
+
+    async fn set_issue_milestone(&self, issue_id: u64, milestone: &str) -> Result<()> {
+        if !self.get_milestones().await?.iter().any(|m| m == milestone) {
+            anyhow::bail!("Milestone '{}' not found in {:?}", milestone, self.dir);
+        }
+        self.edit_issue(issue_id, |i| {
+            i.issue.milestone = Some(milestone.to_string())
+        })
+    }
+
+    fn get_label_link(&self, labels: &[String]) -> String {
+        format!(
+            "file://{}?labels={}",
+            self.dir.join("issues").display(),
+            percent_encoding::utf8_percent_encode(
+                &labels.join(","),
+                percent_encoding::NON_ALPHANUMERIC
+            )
+        )
+    }
+
+    fn prepend_tracker_info(&self, content: &str) -> String {
+        let header = format!(
+            "# Local Target\n\ndirectory: [{}](file://{})",
+            self.dir.display(),
+            self.dir.display()
+        );
+        format!("{}\n\n{}", header, content)
+    }
+}
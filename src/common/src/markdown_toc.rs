@@ -40,7 +40,7 @@ pub fn prepend_markdown_table_of_contents(
 }
 
 pub fn make_markdown_table_of_contents(
-    content: &String,
+    content: &str,
     options: Option<&TocOptions>,
 ) -> Result<String> {
     let default_options = TocOptions::default();
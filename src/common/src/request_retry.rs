@@ -0,0 +1,335 @@
+use anyhow::{Context, Result};
+use http::header::HeaderMap;
+use http::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use tokio::sync::Semaphore;
+use tokio::time::{sleep, Duration};
+
+/// The number of times a request is retried before giving up.
+const MAX_RETRIES: u32 = 6;
+/// The wait before the first retry, doubled for each retry after it.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// The longest wait between retries when the issue tracker doesn't say how long to wait.
+const MAX_BACKOFF: Duration = Duration::from_secs(120);
+
+/// A response of an issue tracker, with its body read.
+pub(crate) struct TrackerResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub text: String,
+}
+
+/// Send a request to an issue tracker and deserialize the response, retrying it when it's safe.
+///
+/// `send` sends the request once, while holding one of the `request_slots`. It fails for errors
+/// that sending again won't fix, and otherwise returns the response or the error that kept it
+/// from arriving, which is retried like a server error.
+pub(crate) async fn send_with_retries<T, F, Fut>(
+    tracker_name: &str,
+    request_slots: &Semaphore,
+    method: &Method,
+    route: &str,
+    mut send: F,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Result<TrackerResponse>>>,
+{
+    let mut attempt: u32 = 0;
+    loop {
+        let response = {
+            let _slot = request_slots
+                .acquire()
+                .await
+                .context("Failed to wait for a request slot")?;
+            send().await?
+        };
+
+        let retry_delay = match response {
+            Err(error) => {
+                if attempt >= MAX_RETRIES || !is_idempotent(method) {
+                    return Err(
+                        error.context(format!("Failed to send the request {} {}", method, route))
+                    );
+                }
+                get_backoff(attempt)
+            }
+            Ok(TrackerResponse {
+                status,
+                headers,
+                text,
+            }) => {
+                if status.is_success() {
+                    let text = if text.trim().is_empty() {
+                        "null"
+                    } else {
+                        &text
+                    };
+                    return serde_json::from_str(text).context(format!(
+                        "Failed to parse the response to {} {}",
+                        method, route
+                    ));
+                }
+                match get_retry_delay(
+                    method,
+                    status,
+                    &headers,
+                    &text,
+                    attempt,
+                    get_epoch_seconds(),
+                ) {
+                    Some(delay) if attempt < MAX_RETRIES => delay,
+                    _ => anyhow::bail!(
+                        "{} responded with {} to {} {}: {}",
+                        tracker_name,
+                        status,
+                        method,
+                        route,
+                        text
+                    ),
+                }
+            }
+        };
+        println!(
+            "Request {} {} failed, retrying in {} seconds",
+            method,
+            route,
+            retry_delay.as_secs()
+        );
+        sleep(retry_delay).await;
+        attempt += 1;
+    }
+}
+
+/// Whether a request can be sent again without risking that the issue tracker applies it twice.
+///
+/// A `POST` that failed may still have created an issue or a comment, so it isn't retried.
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::PATCH | Method::PUT | Method::DELETE
+    )
+}
+
+/// Get how long to wait before retrying a failed request, if it should be retried at all.
+///
+/// Requests that aren't idempotent are only retried when the issue tracker refused them because
+/// of a rate limit and said when to try again, since they weren't applied then.
+fn get_retry_delay(
+    method: &Method,
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &str,
+    attempt: u32,
+    now_epoch_seconds: u64,
+) -> Option<Duration> {
+    let get_header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+    };
+
+    let is_refused = status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::FORBIDDEN;
+    if !is_idempotent(method) && !is_refused {
+        return None;
+    }
+    if let Some(seconds) = get_header("retry-after") {
+        return Some(Duration::from_secs(seconds));
+    }
+    let is_rate_limited = status == StatusCode::TOO_MANY_REQUESTS
+        || (status == StatusCode::FORBIDDEN && body.to_lowercase().contains("rate limit"));
+    if is_refused {
+        // Github prefixes the rate limit headers with `x-`, Gitlab doesn't
+        let remaining =
+            get_header("x-ratelimit-remaining").or_else(|| get_header("ratelimit-remaining"));
+        let reset = get_header("x-ratelimit-reset").or_else(|| get_header("ratelimit-reset"));
+        if let (Some(0), Some(reset)) = (remaining, reset) {
+            return Some(Duration::from_secs(
+                reset.saturating_sub(now_epoch_seconds) + 1,
+            ));
+        }
+    }
+    if !is_idempotent(method) {
+        return None;
+    }
+    if is_rate_limited || status.is_server_error() {
+        return Some(get_backoff(attempt));
+    }
+    None
+}
+
+fn get_backoff(attempt: u32) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_BACKOFF)
+}
+
+fn get_epoch_seconds() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test_get_retry_delay {
+    use super::*;
+    use http::header::HeaderMap;
+
+    fn make_headers(headers: &[(&'static str, &str)]) -> HeaderMap {
+        let mut header_map = HeaderMap::new();
+        for (name, value) in headers {
+            header_map.insert(*name, value.parse().unwrap());
+        }
+        header_map
+    }
+
+    #[test]
+    fn uses_retry_after() {
+        let headers = make_headers(&[("retry-after", "30")]);
+        assert_eq!(
+            get_retry_delay(&Method::GET, StatusCode::FORBIDDEN, &headers, "", 0, 0),
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn waits_for_rate_limit_reset() {
+        let headers = make_headers(&[
+            ("x-ratelimit-remaining", "0"),
+            ("x-ratelimit-reset", "1100"),
+        ]);
+        assert_eq!(
+            get_retry_delay(&Method::GET, StatusCode::FORBIDDEN, &headers, "", 0, 1000),
+            Some(Duration::from_secs(101))
+        );
+    }
+
+    #[test]
+    fn waits_for_gitlab_rate_limit_reset() {
+        let headers = make_headers(&[("ratelimit-remaining", "0"), ("ratelimit-reset", "1010")]);
+        assert_eq!(
+            get_retry_delay(
+                &Method::GET,
+                StatusCode::TOO_MANY_REQUESTS,
+                &headers,
+                "",
+                0,
+                1000
+            ),
+            Some(Duration::from_secs(11))
+        );
+    }
+
+    #[test]
+    fn backs_off_on_secondary_rate_limit() {
+        let body = "You have exceeded a secondary rate limit";
+        let headers = HeaderMap::new();
+        assert_eq!(
+            get_retry_delay(&Method::GET, StatusCode::FORBIDDEN, &headers, body, 0, 0),
+            Some(INITIAL_BACKOFF)
+        );
+        assert_eq!(
+            get_retry_delay(&Method::GET, StatusCode::FORBIDDEN, &headers, body, 2, 0),
+            Some(INITIAL_BACKOFF * 4)
+        );
+        assert_eq!(
+            get_retry_delay(&Method::GET, StatusCode::FORBIDDEN, &headers, body, 20, 0),
+            Some(MAX_BACKOFF)
+        );
+    }
+
+    #[test]
+    fn backs_off_on_server_error() {
+        assert_eq!(
+            get_retry_delay(
+                &Method::GET,
+                StatusCode::BAD_GATEWAY,
+                &HeaderMap::new(),
+                "",
+                1,
+                0
+            ),
+            Some(INITIAL_BACKOFF * 2)
+        );
+    }
+
+    #[test]
+    fn does_not_retry_client_errors() {
+        assert_eq!(
+            get_retry_delay(
+                &Method::GET,
+                StatusCode::NOT_FOUND,
+                &HeaderMap::new(),
+                "",
+                0,
+                0
+            ),
+            None
+        );
+        assert_eq!(
+            get_retry_delay(
+                &Method::GET,
+                StatusCode::FORBIDDEN,
+                &HeaderMap::new(),
+                "Forbidden",
+                0,
+                0
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn only_retries_posts_refused_by_a_rate_limit() {
+        let headers = make_headers(&[("retry-after", "30")]);
+        assert_eq!(
+            get_retry_delay(
+                &Method::POST,
+                StatusCode::TOO_MANY_REQUESTS,
+                &headers,
+                "",
+                0,
+                0
+            ),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            get_retry_delay(
+                &Method::POST,
+                StatusCode::SERVICE_UNAVAILABLE,
+                &headers,
+                "",
+                0,
+                0
+            ),
+            None
+        );
+        assert_eq!(
+            get_retry_delay(
+                &Method::POST,
+                StatusCode::BAD_GATEWAY,
+                &HeaderMap::new(),
+                "",
+                0,
+                0
+            ),
+            None
+        );
+        let body = "You have exceeded a secondary rate limit";
+        assert_eq!(
+            get_retry_delay(
+                &Method::POST,
+                StatusCode::FORBIDDEN,
+                &HeaderMap::new(),
+                body,
+                0,
+                0
+            ),
+            None
+        );
+    }
+}
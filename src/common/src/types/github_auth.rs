@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// How to authenticate with Github.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum GithubAuth {
+    /// A token in the `GITHUB_TOKEN` environment variable or `.env` file
+    #[default]
+    Env,
+    /// A token in a file
+    TokenFile { path: PathBuf },
+    /// A token printed by a command, such as `gh auth token`
+    Command { command: String },
+    /// A Github App installed on the repository
+    App {
+        app_id: u64,
+        private_key_file: PathBuf,
+    },
+}
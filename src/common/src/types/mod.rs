@@ -1,13 +1,19 @@
+mod github_auth;
 mod github_label;
 mod requirement;
 mod requirements_file;
 mod test_case;
 mod test_cases_builder;
 mod test_cases_builder_file;
+mod tracker_activity;
+mod tracker_issue;
 
+pub use github_auth::GithubAuth;
 pub use github_label::GithubLabel;
 pub use requirement::{Action, Expect, Link, Requirement, Step, Terminal};
 pub use requirements_file::RequirementsFile;
 pub use test_case::TestCase;
 pub use test_cases_builder::{Filter, SetSteps, TestCasesBuilder};
 pub use test_cases_builder_file::TestCasesBuilderFile;
+pub use tracker_activity::{TrackerComment, TrackerIssueEvent, TrackerIssueEventKind};
+pub use tracker_issue::{TrackerIssue, TrackerIssueState};
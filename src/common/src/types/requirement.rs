@@ -10,8 +10,8 @@ pub struct Requirement {
     pub links: Option<Vec<Link>>,
 }
 
-impl Requirement {
-    pub fn default() -> Self {
+impl Default for Requirement {
+    fn default() -> Self {
         Requirement {
             name: "name".to_string(),
             shortname: Some("shortname".to_string()),
@@ -47,6 +47,7 @@ impl Requirement {
                     }),
                     Expect::Image("https://placekitten.com/200/300".to_string()),
                 ],
+                checklist: false,
             }],
             labels: Some(vec!["label".to_string()]),
             links: Some(vec![Link {
@@ -69,6 +70,9 @@ pub struct Step {
     pub description: Option<String>,
     pub action: Vec<Action>,
     pub expect: Vec<Expect>,
+    /// Render the expected results as a task list, with a box for the tester to tick per result.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub checklist: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -6,8 +6,8 @@ pub struct RequirementsFile {
     pub requirements: Vec<Requirement>,
 }
 
-impl RequirementsFile {
-    pub fn default() -> Self {
+impl Default for RequirementsFile {
+    fn default() -> Self {
         RequirementsFile {
             requirements: vec![Requirement::default()],
         }
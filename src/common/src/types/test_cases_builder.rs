@@ -23,8 +23,8 @@ fn default_min_permutations_to_render() -> u32 {
     0
 }
 
-impl TestCasesBuilder {
-    pub fn default() -> Self {
+impl Default for TestCasesBuilder {
+    fn default() -> Self {
         let mut permutations = HashMap::new();
         permutations.insert(
             "Operating System".to_string(),
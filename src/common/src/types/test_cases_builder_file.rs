@@ -6,8 +6,8 @@ pub struct TestCasesBuilderFile {
     pub test_cases_builders: Vec<TestCasesBuilder>,
 }
 
-impl TestCasesBuilderFile {
-    pub fn default() -> Self {
+impl Default for TestCasesBuilderFile {
+    fn default() -> Self {
         TestCasesBuilderFile {
             test_cases_builders: vec![TestCasesBuilder::default()],
         }
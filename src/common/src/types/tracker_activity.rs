@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// A comment on an issue.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TrackerComment {
+    /// The user who wrote the comment, if the tracker knows them.
+    pub author: Option<String>,
+    pub body: String,
+    /// When the comment was written, as an RFC 3339 timestamp.
+    pub created_at: Option<String>,
+}
+
+/// A change to the labels or state of an issue.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TrackerIssueEvent {
+    pub kind: TrackerIssueEventKind,
+    /// The user who made the change, if the tracker knows them.
+    pub actor: Option<String>,
+    /// When the change was made, as an RFC 3339 timestamp.
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub enum TrackerIssueEventKind {
+    /// A label, by name, was added.
+    Labeled(String),
+    /// A label, by name, was removed.
+    Unlabeled(String),
+    Closed,
+    Reopened,
+}
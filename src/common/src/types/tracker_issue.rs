@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum TrackerIssueState {
+    Open,
+    Closed,
+}
+
+/// An issue as any issue tracker reports it.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TrackerIssue {
+    /// The number of the issue in its repository.
+    pub number: u64,
+    pub title: String,
+    pub body: Option<String>,
+    /// The names of the labels on the issue.
+    pub labels: Vec<String>,
+    pub state: TrackerIssueState,
+    /// The address of the issue in a browser.
+    pub url: String,
+    /// Whether the issue is a pull request, which some trackers list with the issues.
+    pub is_pull_request: bool,
+    /// The title of the milestone the issue is assigned to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub milestone: Option<String>,
+    /// When the issue was last closed, as an RFC 3339 timestamp.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closed_at: Option<String>,
+    /// The users the issue is assigned to.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assignees: Vec<String>,
+}
@@ -130,30 +130,30 @@ mod migrate_v1_requirements {
         // run the command without the output flag
         assert!(!temp_v2_file_path.exists());
         let mut cmd = get_command();
-        cmd.args(&[&temp_v1_file_path.to_str().unwrap()])
+        cmd.args([&temp_v1_file_path.to_str().unwrap()])
             .assert()
             .success();
         assert!(temp_v2_file_path.exists());
 
         // running the command without force fails to overwrite the existing file
         let mut cmd = get_command();
-        cmd.args(&[&temp_v1_file_path.to_str().unwrap()])
+        cmd.args([&temp_v1_file_path.to_str().unwrap()])
             .assert()
             .failure();
 
         // running the command with force succeeds
         let mut cmd = get_command();
-        cmd.args(&[&temp_v1_file_path.to_str().unwrap(), "--force"])
+        cmd.args([temp_v1_file_path.to_str().unwrap(), "--force"])
             .assert()
             .success();
 
         // running the command with the output flag
         assert!(!temp_v2_file_path_custom_output.exists());
         let mut cmd = get_command();
-        cmd.args(&[
-            &temp_v1_file_path.to_str().unwrap(),
+        cmd.args([
+            temp_v1_file_path.to_str().unwrap(),
             "--output",
-            &temp_v2_file_path_custom_output.to_str().unwrap(),
+            temp_v2_file_path_custom_output.to_str().unwrap(),
         ])
         .assert()
         .success();
@@ -80,6 +80,7 @@ fn check_v1_to_step_v2(check: &CheckV1) -> Result<StepV2> {
         description: check.description.clone(),
         action: actions,
         expect: expects,
+        checklist: false,
     })
 }
 
@@ -0,0 +1,282 @@
+use crate::helpers::{
+    get_id_from_body, get_test_case_id, permutation_to_labels, project_version_to_label,
+};
+use crate::types::{Assignment, AssignmentPlan, Config, Roster, Tester, UnassignedIssue};
+use anyhow::Result;
+use common::issue_tracker::IssueTracker;
+use common::types::{TestCase, TrackerIssue, TrackerIssueState};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::collections::{BTreeMap, HashMap};
+
+/// Plan which tester each unassigned open issue of the workspace version goes to.
+///
+/// A tester can take an issue when they can cover every value of its test case's permutation. The
+/// issues with the fewest eligible testers are handed out first, each to the eligible tester with
+/// the fewest open issues, counting the ones they were already assigned. A tester's own
+/// `max_issues` is their cap, then `max_per_tester`, then the roster's `max_issues_per_tester`.
+pub fn get_assignment_plan(
+    config: &Config,
+    test_cases: &[TestCase],
+    issues: &[TrackerIssue],
+    roster: &Roster,
+    max_per_tester: Option<usize>,
+) -> AssignmentPlan {
+    let version_label = project_version_to_label(&config.workspace_version);
+    let issues: Vec<&TrackerIssue> = issues
+        .iter()
+        .filter(|i| {
+            !i.is_pull_request
+                && i.state == TrackerIssueState::Open
+                && i.labels.contains(&version_label)
+        })
+        .collect();
+    let test_cases_by_id: HashMap<String, &TestCase> = test_cases
+        .iter()
+        .map(|t| (get_test_case_id(t, &config.workspace_version), t))
+        .collect();
+
+    let mut loads: BTreeMap<String, usize> = roster
+        .testers
+        .iter()
+        .map(|t| {
+            let load = issues
+                .iter()
+                .filter(|i| {
+                    i.assignees
+                        .iter()
+                        .any(|a| a.eq_ignore_ascii_case(&t.handle))
+                })
+                .count();
+            (t.handle.clone(), load)
+        })
+        .collect();
+    let get_cap = |tester: &Tester| {
+        tester
+            .max_issues
+            .or(max_per_tester)
+            .or(roster.max_issues_per_tester)
+    };
+
+    let mut unassigned = vec![];
+    let mut candidates: Vec<(&TrackerIssue, Vec<&Tester>)> = vec![];
+    for issue in issues.iter().filter(|i| i.assignees.is_empty()) {
+        let test_case = issue
+            .body
+            .as_deref()
+            .and_then(get_id_from_body)
+            .and_then(|id| test_cases_by_id.get(&id));
+        let Some(test_case) = test_case else {
+            unassigned.push(UnassignedIssue {
+                issue_number: issue.number,
+                title: issue.title.clone(),
+                reason: "no test case makes this issue".to_string(),
+            });
+            continue;
+        };
+        let permutation: BTreeMap<String, String> = test_case
+            .selected_permutation
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        let testers: Vec<&Tester> = roster
+            .testers
+            .iter()
+            .filter(|t| t.can_test(&permutation))
+            .collect();
+        if testers.is_empty() {
+            unassigned.push(UnassignedIssue {
+                issue_number: issue.number,
+                title: issue.title.clone(),
+                reason: format!(
+                    "no tester can cover {}",
+                    permutation_to_labels(&test_case.selected_permutation).join(", ")
+                ),
+            });
+            continue;
+        }
+        candidates.push((issue, testers));
+    }
+    candidates.sort_by_key(|(issue, testers)| (testers.len(), issue.number));
+
+    let mut assignments = vec![];
+    for (issue, testers) in candidates {
+        let tester = testers
+            .into_iter()
+            .filter(|t| get_cap(t).is_none_or(|cap| loads[&t.handle] < cap))
+            .min_by_key(|t| loads[&t.handle]);
+        match tester {
+            Some(tester) => {
+                *loads.get_mut(&tester.handle).unwrap() += 1;
+                assignments.push(Assignment {
+                    issue_number: issue.number,
+                    title: issue.title.clone(),
+                    tester: tester.handle.clone(),
+                });
+            }
+            None => unassigned.push(UnassignedIssue {
+                issue_number: issue.number,
+                title: issue.title.clone(),
+                reason: "every eligible tester has reached their cap".to_string(),
+            }),
+        }
+    }
+    assignments.sort_by_key(|a| a.issue_number);
+    unassigned.sort_by_key(|i| i.issue_number);
+
+    AssignmentPlan {
+        assignments,
+        unassigned,
+        loads,
+    }
+}
+
+/// Assign the issues of a plan to their testers.
+pub async fn apply_assignment_plan(
+    tracker: &dyn IssueTracker,
+    plan: &AssignmentPlan,
+    concurrency: usize,
+) -> Result<()> {
+    stream::iter(&plan.assignments)
+        .map(|assignment| async move {
+            tracker
+                .assign_issue(
+                    assignment.issue_number,
+                    std::slice::from_ref(&assignment.tester),
+                )
+                .await?;
+            println!("{}", assignment);
+            Ok::<_, anyhow::Error>(())
+        })
+        .buffer_unordered(concurrency.max(1))
+        .try_collect::<Vec<_>>()
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_assign {
+    use super::*;
+    use crate::test_utils::{make_test_case, make_test_case_issue};
+    use common::fake_github::FakeGithub;
+    use common::github::Github;
+
+    fn make_issue(
+        config: &Config,
+        number: u64,
+        test_case: &TestCase,
+        assignees: &[&str],
+    ) -> TrackerIssue {
+        let mut issue = make_test_case_issue(number, test_case, &config.workspace_version);
+        issue.assignees = assignees.iter().map(|a| a.to_string()).collect();
+        issue
+    }
+
+    fn make_tester(handle: &str, systems: &[&str]) -> Tester {
+        Tester {
+            handle: handle.to_string(),
+            can_test: match systems.is_empty() {
+                true => BTreeMap::new(),
+                false => BTreeMap::from([(
+                    "OS".to_string(),
+                    systems.iter().map(|s| s.to_string()).collect(),
+                )]),
+            },
+            max_issues: None,
+        }
+    }
+
+    #[test]
+    fn balances_the_issues_across_eligible_testers() {
+        let config = Config::default();
+        let test_cases = vec![
+            make_test_case("a", &[("OS", "Ubuntu")]),
+            make_test_case("b", &[("OS", "Ubuntu")]),
+            make_test_case("c", &[("OS", "Windows")]),
+            make_test_case("d", &[("OS", "Windows")]),
+            make_test_case("e", &[("OS", "macOS")]),
+        ];
+        let issues: Vec<TrackerIssue> = test_cases
+            .iter()
+            .enumerate()
+            .map(|(i, t)| make_issue(&config, i as u64 + 1, t, &[]))
+            .collect();
+        let roster = Roster {
+            max_issues_per_tester: None,
+            testers: vec![
+                make_tester("alice", &["Ubuntu", "Windows"]),
+                make_tester("bob", &["Windows"]),
+            ],
+        };
+
+        let plan = get_assignment_plan(&config, &test_cases, &issues, &roster, None);
+        let assigned: Vec<(u64, &str)> = plan
+            .assignments
+            .iter()
+            .map(|a| (a.issue_number, a.tester.as_str()))
+            .collect();
+        // Only alice runs Ubuntu, so bob takes the Windows issues
+        assert_eq!(
+            assigned,
+            vec![(1, "alice"), (2, "alice"), (3, "bob"), (4, "bob")]
+        );
+        assert_eq!(plan.unassigned.len(), 1);
+        assert_eq!(plan.unassigned[0].issue_number, 5);
+        assert!(plan.unassigned[0].reason.contains("OS: macOS"));
+        assert_eq!(
+            plan.loads,
+            BTreeMap::from([("alice".to_string(), 2), ("bob".to_string(), 2)])
+        );
+    }
+
+    #[test]
+    fn respects_the_caps_and_existing_assignments() {
+        let config = Config::default();
+        let test_cases: Vec<TestCase> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|n| make_test_case(n, &[("OS", "Ubuntu")]))
+            .collect();
+        let mut issues: Vec<TrackerIssue> = test_cases
+            .iter()
+            .enumerate()
+            .map(|(i, t)| make_issue(&config, i as u64 + 1, t, &[]))
+            .collect();
+        issues[0].assignees = vec!["Alice".to_string()];
+        let mut bob = make_tester("bob", &[]);
+        bob.max_issues = Some(1);
+        let roster = Roster {
+            max_issues_per_tester: Some(1),
+            testers: vec![make_tester("alice", &[]), bob],
+        };
+
+        // alice already has an issue, so she only gets more once the cap is raised
+        let plan = get_assignment_plan(&config, &test_cases, &issues, &roster, None);
+        assert_eq!(plan.assignments.len(), 1);
+        assert_eq!(plan.assignments[0].tester, "bob");
+        assert_eq!(plan.unassigned.len(), 2);
+        let plan = get_assignment_plan(&config, &test_cases, &issues, &roster, Some(2));
+        assert_eq!(plan.assignments.len(), 2);
+        assert_eq!(plan.loads["alice"], 2);
+        assert_eq!(plan.loads["bob"], 1);
+    }
+
+    #[tokio::test]
+    async fn assigns_the_issues_on_github() {
+        let fake_github = FakeGithub::start("owner", "repo").await.unwrap();
+        let number = fake_github.add_issue("Test", "", &[]);
+        let github = Github::new_with_token(fake_github.url(), "owner", "repo", "token").unwrap();
+        let plan = AssignmentPlan {
+            assignments: vec![Assignment {
+                issue_number: number,
+                title: "Test".to_string(),
+                tester: "alice".to_string(),
+            }],
+            unassigned: vec![],
+            loads: BTreeMap::new(),
+        };
+
+        apply_assignment_plan(&github, &plan, 1).await.unwrap();
+        assert_eq!(fake_github.issues()[0].assignees, vec!["alice"]);
+        let issues = github.get_issues(None).await.unwrap();
+        assert_eq!(issues[0].assignees, vec!["alice"]);
+    }
+}
@@ -0,0 +1,288 @@
+use crate::helpers::{get_id_from_body, get_test_case_id, project_version_to_label};
+use crate::types::{BulkAction, BulkOperation, Config, FilterKey, FilterTerm, IssueFilter};
+use anyhow::Result;
+use common::issue_tracker::IssueTracker;
+use common::types::{TestCase, TrackerIssue, TrackerIssueState};
+use futures::stream::{self, StreamExt};
+use std::collections::HashMap;
+
+/// Get the operations that apply an action to the yatm issues that match a filter.
+///
+/// Issues are matched against the test case they were made from, for whichever workspace version
+/// they have the label of. Unless the filter has a `version` term, only the issues of the current
+/// workspace version match. Issues the action wouldn't change, such as closed issues when
+/// closing, are left out.
+pub fn get_bulk_operations(
+    config: &Config,
+    test_cases: &[TestCase],
+    issues: &[TrackerIssue],
+    filter: &IssueFilter,
+    action: &BulkAction,
+) -> Vec<BulkOperation> {
+    let has_version_term = filter.terms.iter().any(|t| t.key == FilterKey::Version);
+    let version_label = project_version_to_label(&config.workspace_version);
+    let mut test_cases_by_version: HashMap<String, HashMap<String, &TestCase>> = HashMap::new();
+
+    let mut operations = vec![];
+    for issue in issues {
+        let Some(id) = issue.body.as_deref().and_then(get_id_from_body) else {
+            continue;
+        };
+        if issue.is_pull_request || (!has_version_term && !issue.labels.contains(&version_label)) {
+            continue;
+        }
+        let versions: Vec<String> = issue
+            .labels
+            .iter()
+            .filter_map(|l| l.strip_prefix("version: ").map(|v| v.to_string()))
+            .collect();
+        let test_case = versions.iter().find_map(|version| {
+            test_cases_by_version
+                .entry(version.clone())
+                .or_insert_with(|| {
+                    test_cases
+                        .iter()
+                        .map(|t| (get_test_case_id(t, version), t))
+                        .collect()
+                })
+                .get(&id)
+                .copied()
+        });
+        if !filter
+            .terms
+            .iter()
+            .all(|term| is_term_match(term, issue, test_case, &versions))
+        {
+            continue;
+        }
+
+        let action = match action {
+            BulkAction::Close if issue.state == TrackerIssueState::Closed => continue,
+            BulkAction::Reopen if issue.state == TrackerIssueState::Open => continue,
+            BulkAction::Relabel { add, remove } => {
+                let add: Vec<String> = add
+                    .iter()
+                    .filter(|l| !issue.labels.contains(l))
+                    .cloned()
+                    .collect();
+                let remove: Vec<String> = remove
+                    .iter()
+                    .filter(|l| issue.labels.contains(l))
+                    .cloned()
+                    .collect();
+                if add.is_empty() && remove.is_empty() {
+                    continue;
+                }
+                BulkAction::Relabel { add, remove }
+            }
+            action => action.clone(),
+        };
+        operations.push(BulkOperation {
+            number: issue.number,
+            title: issue.title.clone(),
+            action,
+        });
+    }
+    operations.sort_by_key(|o| o.number);
+    operations
+}
+
+fn is_term_match(
+    term: &FilterTerm,
+    issue: &TrackerIssue,
+    test_case: Option<&TestCase>,
+    versions: &[String],
+) -> bool {
+    let values: Vec<String> = match &term.key {
+        FilterKey::Version => versions.to_vec(),
+        FilterKey::State => vec![match issue.state {
+            TrackerIssueState::Open => "open".to_string(),
+            TrackerIssueState::Closed => "closed".to_string(),
+        }],
+        FilterKey::Requirement => test_case
+            .map(|t| {
+                let mut names = vec![t.requirement.name.clone()];
+                names.extend(t.requirement.shortname.clone());
+                names
+            })
+            .unwrap_or_default(),
+        FilterKey::Builder => test_case
+            .map(|t| vec![t.builder_used.name.clone()])
+            .unwrap_or_default(),
+        FilterKey::Permutation(key) => test_case
+            .and_then(|t| t.selected_permutation.get(key).cloned())
+            .into_iter()
+            .collect(),
+    };
+    let is_match = values
+        .iter()
+        .any(|value| term.values.iter().any(|v| v.eq_ignore_ascii_case(value)));
+    is_match != term.is_negated
+}
+
+/// Apply the operations, reporting the first failure once every operation has been tried.
+pub async fn apply_bulk_operations(
+    tracker: &dyn IssueTracker,
+    operations: &[BulkOperation],
+    concurrency: usize,
+) -> Result<()> {
+    let results: Vec<Result<()>> = stream::iter(operations)
+        .map(|operation| async move {
+            match &operation.action {
+                BulkAction::Close => tracker.close_issue(operation.number).await?,
+                BulkAction::Reopen => tracker.reopen_issue(operation.number).await?,
+                BulkAction::Relabel { add, remove } => {
+                    if !add.is_empty() {
+                        tracker.add_labels_to_issue(operation.number, add).await?;
+                    }
+                    for label in remove {
+                        tracker
+                            .remove_label_from_issue(operation.number, label)
+                            .await?;
+                    }
+                }
+                BulkAction::Comment { body } => {
+                    tracker.comment_on_issue(operation.number, body).await?
+                }
+                BulkAction::Lock => tracker.lock_issue(operation.number).await?,
+            }
+            println!("{}", operation);
+            Ok(())
+        })
+        .buffer_unordered(concurrency.max(1))
+        .collect()
+        .await;
+    results.into_iter().collect()
+}
+
+#[cfg(test)]
+mod test_bulk {
+    use super::*;
+    use crate::helpers::parse_issue_filter;
+    use crate::test_utils::{make_test_case, make_test_case_issue};
+    use common::fake_github::FakeGithub;
+    use common::github::Github;
+
+    fn get_numbers(config: &Config, issues: &[TrackerIssue], expression: &str) -> Vec<u64> {
+        let test_cases = vec![
+            make_test_case("login", &[("OS", "Ubuntu")]),
+            make_test_case("login", &[("OS", "Windows")]),
+            make_test_case("logout", &[("OS", "Ubuntu")]),
+        ];
+        let filter = parse_issue_filter(expression).unwrap();
+        get_bulk_operations(config, &test_cases, issues, &filter, &BulkAction::Lock)
+            .iter()
+            .map(|o| o.number)
+            .collect()
+    }
+
+    #[test]
+    fn filters_the_issues() {
+        let config = Config::default();
+        let version = config.workspace_version.clone();
+        let mut issues = vec![
+            make_test_case_issue(1, &make_test_case("login", &[("OS", "Ubuntu")]), &version),
+            make_test_case_issue(2, &make_test_case("login", &[("OS", "Windows")]), &version),
+            make_test_case_issue(3, &make_test_case("logout", &[("OS", "Ubuntu")]), &version),
+            make_test_case_issue(4, &make_test_case("login", &[("OS", "Ubuntu")]), "0.1.0"),
+        ];
+        issues[1].state = TrackerIssueState::Closed;
+
+        assert_eq!(
+            get_numbers(&config, &issues, "requirement=login"),
+            vec![1, 2]
+        );
+        assert_eq!(
+            get_numbers(&config, &issues, "os=ubuntu"),
+            Vec::<u64>::new()
+        );
+        assert_eq!(get_numbers(&config, &issues, "OS=ubuntu"), vec![1, 3]);
+        assert_eq!(
+            get_numbers(&config, &issues, "requirement!=logout,state=closed"),
+            vec![2]
+        );
+        assert_eq!(
+            get_numbers(
+                &config,
+                &issues,
+                "builder=Demo test cases,OS=Ubuntu|Windows"
+            ),
+            vec![1, 2, 3]
+        );
+        // Other versions only match when asked for
+        assert_eq!(
+            get_numbers(&config, &issues, "version=0.1.0,requirement=login"),
+            vec![4]
+        );
+    }
+
+    #[test]
+    fn leaves_out_issues_the_action_does_not_change() {
+        let config = Config::default();
+        let test_case = make_test_case("login", &[("OS", "Ubuntu")]);
+        let mut issues = vec![
+            make_test_case_issue(1, &test_case, &config.workspace_version),
+            make_test_case_issue(2, &test_case, &config.workspace_version),
+        ];
+        issues[1].state = TrackerIssueState::Closed;
+        issues[1].labels.push("a".to_string());
+        let filter = parse_issue_filter("state=open|closed").unwrap();
+        let get_operations = |action: BulkAction| {
+            get_bulk_operations(
+                &config,
+                std::slice::from_ref(&test_case),
+                &issues,
+                &filter,
+                &action,
+            )
+        };
+
+        assert_eq!(get_operations(BulkAction::Close)[0].number, 1);
+        assert_eq!(get_operations(BulkAction::Reopen)[0].number, 2);
+        let operations = get_operations(BulkAction::Relabel {
+            add: vec!["a".to_string()],
+            remove: vec![],
+        });
+        assert_eq!(operations.len(), 1);
+        assert_eq!(
+            operations[0].to_string(),
+            "Relabel issue #1: Issue 1 (add: [\"a\"], remove: [])"
+        );
+        assert_eq!(
+            BulkAction::Relabel {
+                add: vec!["a".to_string()],
+                remove: vec!["b".to_string()],
+            }
+            .describe(2),
+            "add 'a' to and remove 'b' from 2 issues"
+        );
+    }
+
+    #[tokio::test]
+    async fn applies_the_operations_on_github() {
+        let fake_github = FakeGithub::start("owner", "repo").await.unwrap();
+        let first = fake_github.add_issue("First", "", &[]);
+        let second = fake_github.add_issue("Second", "", &[]);
+        let github = Github::new_with_token(fake_github.url(), "owner", "repo", "token").unwrap();
+        let operations = vec![
+            BulkOperation {
+                number: first,
+                title: "First".to_string(),
+                action: BulkAction::Comment {
+                    body: "Retest please".to_string(),
+                },
+            },
+            BulkOperation {
+                number: second,
+                title: "Second".to_string(),
+                action: BulkAction::Lock,
+            },
+        ];
+
+        apply_bulk_operations(&github, &operations, 2)
+            .await
+            .unwrap();
+        assert_eq!(fake_github.comments(first), vec!["Retest please"]);
+        assert_eq!(fake_github.locked_issues(), vec![second]);
+    }
+}
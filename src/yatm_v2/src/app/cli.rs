@@ -1,25 +1,37 @@
+use crate::app::assign::{apply_assignment_plan, get_assignment_plan};
+use crate::app::bulk::{apply_bulk_operations, get_bulk_operations};
+use crate::app::coverage::{get_coverage_matrix, render_coverage_report};
+use crate::app::get_issue_tracker::{get_github, get_issue_tracker, get_offline_issue_tracker};
 use crate::app::init_workspace::init_workspace;
 use crate::app::load_config::load_config;
-use crate::constants::YAML_EXTENSIONS;
+use crate::app::meta_issue::update_meta_issue;
+use crate::app::metrics::{format_metrics, get_metrics};
+use crate::app::metrics_report::{get_burndown, render_metrics_report};
+use crate::app::pull_results::pull_results;
+use crate::app::upload::{
+    apply_upload_plan, check_plan_config, check_remote_state, make_upload_plan,
+};
+use crate::constants::{METRICS_HISTORY_FILE_NAME, UPLOAD_JOURNAL_FILE_NAME, YAML_EXTENSIONS};
 use crate::helpers::{
-    get_files, get_local_issues_matches, get_requirements_from_file, get_test_cases,
-    get_test_cases_builders_from_file, permutation_to_labels, project_version_to_label,
-    test_case_to_markdown, validate_requirements_file, validate_requirements_files,
-    validate_test_cases_builder_file, GithubIssueMatches, IssueMatchType,
+    append_metrics_snapshot, get_files, get_permutation_groups, get_requirements_from_file,
+    get_results_path, get_test_cases, get_test_cases_builders_from_file, parse_issue_filter,
+    read_metrics_history, read_roster, read_upload_journal, test_case_to_markdown,
+    validate_requirements_file, validate_requirements_files, validate_test_cases_builder_file,
+    write_results_file,
+};
+use crate::types::{
+    BulkAction, Config, CoverageFormat, IssueTrackerKind, MetricsFormat, MetricsReportFormat,
+    MetricsSnapshot, OrphanAction, ResultsFormat, TestResult, UploadOptions, UploadPlan,
 };
-use crate::types::LocalIssue;
-use common::github::Github;
 use common::markdown_toc::{prepend_markdown_table_of_contents, TocOptions};
-use common::types::{Link, RequirementsFile, TestCasesBuilderFile};
+use common::types::{Link, RequirementsFile, TestCasesBuilderFile, TrackerIssueState};
 
-use std::collections::HashSet;
+use std::collections::BTreeMap;
 use std::ffi::OsStr;
 use std::path::PathBuf;
 
 use anyhow::{Context, Ok, Result};
 use clap::{Parser, Subcommand};
-use octocrab::models::IssueState;
-use octocrab::params::State;
 
 // Define the main application
 #[derive(Parser)]
@@ -132,20 +144,68 @@ enum TestCasesSubcommands {
 
 #[derive(Subcommand)]
 enum GithubSubcommands {
+    /// Assign the open issues to the testers of a roster, balancing their load
+    Assign {
+        /// The path to the project
+        #[clap(short, long, default_value = ".")]
+        config_path: PathBuf,
+        /// The YAML file of testers and the permutation values they can cover
+        #[clap(short, long)]
+        roster: PathBuf,
+        /// The most open issues a tester can be assigned, unless the roster sets their own
+        #[clap(long)]
+        max_per_tester: Option<usize>,
+        /// Print the assignments without making them
+        #[clap(short = 'n', long)]
+        dry_run: bool,
+    },
+    /// Check who Github authenticates and whether they can write issues
+    AuthCheck {
+        /// The path to the project
+        #[clap(short, long, default_value = ".")]
+        config_path: PathBuf,
+    },
+    /// Report which requirements were tested on which permutations
+    Coverage {
+        /// The path to the project
+        #[clap(short, long, default_value = ".")]
+        config_path: PathBuf,
+        /// How to render the report
+        #[clap(long, value_enum, default_value_t = CoverageFormat::Markdown)]
+        format: CoverageFormat,
+        /// Where to write the report, defaults to the generated files dir
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
     /// Make links to the Github labels
     MakeLabelLinks {
         /// The path to the project
         #[clap(short, long, default_value = ".")]
         config_path: PathBuf,
     },
+    /// Create or update the pinned tracking issue of the workspace version
+    MetaIssue {
+        /// The path to the project
+        #[clap(short, long, default_value = ".")]
+        config_path: PathBuf,
+    },
     /// Get metrics for the project on Github
+    #[clap(args_conflicts_with_subcommands = true)]
     Metrics {
+        #[clap(subcommand)]
+        subcommand: Option<MetricsSubcommands>,
         /// The path to the project
         #[clap(short, long, default_value = ".")]
         config_path: PathBuf,
         /// The label to analyze
         #[clap(short, long)]
         label: Option<String>,
+        /// How to print the breakdown of the issues
+        #[clap(long, value_enum, default_value_t = MetricsFormat::Table)]
+        format: MetricsFormat,
+        /// Save the metrics to the history in the generated files dir
+        #[clap(long)]
+        snapshot: bool,
     },
     /// Preview the test cases in markdown
     Preview {
@@ -153,6 +213,18 @@ enum GithubSubcommands {
         #[clap(short, long, default_value = ".")]
         config_path: PathBuf,
     },
+    /// Pull the test results from the comments, labels and closers of the issues
+    PullResults {
+        /// The path to the project
+        #[clap(short, long, default_value = ".")]
+        config_path: PathBuf,
+        /// How to store the results
+        #[clap(long, value_enum, default_value_t = ResultsFormat::Yaml)]
+        format: ResultsFormat,
+        /// Where to write the results, defaults to the results dir
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
     /// Upload the test cases to Github
     Upload {
         /// The path to the project
@@ -162,6 +234,53 @@ enum GithubSubcommands {
         /// Do not push things remotely
         #[clap(short = 'n', long)]
         dry_run: bool,
+
+        /// Overwrite issues that were edited on Github since the last upload
+        #[clap(short, long)]
+        force: bool,
+
+        /// What to do with open issues for this version that no test case produces anymore
+        #[clap(long, value_enum, default_value_t = OrphanAction::List)]
+        orphans: OrphanAction,
+
+        /// Move the issues over from this workspace version, keeping the results that still hold
+        #[clap(long)]
+        carry_over_from: Option<String>,
+
+        /// Don't ask for confirmation
+        #[clap(short, long, action = clap::ArgAction::SetTrue)]
+        yes: bool,
+    },
+    /// Plan the changes an upload would make and save them to a file
+    Plan {
+        /// The path to the project
+        #[clap(short, long, default_value = ".")]
+        config_path: PathBuf,
+
+        /// Overwrite issues that were edited on Github since the last upload
+        #[clap(short, long)]
+        force: bool,
+
+        /// What to do with open issues for this version that no test case produces anymore
+        #[clap(long, value_enum, default_value_t = OrphanAction::List)]
+        orphans: OrphanAction,
+
+        /// Move the issues over from this workspace version, keeping the results that still hold
+        #[clap(long)]
+        carry_over_from: Option<String>,
+    },
+    /// Apply an upload plan made with the plan command
+    Apply {
+        /// The path to the project
+        #[clap(short, long, default_value = ".")]
+        config_path: PathBuf,
+
+        /// The path to the upload plan file
+        plan_path: PathBuf,
+
+        /// Don't ask for confirmation
+        #[clap(short, long, action = clap::ArgAction::SetTrue)]
+        yes: bool,
     },
     /// Utilities for Github
     Utils {
@@ -176,8 +295,41 @@ enum GithubSubcommands {
     },
 }
 
+#[derive(Subcommand)]
+enum MetricsSubcommands {
+    /// Chart the progress of the workspace version from the metrics history
+    Report {
+        /// The path to the project
+        #[clap(short, long, default_value = ".")]
+        config_path: PathBuf,
+        /// How to render the chart
+        #[clap(long, value_enum, default_value_t = MetricsReportFormat::Html)]
+        format: MetricsReportFormat,
+        /// Where to write the report, defaults to the generated files dir
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
 #[derive(Subcommand)]
 enum GithubUtilsSubcommands {
+    /// Close, reopen, relabel, comment on or lock the issues that match a filter
+    Bulk {
+        /// The path to the project
+        #[clap(short, long, default_value = ".")]
+        config_path: PathBuf,
+        /// The issues to act on, such as `requirement=login,Operating System=Ubuntu 22.04|Windows 11`
+        #[clap(short, long)]
+        filter: String,
+        /// Print the operations without applying them
+        #[clap(short = 'n', long)]
+        dry_run: bool,
+        /// Don't ask for confirmation
+        #[clap(short, long, action = clap::ArgAction::SetTrue)]
+        yes: bool,
+        #[clap(subcommand)]
+        action: BulkAction,
+    },
     /// Close issues that have a matching label
     CloseIssues {
         /// The path to the project
@@ -378,6 +530,31 @@ pub async fn cli() -> Result<()> {
             }
         },
         Commands::Github { subcommand } => match subcommand {
+            GithubSubcommands::AuthCheck { config_path } => {
+                let config = load_config(&config_path)?;
+                if config.issue_tracker != IssueTrackerKind::Github {
+                    anyhow::bail!(
+                        "The issue tracker is {:?}, set `issue_tracker: github` to check the Github authentication",
+                        config.issue_tracker
+                    );
+                }
+                let github = get_github(&config).await?;
+
+                let auth_check = github.check_auth().await?;
+                println!("Authenticated as {}", auth_check.identity);
+                if !auth_check.can_write_issues {
+                    anyhow::bail!(
+                        "{} can't write issues in {}/{}",
+                        auth_check.identity,
+                        config.repo_owner,
+                        config.repo_name
+                    );
+                }
+                println!(
+                    "Can write issues in {}/{} 🚀",
+                    config.repo_owner, config.repo_name
+                );
+            }
             GithubSubcommands::Validate { config_path } => {
                 let config = load_config(&config_path)?;
 
@@ -449,9 +626,9 @@ pub async fn cli() -> Result<()> {
                 };
                 file_contents =
                     prepend_markdown_table_of_contents(&file_contents, Some(&toc_options));
-                let gh = Github::new(&config.repo_owner, &config.repo_name)?;
+                let tracker = get_offline_issue_tracker(&config).await?;
 
-                file_contents = gh.prepend_github_info(&file_contents);
+                file_contents = tracker.prepend_tracker_info(&file_contents);
 
                 // Write the test cases to a file
                 let datetime_string = chrono::Local::now().format("%Y-%m-%d-%H-%M-%S").to_string();
@@ -474,134 +651,253 @@ pub async fn cli() -> Result<()> {
                 ))?;
                 println!("Created the test cases preview file: {:?}", output_path);
             }
-            GithubSubcommands::Upload {
+            GithubSubcommands::Assign {
                 config_path,
+                roster,
+                max_per_tester,
                 dry_run,
             } => {
                 let config = load_config(&config_path)?;
-
-                // Get the test cases
                 let test_cases = get_test_cases(&config)?;
-                if test_cases.is_empty() {
-                    anyhow::bail!("No test cases found");
+                let roster = read_roster(&roster)?;
+                let tracker = get_issue_tracker(&config).await?;
+
+                let issues = tracker.get_issues(Some(TrackerIssueState::Open)).await?;
+                let plan =
+                    get_assignment_plan(&config, &test_cases, &issues, &roster, max_per_tester);
+                for issue in &plan.unassigned {
+                    println!("{}", issue);
                 }
-
-                // Convert the test cases to markdown
-                let local_issues = test_cases
-                    .iter()
-                    .map(|test_case| {
-                        test_case_to_markdown(test_case.clone(), &config.workspace_version).context(
-                            format!("Failed to convert test case to markdown: {:?}", test_case),
-                        )
-                    })
-                    .collect::<Result<Vec<_>>>()?;
-                let local_issues_count = local_issues.len();
-                println!("{} test cases total", local_issues_count);
-
-                // Get the issues from Github
-                let gh = Github::new(&config.repo_owner, &config.repo_name)?;
-                println!(
-                    "Connecting to repository: {}/{}",
-                    config.repo_owner, config.repo_name
-                );
-                let github_issues = gh.get_issues(Some(State::All)).await?;
-
-                let matched_issues = get_local_issues_matches(&local_issues, &github_issues);
-                for i in &matched_issues {
-                    match i.match_type {
-                        IssueMatchType::Match => println!(
-                            "Matched: {} -- {}",
-                            i.local_issue.title,
-                            i.github_issue
-                                .as_ref()
-                                .map_or("No URL for issue".to_string(), |issue| issue
-                                    .html_url
-                                    .to_string())
-                        ),
-                        IssueMatchType::MatchedWithDiff => println!(
-                            "Changed: {} -- {}",
-                            i.local_issue.title,
-                            i.github_issue
-                                .as_ref()
-                                .map_or("No URL for issue".to_string(), |issue| issue
-                                    .html_url
-                                    .to_string())
-                        ),
-                        IssueMatchType::Missing => println!("Missing: {}", i.local_issue.title),
-                        #[allow(unreachable_patterns)]
-                        _ => println!("Unknown match type for {}", i.local_issue.title),
+                if dry_run {
+                    for assignment in &plan.assignments {
+                        println!("{}", assignment);
                     }
+                } else {
+                    apply_assignment_plan(tracker.as_ref(), &plan, config.concurrency).await?;
                 }
-
-                // Create issues that don't exist on Github
-                let unmatched_local_issues: Vec<LocalIssue> = matched_issues
-                    .iter()
-                    .filter(|m| m.match_type == IssueMatchType::Missing)
-                    .map(|m| m.local_issue.clone())
-                    .collect();
-                let unmatched_local_issues_count = unmatched_local_issues.len();
-                println!("{} test cases without issues", unmatched_local_issues_count);
-                for i in &unmatched_local_issues {
-                    println!("Unmatched: {}", i.title);
+                println!();
+                for (tester, load) in &plan.loads {
+                    println!("@{}: {} open issues", tester, load);
                 }
-                for issue in unmatched_local_issues {
-                    println!("Creating issue: {}", issue.title);
-                    if dry_run {
-                        println!("Dry run skipping update.");
-                    } else {
-                        gh.create_issue(issue.title, issue.text_body, issue.labels)
-                            .await?;
+                match dry_run {
+                    true => println!(
+                        "Dry run skipping {} assignments, {} issues can't be assigned.",
+                        plan.assignments.len(),
+                        plan.unassigned.len()
+                    ),
+                    false => println!(
+                        "Assigned {} issues, {} issues can't be assigned.",
+                        plan.assignments.len(),
+                        plan.unassigned.len()
+                    ),
+                }
+            }
+            GithubSubcommands::PullResults {
+                config_path,
+                format,
+                output,
+            } => {
+                let config = load_config(&config_path)?;
+                let tracker = get_issue_tracker(&config).await?;
+
+                let results = pull_results(&config, tracker.as_ref()).await?;
+                let output_path = output.unwrap_or(get_results_path(
+                    &config.results_dir,
+                    &config.workspace_version,
+                    format,
+                ));
+                write_results_file(&output_path, &results, format)?;
+
+                let mut counts: BTreeMap<TestResult, usize> = BTreeMap::new();
+                for result in &results.results {
+                    *counts.entry(result.result).or_default() += 1;
+                }
+                for (result, count) in counts {
+                    println!("{:?}: {}", result, count);
+                }
+                for rate in &results.step_pass_rates {
+                    println!(
+                        "{} - step {}: {}/{} passed ({:.2}%)",
+                        rate.requirement,
+                        rate.step,
+                        rate.passed,
+                        rate.reported,
+                        rate.percent_passed
+                    );
+                }
+                println!("Saved the results to {:?}", output_path);
+            }
+            GithubSubcommands::Upload {
+                config_path,
+                dry_run,
+                force,
+                orphans,
+                carry_over_from,
+                yes: is_confirmed,
+            } => {
+                let config = load_config(&config_path)?;
+                let tracker = get_issue_tracker(&config).await?;
+                let journal_path = get_upload_journal_path(&config)?;
+                let journal = read_upload_journal(&journal_path)?;
+
+                let plan = make_upload_plan(
+                    &config,
+                    tracker.as_ref(),
+                    &UploadOptions {
+                        force,
+                        orphans,
+                        carry_over_from,
+                    },
+                    &journal,
+                )
+                .await?;
+                if dry_run {
+                    for operation in &plan.operations {
+                        println!("{}", operation);
                     }
+                    println!("Dry run skipping {} operations.", plan.operations.len());
+                    return Ok(());
                 }
-                println!("{} issues created", unmatched_local_issues_count);
+                if plan.changes_existing_issues() && !is_confirmed {
+                    for operation in &plan.operations {
+                        println!("{}", operation);
+                    }
+                    confirm(&format!(
+                        "Are you sure you want to apply {} operations to {}/{}?",
+                        plan.operations.len(),
+                        plan.repo_owner,
+                        plan.repo_name
+                    ))?;
+                }
+                apply_upload_plan(tracker.as_ref(), &plan, &journal_path, config.concurrency)
+                    .await?;
 
-                // Edit  issues that don't exactly match
-                let changed_issues: Vec<&GithubIssueMatches> = matched_issues
-                    .iter()
-                    .filter(|m| m.match_type == IssueMatchType::MatchedWithDiff)
-                    .collect();
-                let changed_local_issues_count = changed_issues.len();
-                println!(
-                    "{} test cases with changed issues",
-                    changed_local_issues_count
-                );
+                println!("Done 🚀");
+            }
+            GithubSubcommands::Plan {
+                config_path,
+                force,
+                orphans,
+                carry_over_from,
+            } => {
+                let config = load_config(&config_path)?;
+                let tracker = get_issue_tracker(&config).await?;
+                let journal = read_upload_journal(&get_upload_journal_path(&config)?)?;
+
+                let plan = make_upload_plan(
+                    &config,
+                    tracker.as_ref(),
+                    &UploadOptions {
+                        force,
+                        orphans,
+                        carry_over_from,
+                    },
+                    &journal,
+                )
+                .await?;
+                for operation in &plan.operations {
+                    println!("{}", operation);
+                }
+                println!("{} operations planned", plan.operations.len());
 
-                for m in &changed_issues {
+                // Write the plan to a file
+                let datetime_string = chrono::Local::now().format("%Y-%m-%d-%H-%M-%S").to_string();
+                let output_file_name = format!("upload-plan-{}.yaml", datetime_string);
+                let output_path = config.generated_files_dir.join(output_file_name);
+                std::fs::create_dir_all(&config.generated_files_dir).context(format!(
+                    "Failed to create generated files dir: {:?}",
+                    config.generated_files_dir
+                ))?;
+                let plan = serde_yaml::to_string(&plan)
+                    .context("Failed to turn the upload plan into a string")?;
+                std::fs::write(&output_path, plan).context(format!(
+                    "Failed to write the upload plan file: {:?}",
+                    output_path
+                ))?;
+                println!("Created the upload plan file: {:?}", output_path);
+            }
+            GithubSubcommands::Apply {
+                config_path,
+                plan_path,
+                yes: is_confirmed,
+            } => {
+                let config = load_config(&config_path)?;
+                let plan = std::fs::read_to_string(&plan_path)
+                    .context(format!("No upload plan file found: {:?}", plan_path))?;
+                let plan = serde_yaml::from_str::<UploadPlan>(&plan).context(format!(
+                    "Failed to deserialize the upload plan: {:?}",
+                    plan_path
+                ))?;
+                check_plan_config(&config, &plan)?;
+                let tracker = get_issue_tracker(&config).await?;
+                let journal_path = get_upload_journal_path(&config)?;
+
+                // A plan that was partly applied already changed the issues it touched
+                let journal = read_upload_journal(&journal_path)?;
+                if journal.iter().any(|e| e.is_of_plan(&plan)) {
                     println!(
-                        "Updating issue: {}, {}",
-                        m.local_issue.title,
-                        m.github_issue.as_ref().unwrap().html_url
+                        "Resuming the upload plan from the journal: {:?}",
+                        journal_path
                     );
-                    if dry_run {
-                        println!("Dry run skipping update.");
-                    } else {
-                        gh.update_issue(
-                            m.github_issue.clone().unwrap().number,
-                            m.local_issue.title.clone(),
-                            m.local_issue.text_body.clone(),
-                        )
-                        .await?; //, issue.labels
+                }
+                check_remote_state(tracker.as_ref(), &plan, &journal).await?;
+                if !is_confirmed {
+                    for operation in &plan.operations {
+                        println!("{}", operation);
                     }
+                    confirm(&format!(
+                        "Are you sure you want to apply {} operations to {}/{}?",
+                        plan.operations.len(),
+                        plan.repo_owner,
+                        plan.repo_name
+                    ))?;
                 }
+                apply_upload_plan(tracker.as_ref(), &plan, &journal_path, config.concurrency)
+                    .await?;
 
                 println!("Done 🚀");
             }
+            GithubSubcommands::Coverage {
+                config_path,
+                format,
+                output,
+            } => {
+                let config = load_config(&config_path)?;
+                let test_cases = get_test_cases(&config)?;
+                let tracker = get_issue_tracker(&config).await?;
+
+                let issues = tracker.get_issues(None).await?;
+                let matrix = get_coverage_matrix(&config, &test_cases, &issues);
+                let report = render_coverage_report(&config, &matrix, format)?;
+
+                let output_path = match output {
+                    Some(output) => output,
+                    None => {
+                        std::fs::create_dir_all(&config.generated_files_dir).context(format!(
+                            "Failed to create generated files dir: {:?}",
+                            config.generated_files_dir
+                        ))?;
+                        config.generated_files_dir.join(format!(
+                            "coverage-{}.{}",
+                            config.workspace_version,
+                            format.extension()
+                        ))
+                    }
+                };
+                std::fs::write(&output_path, report).context(format!(
+                    "Failed to write the coverage report: {:?}",
+                    output_path
+                ))?;
+                println!("{}", matrix.summary());
+                println!("Created the coverage report: {:?}", output_path);
+            }
             GithubSubcommands::MakeLabelLinks { config_path } => {
                 let config = load_config(&config_path)?;
                 let test_cases = get_test_cases(&config)?;
-                let mut permutations: HashSet<Vec<String>> = HashSet::new();
-                for test_case in &test_cases {
-                    permutations.insert(permutation_to_labels(&test_case.selected_permutation));
-                }
+                let tracker = get_offline_issue_tracker(&config).await?;
                 let mut links: Vec<Link> = vec![];
-                for permutation in permutations {
-                    let mut url = format!(
-                        "https://github.com/{}/{}/issues?q=is:issue+is:open",
-                        config.repo_owner, config.repo_name
-                    );
-                    for label in &permutation {
-                        url += &format!("+label:%22{}%22", label.replace(" ", "+"));
-                    }
+                for permutation in get_permutation_groups(&test_cases) {
+                    let url = tracker.get_label_link(&permutation);
                     let text = &permutation
                         .iter()
                         .map(|l| format!("`{}`", l))
@@ -628,130 +924,248 @@ pub async fn cli() -> Result<()> {
                     output_path
                 ))?;
             }
-            GithubSubcommands::Metrics { config_path, label } => {
+            GithubSubcommands::MetaIssue { config_path } => {
                 let config = load_config(&config_path)?;
-                let project_version = project_version_to_label(&config.workspace_version);
-
-                let gh = Github::new(&config.repo_owner, &config.repo_name)?;
-
-                let issues = gh.get_issues(Some(State::All)).await?;
-                let issues = issues
-                    .iter()
-                    .filter(|i| {
-                        let is_version = i
-                            .labels
-                            .iter()
-                            .any(|gh_label| gh_label.name == project_version);
-                        let is_label_of_interest = match &label {
-                            Some(label) => i.labels.iter().any(|l| l.name == *label),
-                            None => true,
-                        };
-                        is_version && is_label_of_interest
-                    })
-                    .collect::<Vec<_>>();
-                if issues.is_empty() {
-                    println!("No issues found");
-                } else {
-                    let closed_issues = issues
-                        .iter()
-                        .filter(|i| i.state == IssueState::Closed)
-                        .collect::<Vec<_>>();
+                let tracker = get_issue_tracker(&config).await?;
 
-                    println!(
-                        "{}/{} issues closed: {:.2}%",
-                        closed_issues.len(),
-                        issues.len(),
-                        (closed_issues.len() as f64 / issues.len() as f64) * 100.0
-                    );
-                }
+                update_meta_issue(&config, tracker.as_ref()).await?;
             }
-            GithubSubcommands::Utils { subcommand } => {
-                match subcommand {
-                    GithubUtilsSubcommands::CloseIssues {
+            GithubSubcommands::Metrics {
+                subcommand:
+                    Some(MetricsSubcommands::Report {
                         config_path,
-                        label,
-                        yes: is_confirmed,
-                    } => {
-                        let config = load_config(&config_path)?;
-                        let gh = Github::new(&config.repo_owner, &config.repo_name)?;
-
-                        if !is_confirmed {
-                            let mut input = String::new();
-                            println!("Are you sure you want to delete all of the existing labels? (yes/no)");
-                            std::io::stdin()
-                                .read_line(&mut input)
-                                .context("Failed to read the user input")?;
-                            if input.trim() != "yes" {
-                                anyhow::bail!("The user did not confirm");
-                            }
-                        }
+                        format,
+                        output,
+                    }),
+                ..
+            } => {
+                let config = load_config(&config_path)?;
+                let tracker = get_issue_tracker(&config).await?;
+
+                let issues = tracker.get_issues(None).await?;
+                let snapshots = read_metrics_history(
+                    &config.generated_files_dir.join(METRICS_HISTORY_FILE_NAME),
+                )?;
+                let points = get_burndown(&config, &snapshots, &issues, chrono::Utc::now())?;
+                let report = render_metrics_report(&config, &points, format)?;
+
+                let output_path = match output {
+                    Some(output) => output,
+                    None => {
+                        std::fs::create_dir_all(&config.generated_files_dir).context(format!(
+                            "Failed to create generated files dir: {:?}",
+                            config.generated_files_dir
+                        ))?;
+                        config.generated_files_dir.join(format!(
+                            "metrics-report-{}.{}",
+                            config.workspace_version,
+                            format.extension()
+                        ))
+                    }
+                };
+                std::fs::write(&output_path, report).context(format!(
+                    "Failed to write the metrics report: {:?}",
+                    output_path
+                ))?;
+                println!("Created the metrics report: {:?}", output_path);
+            }
+            GithubSubcommands::Metrics {
+                subcommand: None,
+                config_path,
+                label,
+                format,
+                snapshot,
+            } => {
+                let config = load_config(&config_path)?;
+                let test_cases = get_test_cases(&config)?;
+
+                let tracker = get_issue_tracker(&config).await?;
+
+                let issues = tracker.get_issues(None).await?;
+                let rows = get_metrics(&config, &test_cases, &issues, label.as_ref());
+                if snapshot {
+                    std::fs::create_dir_all(&config.generated_files_dir).context(format!(
+                        "Failed to create generated files dir: {:?}",
+                        config.generated_files_dir
+                    ))?;
+                    append_metrics_snapshot(
+                        &config.generated_files_dir.join(METRICS_HISTORY_FILE_NAME),
+                        &MetricsSnapshot {
+                            timestamp: chrono::Utc::now().to_rfc3339(),
+                            workspace_version: config.workspace_version.clone(),
+                            label: label.clone(),
+                            rows: rows.clone(),
+                        },
+                    )?;
+                }
+                match rows.iter().find(|r| r.group == "all") {
+                    None if format == MetricsFormat::Table => println!("No issues found"),
+                    Some(all) if format == MetricsFormat::Table => {
+                        println!(
+                            "{}/{} issues closed: {:.2}%",
+                            all.closed, all.total, all.percent_closed
+                        );
+                        println!();
+                        print!("{}", format_metrics(&rows, format)?);
+                    }
+                    _ => print!("{}", format_metrics(&rows, format)?),
+                }
+            }
+            GithubSubcommands::Utils { subcommand } => match subcommand {
+                GithubUtilsSubcommands::CloseIssues {
+                    config_path,
+                    label,
+                    yes: is_confirmed,
+                } => {
+                    let config = load_config(&config_path)?;
+                    let tracker = get_issue_tracker(&config).await?;
+
+                    let issues: Vec<_> = tracker
+                        .get_issues(Some(TrackerIssueState::Open))
+                        .await?
+                        .into_iter()
+                        .filter(|i| i.labels.contains(&label))
+                        .collect();
+                    if !is_confirmed {
+                        confirm(&format!(
+                            "Are you sure you want to close the {} open issues with the label '{}' in {}/{}?",
+                            issues.len(),
+                            label,
+                            config.repo_owner,
+                            config.repo_name
+                        ))?;
+                    }
 
-                        let issues = gh.get_issues(Some(octocrab::params::State::Open)).await?;
-                        for issue in issues {
-                            if issue.labels.iter().any(|gh_label| gh_label.name == label) {
-                                println!("Closing issue: {}", &issue.title);
-                                gh.close_issue(&issue).await?;
-                            }
+                    for issue in issues {
+                        println!("Closing issue: {}", &issue.title);
+                        tracker.close_issue(issue.number).await?;
+                    }
+                }
+                GithubUtilsSubcommands::Bulk {
+                    config_path,
+                    filter,
+                    dry_run,
+                    yes: is_confirmed,
+                    action,
+                } => {
+                    match &action {
+                        BulkAction::Relabel { add, remove }
+                            if add.is_empty() && remove.is_empty() =>
+                        {
+                            anyhow::bail!(
+                                "Give the labels to add with --add or to remove with --remove"
+                            )
+                        }
+                        BulkAction::Comment { body } if body.trim().is_empty() => {
+                            anyhow::bail!("The comment can't be empty")
                         }
+                        _ => {}
+                    }
+                    let config = load_config(&config_path)?;
+                    let filter = parse_issue_filter(&filter)?;
+                    let test_cases = get_test_cases(&config)?;
+                    let tracker = get_issue_tracker(&config).await?;
+
+                    let issues = tracker.get_issues(None).await?;
+                    let operations =
+                        get_bulk_operations(&config, &test_cases, &issues, &filter, &action);
+                    if operations.is_empty() {
+                        println!("No issues match the filter or need changing");
+                        return Ok(());
                     }
-                    GithubUtilsSubcommands::ListLabels { config_path } => {
-                        let config = load_config(&config_path)?;
-                        let gh = Github::new(&config.repo_owner, &config.repo_name)?;
-
-                        let labels = gh.get_labels().await?;
-                        println!("Labels:");
-                        for label in labels {
-                            if let Some(description) = label.description {
-                                println!("- {}: {}", label.name, description);
-                            } else {
-                                println!("- {}", label.name);
-                            }
+                    if dry_run || !is_confirmed {
+                        for operation in &operations {
+                            println!("{}", operation);
                         }
                     }
-                    GithubUtilsSubcommands::DeleteAllLabels {
-                        config_path,
-                        yes: is_confirmed,
-                    } => {
-                        let config = load_config(&config_path)?;
-                        let gh = Github::new(&config.repo_owner, &config.repo_name)?;
-
-                        if !is_confirmed {
-                            let mut input = String::new();
-                            println!("Are you sure you want to delete all of the existing labels? (yes/no)");
-                            std::io::stdin()
-                                .read_line(&mut input)
-                                .context("Failed to read the user input")?;
-                            if input.trim() != "yes" {
-                                anyhow::bail!("The user did not confirm");
-                            }
+                    if dry_run {
+                        println!("Dry run skipping {} operations.", operations.len());
+                        return Ok(());
+                    }
+                    if !is_confirmed {
+                        confirm(&format!(
+                            "Are you sure you want to {} in {}/{}?",
+                            action.describe(operations.len()),
+                            config.repo_owner,
+                            config.repo_name
+                        ))?;
+                    }
+                    apply_bulk_operations(tracker.as_ref(), &operations, config.concurrency)
+                        .await?;
+                }
+                GithubUtilsSubcommands::ListLabels { config_path } => {
+                    let config = load_config(&config_path)?;
+                    let tracker = get_issue_tracker(&config).await?;
+
+                    let labels = tracker.get_labels().await?;
+                    println!("Labels:");
+                    for label in labels {
+                        if let Some(description) = label.description {
+                            println!("- {}: {}", label.name, description);
+                        } else {
+                            println!("- {}", label.name);
                         }
-
-                        gh.delete_labels().await?;
-                        println!("Done 🚀");
                     }
-                    GithubUtilsSubcommands::CreateLabels { config_path } => {
-                        let config = load_config(&config_path)?;
-                        let gh = Github::new(&config.repo_owner, &config.repo_name)?;
-
-                        gh.create_labels(config.labels).await?;
-                        println!("Done 🚀");
+                }
+                GithubUtilsSubcommands::DeleteAllLabels {
+                    config_path,
+                    yes: is_confirmed,
+                } => {
+                    let config = load_config(&config_path)?;
+                    let tracker = get_issue_tracker(&config).await?;
+
+                    if !is_confirmed {
+                        confirm("Are you sure you want to delete all of the existing labels?")?;
                     }
+
+                    tracker.delete_labels().await?;
+                    println!("Done 🚀");
                 }
-            }
+                GithubUtilsSubcommands::CreateLabels { config_path } => {
+                    let config = load_config(&config_path)?;
+                    let tracker = get_issue_tracker(&config).await?;
+
+                    tracker.create_labels(config.labels).await?;
+                    println!("Done 🚀");
+                }
+            },
         },
     }
     Ok(())
 }
 
+/// Get the path to the upload journal, making sure its directory exists.
+fn get_upload_journal_path(config: &Config) -> Result<PathBuf> {
+    std::fs::create_dir_all(&config.generated_files_dir).context(format!(
+        "Failed to create generated files dir: {:?}",
+        config.generated_files_dir
+    ))?;
+    Ok(config.generated_files_dir.join(UPLOAD_JOURNAL_FILE_NAME))
+}
+
+/// Ask the user to confirm with "yes", failing if they don't.
+fn confirm(question: &str) -> Result<()> {
+    let mut input = String::new();
+    println!("{} (yes/no)", question);
+    std::io::stdin()
+        .read_line(&mut input)
+        .context("Failed to read the user input")?;
+    if input.trim() != "yes" {
+        anyhow::bail!("The user did not confirm");
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod test_cli {
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
     use assert_cmd::Command;
     use predicates::prelude::predicate;
     use tempfile::tempdir;
 
     use crate::app::load_config::load_config;
+    use crate::helpers::{get_test_cases, project_version_to_label};
 
     fn get_command() -> Command {
         Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap()
@@ -779,7 +1193,7 @@ mod test_cli {
 
         // run the init command
         let mut cmd = get_command();
-        cmd.args(&["init", "--path", dir.to_str().unwrap()])
+        cmd.args(["init", "--path", dir.to_str().unwrap()])
             .assert()
             .success()
             .stdout(predicate::str::contains("Created a YATM workspace in"));
@@ -802,7 +1216,7 @@ mod test_cli {
 
         // run the init command
         let mut cmd = get_command();
-        cmd.args(&["init", "--path", dir.to_str().unwrap()])
+        cmd.args(["init", "--path", dir.to_str().unwrap()])
             .assert()
             .success();
 
@@ -813,7 +1227,7 @@ mod test_cli {
         // run the requirements new command
         let new_requirements_file_name = "my-test-requirements.yaml";
         let mut cmd = get_command();
-        cmd.args(&[
+        cmd.args([
             "requirements",
             "new",
             "--config-path",
@@ -830,7 +1244,7 @@ mod test_cli {
 
         // run the requirements list command
         let mut cmd = get_command();
-        cmd.args(&[
+        cmd.args([
             "requirements",
             "list",
             "--config-path",
@@ -841,7 +1255,7 @@ mod test_cli {
 
         // run the requirements validate command
         let mut cmd = get_command();
-        cmd.args(&[
+        cmd.args([
             "requirements",
             "validate",
             "--config-path",
@@ -852,7 +1266,7 @@ mod test_cli {
 
         // run the requirements validate file command
         let mut cmd = get_command();
-        cmd.args(&[
+        cmd.args([
             "requirements",
             "validate-file",
             "--file-path",
@@ -868,7 +1282,7 @@ mod test_cli {
 
         // run the init command
         let mut cmd = get_command();
-        cmd.args(&["init", "--path", dir.to_str().unwrap()])
+        cmd.args(["init", "--path", dir.to_str().unwrap()])
             .assert()
             .success();
 
@@ -879,7 +1293,7 @@ mod test_cli {
         // run the test cases new command
         let new_test_cases_builder_file_name = "my-test-test-cases-builder.yaml";
         let mut cmd = get_command();
-        cmd.args(&[
+        cmd.args([
             "test-cases",
             "new",
             "--config-path",
@@ -900,13 +1314,13 @@ mod test_cli {
 
         // run the test cases list command
         let mut cmd = get_command();
-        cmd.args(&["test-cases", "list", "--config-path", dir.to_str().unwrap()])
+        cmd.args(["test-cases", "list", "--config-path", dir.to_str().unwrap()])
             .assert()
             .success();
 
         // run the test cases validate command
         let mut cmd = get_command();
-        cmd.args(&[
+        cmd.args([
             "test-cases",
             "validate",
             "--config-path",
@@ -917,7 +1331,7 @@ mod test_cli {
 
         // fail on validating a bad directory
         let mut cmd = get_command();
-        cmd.args(&[
+        cmd.args([
             "test-cases",
             "validate",
             "--config-path",
@@ -928,7 +1342,7 @@ mod test_cli {
 
         // run the test cases validate file command
         let mut cmd = get_command();
-        cmd.args(&[
+        cmd.args([
             "test-cases",
             "validate-file",
             "--file-path",
@@ -940,7 +1354,7 @@ mod test_cli {
         // run the test cases preview command
         assert_eq!(get_number_of_files_in_dir(&config.generated_files_dir), 0);
         let mut cmd = get_command();
-        cmd.args(&[
+        cmd.args([
             "test-cases",
             "preview",
             "--config-path",
@@ -951,6 +1365,465 @@ mod test_cli {
         assert_eq!(get_number_of_files_in_dir(&config.generated_files_dir), 1);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_github_auth_check() {
+        let fake_github = common::fake_github::FakeGithub::start("repo_owner", "repo_name")
+            .await
+            .unwrap();
+        let dir = tempdir().unwrap().path().to_path_buf();
+
+        // run the init command and read the token from a file next to the config
+        let mut cmd = get_command();
+        cmd.args(["init", "--path", dir.to_str().unwrap()])
+            .assert()
+            .success();
+        let config_path = dir.join("config.yaml");
+        let config_file = std::fs::read_to_string(&config_path).unwrap().replace(
+            "github_auth:\n  method: env\n",
+            &format!(
+                "github_api_url: {}\ngithub_auth:\n  method: token_file\n  path: token.txt\n",
+                fake_github.url()
+            ),
+        );
+        std::fs::write(&config_path, config_file).unwrap();
+        std::fs::write(dir.join("token.txt"), "file-token\n").unwrap();
+
+        let mut cmd = get_command();
+        cmd.env_remove("GITHUB_TOKEN")
+            .args([
+                "github",
+                "auth-check",
+                "--config-path",
+                dir.to_str().unwrap(),
+            ])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Authenticated as fake-user"))
+            .stdout(predicate::str::contains(
+                "Can write issues in repo_owner/repo_name",
+            ));
+        assert_eq!(
+            fake_github.requests()[0].get_header("authorization"),
+            Some("Bearer file-token")
+        );
+
+        // a read-only token fails the check
+        fake_github.set_read_only(true);
+        let mut cmd = get_command();
+        cmd.args([
+            "github",
+            "auth-check",
+            "--config-path",
+            dir.to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "fake-user can't write issues in repo_owner/repo_name",
+        ));
+    }
+
+    #[test]
+    fn test_github_preview_without_a_token() {
+        let dir = tempdir().unwrap().path().to_path_buf();
+
+        // run the init command
+        let mut cmd = get_command();
+        cmd.args(["init", "--path", dir.to_str().unwrap()])
+            .assert()
+            .success();
+        let config = load_config(&dir).unwrap();
+
+        // previewing and making links don't connect to Github
+        let mut cmd = get_command();
+        cmd.env_remove("GITHUB_TOKEN")
+            .args(["github", "preview", "--config-path", dir.to_str().unwrap()])
+            .assert()
+            .success();
+        assert_eq!(get_number_of_files_in_dir(&config.generated_files_dir), 1);
+        let mut cmd = get_command();
+        cmd.env_remove("GITHUB_TOKEN")
+            .args([
+                "github",
+                "make-label-links",
+                "--config-path",
+                dir.to_str().unwrap(),
+            ])
+            .assert()
+            .success();
+        assert_eq!(get_number_of_files_in_dir(&config.generated_files_dir), 2);
+    }
+
+    /// Make a workspace that keeps its issues in a local directory, optionally uploading the test
+    /// cases to it.
+    fn init_local_workspace(upload: bool) -> PathBuf {
+        let dir = tempdir().unwrap().path().to_path_buf();
+
+        let mut cmd = get_command();
+        cmd.args(["init", "--path", dir.to_str().unwrap()])
+            .assert()
+            .success();
+        let config_path = dir.join("config.yaml");
+        let config_file = std::fs::read_to_string(&config_path).unwrap().replace(
+            "issue_tracker: github\n",
+            "issue_tracker: local\nlocal_tracker_dir: tracker\n",
+        );
+        std::fs::write(&config_path, config_file).unwrap();
+
+        if upload {
+            let mut cmd = get_command();
+            cmd.args(["github", "upload", "--config-path", dir.to_str().unwrap()])
+                .assert()
+                .success();
+        }
+        dir
+    }
+
+    /// Close every issue of the workspace version in a local workspace.
+    fn close_local_issues(dir: &Path) {
+        let config = load_config(dir).unwrap();
+        let mut cmd = get_command();
+        cmd.args([
+            "github",
+            "utils",
+            "close-issues",
+            "--config-path",
+            dir.to_str().unwrap(),
+            "--label",
+            &project_version_to_label(&config.workspace_version),
+            "--yes",
+        ])
+        .assert()
+        .success();
+    }
+
+    #[test]
+    fn test_local_tracker_upload() {
+        let dir = init_local_workspace(false);
+        let config = load_config(&dir).unwrap();
+        let issues_dir = dir.join("tracker").join("issues");
+        let number_of_test_cases = get_test_cases(&config).unwrap().len();
+
+        // upload the test cases
+        let mut cmd = get_command();
+        cmd.args(["github", "upload", "--config-path", dir.to_str().unwrap()])
+            .assert()
+            .success();
+        assert_eq!(
+            get_number_of_files_in_dir(&issues_dir),
+            number_of_test_cases
+        );
+        assert!(dir.join("tracker").join("labels.yaml").is_file());
+
+        // uploading again changes nothing
+        let mut cmd = get_command();
+        cmd.args(["github", "upload", "--config-path", dir.to_str().unwrap()])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("0 test cases without issues"))
+            .stdout(predicate::str::contains("0 test cases with changed issues"));
+        assert_eq!(
+            get_number_of_files_in_dir(&issues_dir),
+            number_of_test_cases
+        );
+
+        // an issue edited by hand is only overwritten when forced to
+        let issue_path = issues_dir.join("1.yaml");
+        let mut issue: serde_yaml::Value =
+            serde_yaml::from_str(&std::fs::read_to_string(&issue_path).unwrap()).unwrap();
+        let body = format!("Edited by hand\n\n{}", issue["body"].as_str().unwrap());
+        issue["body"] = serde_yaml::Value::from(body);
+        std::fs::write(&issue_path, serde_yaml::to_string(&issue).unwrap()).unwrap();
+        let mut cmd = get_command();
+        cmd.args(["github", "upload", "--config-path", dir.to_str().unwrap()])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("0 test cases with changed issues"));
+
+        // changing an existing issue is only done once confirmed
+        let mut cmd = get_command();
+        cmd.args([
+            "github",
+            "upload",
+            "--force",
+            "--config-path",
+            dir.to_str().unwrap(),
+        ])
+        .write_stdin("no\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("The user did not confirm"));
+        assert!(std::fs::read_to_string(&issue_path)
+            .unwrap()
+            .contains("Edited by hand"));
+        let mut cmd = get_command();
+        cmd.args([
+            "github",
+            "upload",
+            "--force",
+            "--yes",
+            "--config-path",
+            dir.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 test cases with changed issues"));
+        assert!(std::fs::read_to_string(&issue_path)
+            .unwrap()
+            .contains("yatm-id"));
+    }
+
+    #[test]
+    fn test_local_tracker_plan_and_apply() {
+        let dir = init_local_workspace(false);
+        let config = load_config(&dir).unwrap();
+        let issues_dir = dir.join("tracker").join("issues");
+        let number_of_test_cases = get_test_cases(&config).unwrap().len();
+
+        // plan the upload without changing the issues
+        let mut cmd = get_command();
+        cmd.args(["github", "plan", "--config-path", dir.to_str().unwrap()])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Created the upload plan file"));
+        assert_eq!(get_number_of_files_in_dir(&issues_dir), 0);
+        let plan_path = std::fs::read_dir(&config.generated_files_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| {
+                path.file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .starts_with("upload-plan-")
+            })
+            .unwrap();
+
+        // apply the plan
+        let mut cmd = get_command();
+        cmd.args([
+            "github",
+            "apply",
+            "--config-path",
+            dir.to_str().unwrap(),
+            "--yes",
+            plan_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+        assert_eq!(
+            get_number_of_files_in_dir(&issues_dir),
+            number_of_test_cases
+        );
+    }
+
+    #[test]
+    fn test_local_tracker_assign() {
+        let dir = init_local_workspace(true);
+        let config = load_config(&dir).unwrap();
+        let issue_path = dir.join("tracker").join("issues").join("1.yaml");
+        let number_of_test_cases = get_test_cases(&config).unwrap().len();
+
+        // hand the issues out to a roster of two testers
+        let roster_path = dir.join("roster.yaml");
+        std::fs::write(
+            &roster_path,
+            "testers:\n- handle: alice\n- handle: bob\n  max_issues: 1\n",
+        )
+        .unwrap();
+        let mut cmd = get_command();
+        cmd.args([
+            "github",
+            "assign",
+            "--config-path",
+            dir.to_str().unwrap(),
+            "--roster",
+            roster_path.to_str().unwrap(),
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "Dry run skipping {} assignments",
+            number_of_test_cases
+        )));
+        assert!(!std::fs::read_to_string(&issue_path)
+            .unwrap()
+            .contains("assignees"));
+        let mut cmd = get_command();
+        cmd.args([
+            "github",
+            "assign",
+            "--config-path",
+            dir.to_str().unwrap(),
+            "--roster",
+            roster_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("@bob: 1 open issues"))
+        .stdout(predicate::str::contains(format!(
+            "@alice: {} open issues",
+            number_of_test_cases - 1
+        )));
+    }
+
+    #[test]
+    fn test_local_tracker_bulk() {
+        let dir = init_local_workspace(true);
+        let config = load_config(&dir).unwrap();
+        let number_of_test_cases = get_test_cases(&config).unwrap().len();
+
+        // preview and then comment on the open issues in bulk
+        let mut cmd = get_command();
+        cmd.args([
+            "github",
+            "utils",
+            "bulk",
+            "--config-path",
+            dir.to_str().unwrap(),
+            "--filter",
+            "state=open",
+            "--dry-run",
+            "lock",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "Dry run skipping {} operations.",
+            number_of_test_cases
+        )));
+        let mut cmd = get_command();
+        cmd.args([
+            "github",
+            "utils",
+            "bulk",
+            "--config-path",
+            dir.to_str().unwrap(),
+            "--filter",
+            "state=open",
+            "--yes",
+            "comment",
+            "--body",
+            "Please retest",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Comment on issue #"));
+
+        // relabeling needs labels to add or remove
+        let mut cmd = get_command();
+        cmd.args([
+            "github",
+            "utils",
+            "bulk",
+            "--config-path",
+            dir.to_str().unwrap(),
+            "--filter",
+            "state=open",
+            "relabel",
+        ])
+        .assert()
+        .failure();
+    }
+
+    #[test]
+    fn test_local_tracker_metrics() {
+        let dir = init_local_workspace(true);
+        let config = load_config(&dir).unwrap();
+        let number_of_test_cases = get_test_cases(&config).unwrap().len();
+
+        // run the metrics command and save a snapshot
+        let mut cmd = get_command();
+        cmd.args([
+            "github",
+            "metrics",
+            "--config-path",
+            dir.to_str().unwrap(),
+            "--snapshot",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "0/{} issues closed",
+            number_of_test_cases
+        )));
+
+        // close the issues and check the metrics again
+        close_local_issues(&dir);
+        let mut cmd = get_command();
+        cmd.args(["github", "metrics", "--config-path", dir.to_str().unwrap()])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(format!(
+                "{}/{} issues closed",
+                number_of_test_cases, number_of_test_cases
+            )));
+
+        // chart the progress from the snapshot and the closed issues
+        let mut cmd = get_command();
+        cmd.args([
+            "github",
+            "metrics",
+            "report",
+            "--config-path",
+            dir.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created the metrics report"));
+        let report = std::fs::read_to_string(
+            config
+                .generated_files_dir
+                .join(format!("metrics-report-{}.html", config.workspace_version)),
+        )
+        .unwrap();
+        assert!(report.contains("<svg"));
+        assert!(report.contains(&format!(
+            "<td>{}</td><td>{}</td><td>0</td></tr>",
+            number_of_test_cases, number_of_test_cases
+        )));
+    }
+
+    #[test]
+    fn test_local_tracker_coverage() {
+        let dir = init_local_workspace(true);
+        close_local_issues(&dir);
+
+        // every test case passed once its issue was closed
+        let mut cmd = get_command();
+        cmd.args(["github", "coverage", "--config-path", dir.to_str().unwrap()])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(" 0 failed, 0 untested"));
+    }
+
+    #[test]
+    fn test_local_tracker_pull_results() {
+        let dir = init_local_workspace(true);
+        let config = load_config(&dir).unwrap();
+        let number_of_test_cases = get_test_cases(&config).unwrap().len();
+        close_local_issues(&dir);
+
+        // pull the results, which are passed since the issues were closed
+        let mut cmd = get_command();
+        cmd.args([
+            "github",
+            "pull-results",
+            "--config-path",
+            dir.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "Passed: {}",
+            number_of_test_cases
+        )));
+        assert!(dir
+            .join("results")
+            .join(format!("results-{}.yaml", config.workspace_version))
+            .is_file());
+    }
+
     #[test]
     #[cfg(feature = "github_tests")]
     fn test_github() {
@@ -958,7 +1831,7 @@ mod test_cli {
 
         // run the init command
         let mut cmd = get_command();
-        cmd.args(&["init", "--path", dir.to_str().unwrap()])
+        cmd.args(["init", "--path", dir.to_str().unwrap()])
             .assert()
             .success();
 
@@ -968,13 +1841,13 @@ mod test_cli {
 
         // run the github validate command
         let mut cmd = get_command();
-        cmd.args(&["github", "validate", "--config-path", dir.to_str().unwrap()])
+        cmd.args(["github", "validate", "--config-path", dir.to_str().unwrap()])
             .assert()
             .success();
 
         // fail on validating a bad directory
         let mut cmd = get_command();
-        cmd.args(&[
+        cmd.args([
             "github",
             "validate",
             "--config-path",
@@ -986,7 +1859,7 @@ mod test_cli {
         // run the github preview command
         assert_eq!(get_number_of_files_in_dir(&config.generated_files_dir), 0);
         let mut cmd = get_command();
-        cmd.args(&["github", "preview", "--config-path", dir.to_str().unwrap()])
+        cmd.args(["github", "preview", "--config-path", dir.to_str().unwrap()])
             .assert()
             .success();
         assert_eq!(get_number_of_files_in_dir(&config.generated_files_dir), 1);
@@ -994,7 +1867,7 @@ mod test_cli {
         // run the make label links command
         assert_eq!(get_number_of_files_in_dir(&config.generated_files_dir), 1);
         let mut cmd = get_command();
-        cmd.args(&[
+        cmd.args([
             "github",
             "make-label-links",
             "--config-path",
@@ -1004,4 +1877,42 @@ mod test_cli {
         .success();
         assert_eq!(get_number_of_files_in_dir(&config.generated_files_dir), 2);
     }
+
+    /// Plan an upload and get the metrics of the repository the tests run in, which needs the
+    /// `GITHUB_TOKEN` and `GITHUB_REPOSITORY` environment variables.
+    #[test]
+    #[cfg(feature = "github_tests")]
+    fn test_github_plan_and_metrics() {
+        let dir = tempdir().unwrap().path().to_path_buf();
+
+        // run the init command and point the workspace at the repository
+        let mut cmd = get_command();
+        cmd.args(["init", "--path", dir.to_str().unwrap()])
+            .assert()
+            .success();
+        let repository = std::env::var("GITHUB_REPOSITORY").unwrap();
+        let (repo_owner, repo_name) = repository.split_once('/').unwrap();
+        let config_path = dir.join("config.yaml");
+        let config_file = std::fs::read_to_string(&config_path).unwrap().replace(
+            "repo_owner: repo_owner\nrepo_name: repo_name\n",
+            &format!("repo_owner: {}\nrepo_name: {}\n", repo_owner, repo_name),
+        );
+        std::fs::write(&config_path, config_file).unwrap();
+        let config = load_config(&dir).unwrap();
+        assert_eq!(config.repo_owner, repo_owner);
+
+        // plan an upload, which only reads the issues
+        let mut cmd = get_command();
+        cmd.args(["github", "plan", "--config-path", dir.to_str().unwrap()])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Created the upload plan file"));
+        assert_eq!(get_number_of_files_in_dir(&config.generated_files_dir), 1);
+
+        // get the metrics of the workspace version
+        let mut cmd = get_command();
+        cmd.args(["github", "metrics", "--config-path", dir.to_str().unwrap()])
+            .assert()
+            .success();
+    }
 }
@@ -0,0 +1,320 @@
+use crate::helpers::{
+    get_id_from_body, get_test_case_id, permutation_to_labels, project_version_to_label,
+};
+use crate::types::{Config, CoverageFormat, CoverageStatus};
+use anyhow::{Context, Result};
+use askama::Template;
+use common::types::{TestCase, TrackerIssue, TrackerIssueState};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// The column of the test cases without a permutation.
+const NO_PERMUTATION: &str = "(no permutation)";
+
+/// The test cases of every requirement, by permutation, and how they did.
+#[derive(Debug)]
+pub struct CoverageMatrix {
+    /// The permutations of the columns, as their labels joined together.
+    pub permutations: Vec<String>,
+    pub rows: Vec<CoverageRow>,
+}
+
+#[derive(Debug)]
+pub struct CoverageRow {
+    pub requirement: String,
+    /// A cell per permutation, with nothing when no test case has the requirement and permutation.
+    pub cells: Vec<Option<CoverageCell>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageCell {
+    /// The worst status of the test cases in the cell.
+    pub status: CoverageStatus,
+    pub issues: Vec<CoverageIssue>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageIssue {
+    pub number: u64,
+    pub url: String,
+}
+
+#[derive(Template)]
+#[template(path = "coverage_report.html")]
+struct CoverageReportTemplate<'a> {
+    title: String,
+    summary: String,
+    matrix: &'a CoverageMatrix,
+}
+
+impl CoverageMatrix {
+    /// Count the cells by status.
+    pub fn count(&self, status: CoverageStatus) -> usize {
+        self.rows
+            .iter()
+            .flat_map(|r| r.cells.iter().flatten())
+            .filter(|c| c.status == status)
+            .count()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} passed, {} failed, {} untested",
+            self.count(CoverageStatus::Passed),
+            self.count(CoverageStatus::Failed),
+            self.count(CoverageStatus::Untested)
+        )
+    }
+}
+
+/// Cross every test case with the issue made from it.
+///
+/// A test case fails when its issue has one of the failed or bad instructions labels, passes when
+/// its issue has one of the passed labels or is closed, and is untested otherwise, including when it
+/// has no issue.
+pub fn get_coverage_matrix(
+    config: &Config,
+    test_cases: &[TestCase],
+    issues: &[TrackerIssue],
+) -> CoverageMatrix {
+    let version_label = project_version_to_label(&config.workspace_version);
+    let mut issues_by_id: HashMap<String, Vec<&TrackerIssue>> = HashMap::new();
+    for issue in issues
+        .iter()
+        .filter(|i| !i.is_pull_request && i.labels.contains(&version_label))
+    {
+        if let Some(id) = issue.body.as_deref().and_then(get_id_from_body) {
+            issues_by_id.entry(id).or_default().push(issue);
+        }
+    }
+
+    let mut cells: BTreeMap<String, BTreeMap<String, CoverageCell>> = BTreeMap::new();
+    let mut permutations: BTreeSet<String> = BTreeSet::new();
+    for test_case in test_cases {
+        let permutation = match permutation_to_labels(&test_case.selected_permutation) {
+            labels if labels.is_empty() => NO_PERMUTATION.to_string(),
+            labels => labels.join(", "),
+        };
+        let id = get_test_case_id(test_case, &config.workspace_version);
+        let test_case_issues = issues_by_id.get(&id).cloned().unwrap_or_default();
+        let status = test_case_issues
+            .iter()
+            .map(|i| get_issue_status(config, i))
+            .max()
+            .unwrap_or(CoverageStatus::Untested);
+
+        let cell = cells
+            .entry(test_case.requirement.name.clone())
+            .or_default()
+            .entry(permutation.clone())
+            .or_insert(CoverageCell {
+                status,
+                issues: vec![],
+            });
+        cell.status = cell.status.max(status);
+        cell.issues
+            .extend(test_case_issues.iter().map(|i| CoverageIssue {
+                number: i.number,
+                url: i.url.clone(),
+            }));
+        permutations.insert(permutation);
+    }
+
+    let permutations: Vec<String> = permutations.into_iter().collect();
+    CoverageMatrix {
+        rows: cells
+            .into_iter()
+            .map(|(requirement, mut cells)| CoverageRow {
+                requirement,
+                cells: permutations.iter().map(|p| cells.remove(p)).collect(),
+            })
+            .collect(),
+        permutations,
+    }
+}
+
+fn get_issue_status(config: &Config, issue: &TrackerIssue) -> CoverageStatus {
+    let has_any = |labels: &[String]| labels.iter().any(|l| issue.labels.contains(l));
+    let labels = &config.result_labels;
+    if has_any(&labels.failed) || has_any(&labels.bad_instructions) {
+        CoverageStatus::Failed
+    } else if has_any(&labels.passed) || issue.state == TrackerIssueState::Closed {
+        CoverageStatus::Passed
+    } else {
+        CoverageStatus::Untested
+    }
+}
+
+/// Render the coverage matrix as a Markdown table or an HTML page.
+pub fn render_coverage_report(
+    config: &Config,
+    matrix: &CoverageMatrix,
+    format: CoverageFormat,
+) -> Result<String> {
+    let title = format!("Coverage for version: {}", config.workspace_version);
+    match format {
+        CoverageFormat::Markdown => {
+            let mut markdown = format!(
+                "# {}\n\n{}\n\n{} passed, {} failed, {} untested\n\n",
+                title,
+                matrix.summary(),
+                CoverageStatus::Passed.symbol(),
+                CoverageStatus::Failed.symbol(),
+                CoverageStatus::Untested.symbol()
+            );
+            markdown.push_str(&format!(
+                "| Requirement | {} |\n",
+                matrix
+                    .permutations
+                    .iter()
+                    .map(|p| escape_markdown_cell(p))
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+            ));
+            markdown.push_str(&format!(
+                "| --- |{}\n",
+                " :---: |".repeat(matrix.permutations.len())
+            ));
+            for row in &matrix.rows {
+                let cells: Vec<String> = row
+                    .cells
+                    .iter()
+                    .map(|cell| match cell {
+                        Some(cell) => std::iter::once(cell.status.symbol().to_string())
+                            .chain(
+                                cell.issues
+                                    .iter()
+                                    .map(|i| format!("[#{}]({})", i.number, i.url)),
+                            )
+                            .collect::<Vec<_>>()
+                            .join(" "),
+                        None => String::new(),
+                    })
+                    .collect();
+                markdown.push_str(&format!(
+                    "| {} | {} |\n",
+                    escape_markdown_cell(&row.requirement),
+                    cells.join(" | ")
+                ));
+            }
+            Ok(markdown)
+        }
+        CoverageFormat::Html => CoverageReportTemplate {
+            title,
+            summary: matrix.summary(),
+            matrix,
+        }
+        .render()
+        .context("Failed to render the coverage report template"),
+    }
+}
+
+fn escape_markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod test_coverage {
+    use super::*;
+    use crate::app::init_workspace::init_workspace;
+    use crate::app::load_config::load_config;
+    use crate::helpers::{get_test_cases, test_case_to_markdown};
+    use crate::test_utils::make_tracker_issue;
+    use tempfile::tempdir;
+
+    fn get_matrix() -> (Config, Vec<TestCase>, CoverageMatrix) {
+        let dir = tempdir().unwrap().path().to_path_buf();
+        init_workspace(&dir).unwrap();
+        let config = load_config(&dir).unwrap();
+        let test_cases = get_test_cases(&config).unwrap();
+        // The first test case passed, the second failed and the rest have no issue yet
+        let issues: Vec<TrackerIssue> = test_cases[..2]
+            .iter()
+            .enumerate()
+            .map(|(i, test_case)| {
+                let issue =
+                    test_case_to_markdown(test_case.clone(), &config.workspace_version).unwrap();
+                let mut labels = issue.labels;
+                if i == 1 {
+                    labels.push("confirmed: bug".to_string());
+                }
+                let mut issue =
+                    make_tracker_issue(i as u64 + 1, &issue.title, Some(&issue.text_body), labels);
+                issue.url = format!("https://example.com/issues/{}", i + 1);
+                if i == 0 {
+                    issue.state = TrackerIssueState::Closed;
+                }
+                issue
+            })
+            .collect();
+        let matrix = get_coverage_matrix(&config, &test_cases, &issues);
+        (config, test_cases, matrix)
+    }
+
+    fn find_cell<'a>(matrix: &'a CoverageMatrix, test_case: &TestCase) -> &'a CoverageCell {
+        let permutation = permutation_to_labels(&test_case.selected_permutation).join(", ");
+        let column = matrix
+            .permutations
+            .iter()
+            .position(|p| p == &permutation)
+            .unwrap();
+        matrix
+            .rows
+            .iter()
+            .find(|r| r.requirement == test_case.requirement.name)
+            .unwrap()
+            .cells[column]
+            .as_ref()
+            .unwrap()
+    }
+
+    #[test]
+    fn crosses_the_test_cases_with_their_issues() {
+        let (_, test_cases, matrix) = get_matrix();
+        let cells: usize = matrix
+            .rows
+            .iter()
+            .map(|r| r.cells.iter().flatten().count())
+            .sum();
+        assert!(cells > 0 && cells <= test_cases.len());
+        assert!(matrix
+            .rows
+            .iter()
+            .all(|r| r.cells.len() == matrix.permutations.len()));
+
+        let first_cell = find_cell(&matrix, &test_cases[0]);
+        let second_cell = find_cell(&matrix, &test_cases[1]);
+        if first_cell != second_cell {
+            assert_eq!(first_cell.status, CoverageStatus::Passed);
+            assert_eq!(second_cell.status, CoverageStatus::Failed);
+        }
+        // A failure is the status of the cell it's in, whatever else is in it
+        assert_eq!(matrix.count(CoverageStatus::Failed), 1);
+        assert_eq!(second_cell.issues[0].number, 2);
+        assert!(matrix.count(CoverageStatus::Untested) > 0);
+    }
+
+    #[test]
+    fn renders_the_report() {
+        let (config, _, matrix) = get_matrix();
+
+        let markdown = render_coverage_report(&config, &matrix, CoverageFormat::Markdown).unwrap();
+        let lines: Vec<&str> = markdown.lines().collect();
+        assert_eq!(
+            lines[0],
+            format!("# Coverage for version: {}", config.workspace_version)
+        );
+        let header = lines
+            .iter()
+            .position(|l| l.starts_with("| Requirement |"))
+            .unwrap();
+        assert_eq!(lines.len(), header + 2 + matrix.rows.len());
+        assert!(markdown.contains("❌ [#2](https://example.com/issues/2)"));
+
+        let html = render_coverage_report(&config, &matrix, CoverageFormat::Html).unwrap();
+        assert!(html.contains(&matrix.summary()));
+        assert!(html.contains(
+            "<td class=\"Failed\">❌ <a href=\"https://example.com/issues/2\">#2</a></td>"
+        ));
+        assert_eq!(html.matches("<tr><th>").count(), matrix.rows.len() + 1);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::constants::LOCAL_TRACKER_DIR_NAME;
+use crate::types::{Config, IssueTrackerKind};
+use anyhow::Result;
+use common::github::{Github, GITHUB_API_URL, GITHUB_WEB_URL};
+use common::gitlab::{Gitlab, GITLAB_URL};
+use common::issue_tracker::IssueTracker;
+use common::local_tracker::LocalTracker;
+
+/// Connect to the issue tracker chosen in the configuration.
+pub async fn get_issue_tracker(config: &Config) -> Result<Box<dyn IssueTracker>> {
+    match config.issue_tracker {
+        IssueTrackerKind::Github => Ok(Box::new(get_github(config).await?)),
+        IssueTrackerKind::Gitlab => Ok(Box::new(
+            Gitlab::new(
+                config.gitlab_url.as_deref().unwrap_or(GITLAB_URL),
+                &config.repo_owner,
+                &config.repo_name,
+            )?
+            .with_concurrency(config.concurrency),
+        )),
+        IssueTrackerKind::Local => Ok(Box::new(LocalTracker::new(
+            &config
+                .local_tracker_dir
+                .clone()
+                .unwrap_or(config.generated_files_dir.join(LOCAL_TRACKER_DIR_NAME)),
+        )?)),
+    }
+}
+
+/// Get the issue tracker chosen in the configuration without authenticating with it.
+///
+/// The tracker can make links and previews, but requests to it fail when they need credentials.
+pub async fn get_offline_issue_tracker(config: &Config) -> Result<Box<dyn IssueTracker>> {
+    match config.issue_tracker {
+        IssueTrackerKind::Github => Ok(Box::new(configure_github(
+            config,
+            Github::new_offline(
+                config.github_api_url.as_deref().unwrap_or(GITHUB_API_URL),
+                &config.repo_owner,
+                &config.repo_name,
+            )?,
+        ))),
+        _ => get_issue_tracker(config).await,
+    }
+}
+
+/// Connect to Github with the authentication method chosen in the configuration.
+pub async fn get_github(config: &Config) -> Result<Github> {
+    let github = Github::new_with_auth(
+        config.github_api_url.as_deref().unwrap_or(GITHUB_API_URL),
+        &config.repo_owner,
+        &config.repo_name,
+        &config.github_auth,
+    )
+    .await?;
+    Ok(configure_github(config, github))
+}
+
+/// Set the website, concurrency and project of the configuration on a Github client.
+fn configure_github(config: &Config, github: Github) -> Github {
+    let github = github
+        .with_web_url(&get_github_web_url(config))
+        .with_concurrency(config.concurrency);
+    match &config.github_project {
+        Some(project) => github.with_project(&project.owner, project.number),
+        None => github,
+    }
+}
+
+/// Get the address of the Github website.
+///
+/// Github Enterprise Server serves its API from `/api/v3` on the same host as the website, so the
+/// website address can usually be found from the API address when it isn't configured.
+fn get_github_web_url(config: &Config) -> String {
+    if let Some(web_url) = &config.github_web_url {
+        return web_url.clone();
+    }
+    match &config.github_api_url {
+        Some(api_url) => api_url
+            .trim_end_matches('/')
+            .trim_end_matches("/api/v3")
+            .to_string(),
+        None => GITHUB_WEB_URL.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test_get_issue_tracker {
+    use super::*;
+    use common::types::GithubAuth;
+
+    fn get_test_auth() -> GithubAuth {
+        GithubAuth::Command {
+            command: "echo token".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn github_is_the_default() {
+        let config = serde_yaml::to_string(&Config::default()).unwrap();
+        let config = config.replace("issue_tracker: github\n", "");
+        let mut config: Config = serde_yaml::from_str(&config).unwrap();
+        assert_eq!(config.issue_tracker, IssueTrackerKind::Github);
+        config.github_auth = get_test_auth();
+
+        let tracker = get_issue_tracker(&config).await.unwrap();
+        assert_eq!(
+            tracker.get_label_link(&["version: 0.0.1".to_string()]),
+            "https://github.com/repo_owner/repo_name/issues?q=is:issue+is:open+label:%22version:+0.0.1%22"
+        );
+    }
+
+    #[tokio::test]
+    async fn github_enterprise_uses_the_configured_urls() {
+        let mut config = Config {
+            github_api_url: Some("https://github.example.com/api/v3".to_string()),
+            github_auth: get_test_auth(),
+            ..Default::default()
+        };
+        assert_eq!(get_github_web_url(&config), "https://github.example.com");
+
+        config.github_web_url = Some("https://github-web.example.com".to_string());
+        let tracker = get_issue_tracker(&config).await.unwrap();
+        assert!(tracker
+            .get_label_link(&[])
+            .starts_with("https://github-web.example.com/repo_owner/repo_name/issues"));
+        assert!(tracker
+            .prepend_tracker_info("content")
+            .contains("(https://github-web.example.com/repo_owner/repo_name)"));
+    }
+
+    #[tokio::test]
+    async fn gitlab_uses_the_configured_url() {
+        let config = Config {
+            issue_tracker: IssueTrackerKind::Gitlab,
+            gitlab_url: Some("https://gitlab.example.com/".to_string()),
+            ..Default::default()
+        };
+
+        let tracker = get_issue_tracker(&config).await.unwrap();
+        assert_eq!(
+            tracker.get_label_link(&["version: 0.0.1".to_string()]),
+            "https://gitlab.example.com/repo_owner/repo_name/-/issues?state=opened&label_name[]=version%3A%200%2E0%2E1"
+        );
+    }
+}
@@ -1,7 +1,6 @@
 use crate::types::Config;
 use anyhow::{Context, Result};
 use common::types::{RequirementsFile, TestCasesBuilderFile};
-use serde_yaml;
 use std::path::PathBuf;
 
 /// Initialize the configuration directory.
@@ -50,7 +49,7 @@ pub fn init_workspace(dir: &PathBuf) -> Result<()> {
     let gitignore_file = dir.join(".gitignore");
     std::fs::write(
         &gitignore_file,
-        &format!("/{}\n.env\n", config.generated_files_dir.to_string_lossy()),
+        format!("/{}\n.env\n", config.generated_files_dir.to_string_lossy()),
     )
     .context("Failed to write the .gitignore file")?;
 
@@ -1,13 +1,13 @@
 use crate::types::Config;
 use anyhow::{Context, Result};
-use serde_yaml;
-use std::path::PathBuf;
+use common::types::GithubAuth;
+use std::path::Path;
 
 /// Load the configuration.
-pub fn load_config(path: &PathBuf) -> Result<Config> {
-    let mut path = path.clone();
+pub fn load_config(path: &Path) -> Result<Config> {
+    let mut path = path.to_path_buf();
     if path.is_dir() {
-        path = path.join("config.yaml".to_string());
+        path = path.join("config.yaml");
     }
 
     let config =
@@ -32,6 +32,15 @@ pub fn load_config(path: &PathBuf) -> Result<Config> {
         .collect();
     config.new_test_cases_builder_dir = parent_dir.join(config.new_test_cases_builder_dir);
     config.generated_files_dir = parent_dir.join(config.generated_files_dir);
+    config.results_dir = parent_dir.join(config.results_dir);
+    config.local_tracker_dir = config.local_tracker_dir.map(|dir| parent_dir.join(dir));
+    match &mut config.github_auth {
+        GithubAuth::TokenFile { path } => *path = parent_dir.join(&path),
+        GithubAuth::App {
+            private_key_file, ..
+        } => *private_key_file = parent_dir.join(&private_key_file),
+        GithubAuth::Env | GithubAuth::Command { .. } => {}
+    }
 
     Ok(config)
 }
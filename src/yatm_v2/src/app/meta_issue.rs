@@ -0,0 +1,204 @@
+use crate::helpers::{
+    get_meta_version_from_body, get_permutation_groups, get_test_cases, make_meta_marker,
+    project_version_to_label,
+};
+use crate::types::Config;
+use anyhow::Result;
+use common::issue_tracker::IssueTracker;
+use common::types::{TrackerIssue, TrackerIssueState};
+
+/// Create or update the issue that tracks the testing of the workspace version.
+///
+/// The issue is found again by a hidden marker with the workspace version, and its body is
+/// rewritten on every run with the current counts. It's pinned when it's created.
+pub async fn update_meta_issue(config: &Config, tracker: &dyn IssueTracker) -> Result<u64> {
+    let test_cases = get_test_cases(config)?;
+    if test_cases.is_empty() {
+        anyhow::bail!("No test cases found");
+    }
+    let issues = tracker.get_issues(None).await?;
+
+    let version_label = project_version_to_label(&config.workspace_version);
+    let title = format!("Tracking issue for {}", version_label);
+    let body = make_meta_issue_body(
+        config,
+        tracker,
+        &get_permutation_groups(&test_cases),
+        &issues,
+    );
+
+    let meta_issue = issues.iter().find(|i| {
+        !i.is_pull_request
+            && i.body.as_deref().and_then(get_meta_version_from_body)
+                == Some(config.workspace_version.clone())
+    });
+    match meta_issue {
+        Some(meta_issue) => {
+            tracker.update_issue(meta_issue.number, title, body).await?;
+            println!("Updated the tracking issue: {}", meta_issue.url);
+            Ok(meta_issue.number)
+        }
+        None => {
+            let number = tracker.create_issue(title, body, vec![]).await?;
+            println!("Created the tracking issue #{}", number);
+            // Github only lets a few issues be pinned at a time, so not pinning isn't fatal
+            if let Err(error) = tracker.pin_issue(number).await {
+                println!("Couldn't pin the tracking issue: {:#}", error);
+            }
+            Ok(number)
+        }
+    }
+}
+
+/// Make the body of the tracking issue, with a row of counts and a link per permutation.
+fn make_meta_issue_body(
+    config: &Config,
+    tracker: &dyn IssueTracker,
+    permutation_groups: &[Vec<String>],
+    issues: &[TrackerIssue],
+) -> String {
+    let version_label = project_version_to_label(&config.workspace_version);
+    let version_issues: Vec<&TrackerIssue> = issues
+        .iter()
+        .filter(|i| !i.is_pull_request && i.labels.contains(&version_label))
+        .collect();
+
+    let mut body = format!(
+        "This issue tracks the test cases of `{}`. Pick an open issue from one of the permutations below, follow its steps and report how it went on the issue.\n\n",
+        version_label
+    );
+    body.push_str("| Permutation | Open | Closed | Progress |\n");
+    body.push_str("| --- | ---: | ---: | ---: |\n");
+    for labels in permutation_groups {
+        let group_issues: Vec<&TrackerIssue> = version_issues
+            .iter()
+            .filter(|i| labels.iter().all(|l| i.labels.contains(l)))
+            .copied()
+            .collect();
+        let name = match labels.is_empty() {
+            true => "No permutation".to_string(),
+            false => labels
+                .iter()
+                .map(|l| format!("`{}`", l))
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        let link_labels: Vec<String> = std::iter::once(version_label.clone())
+            .chain(labels.iter().cloned())
+            .collect();
+        body.push_str(&make_row(
+            &format!("[{}]({})", name, tracker.get_label_link(&link_labels)),
+            &group_issues,
+        ));
+    }
+    body.push_str(&make_row(
+        &format!(
+            "[**All**]({})",
+            tracker.get_label_link(std::slice::from_ref(&version_label))
+        ),
+        &version_issues,
+    ));
+
+    body.push_str(&format!(
+        "\n_Updated by `yatm_v2 github meta-issue` on {}. Changes made to this issue by hand are overwritten._\n\n{}\n",
+        chrono::Utc::now().format("%Y-%m-%d %H:%M UTC"),
+        make_meta_marker(&config.workspace_version)
+    ));
+    body
+}
+
+fn make_row(name: &str, issues: &[&TrackerIssue]) -> String {
+    let closed = issues
+        .iter()
+        .filter(|i| i.state == TrackerIssueState::Closed)
+        .count();
+    let progress = match issues.len() {
+        0 => "-".to_string(),
+        total => format!("{:.0}%", closed as f64 / total as f64 * 100.0),
+    };
+    format!(
+        "| {} | {} | {} | {} |\n",
+        name,
+        issues.len() - closed,
+        closed,
+        progress
+    )
+}
+
+#[cfg(test)]
+mod test_meta_issue {
+    use super::*;
+    use crate::app::init_workspace::init_workspace;
+    use crate::app::load_config::load_config;
+    use crate::app::upload::{apply_upload_plan, make_upload_plan};
+    use crate::constants::UPLOAD_JOURNAL_FILE_NAME;
+    use crate::types::{OrphanAction, UploadOptions};
+    use common::fake_github::FakeGithub;
+    use common::github::Github;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn creates_then_updates_the_tracking_issue() {
+        let dir = tempdir().unwrap().path().to_path_buf();
+        init_workspace(&dir).unwrap();
+        let config = load_config(&dir).unwrap();
+        let journal_path = config.generated_files_dir.join(UPLOAD_JOURNAL_FILE_NAME);
+        let test_cases = get_test_cases(&config).unwrap();
+
+        let fake_github = FakeGithub::start(&config.repo_owner, &config.repo_name)
+            .await
+            .unwrap();
+        let github = Github::new_with_token(
+            fake_github.url(),
+            &config.repo_owner,
+            &config.repo_name,
+            "token",
+        )
+        .unwrap();
+        let options = UploadOptions {
+            force: false,
+            orphans: OrphanAction::List,
+            carry_over_from: None,
+        };
+        let plan = make_upload_plan(&config, &github, &options, &[])
+            .await
+            .unwrap();
+        apply_upload_plan(&github, &plan, &journal_path, 2)
+            .await
+            .unwrap();
+
+        // The tracking issue is created and pinned, with a row per permutation and a total
+        let number = update_meta_issue(&config, &github).await.unwrap();
+        assert_eq!(number, test_cases.len() as u64 + 1);
+        assert_eq!(fake_github.pinned_issues(), vec![number]);
+        let body = fake_github.issues()[number as usize - 1]
+            .body
+            .clone()
+            .unwrap();
+        let rows = get_permutation_groups(&test_cases).len() + 1;
+        assert_eq!(body.lines().filter(|l| l.starts_with("| [")).count(), rows);
+        assert!(body.contains(&format!(
+            "| [**All**]({}) | {} | 0 | 0% |",
+            github.get_label_link(&["version: 0.0.1".to_string()]),
+            test_cases.len()
+        )));
+
+        // Running it again updates the same issue with the new counts
+        fake_github.edit_issue(1, |issue| issue.state = TrackerIssueState::Closed);
+        assert_eq!(update_meta_issue(&config, &github).await.unwrap(), number);
+        assert_eq!(fake_github.issues().len(), number as usize);
+        assert_eq!(fake_github.pinned_issues(), vec![number]);
+        let body = fake_github.issues()[number as usize - 1]
+            .body
+            .clone()
+            .unwrap();
+        assert!(body.contains(&format!("| {} | 1 |", test_cases.len() - 1)));
+
+        // The tracking issue isn't mistaken for a test case on the next upload
+        let journal = crate::helpers::read_upload_journal(&journal_path).unwrap();
+        let plan = make_upload_plan(&config, &github, &options, &journal)
+            .await
+            .unwrap();
+        assert!(plan.operations.is_empty());
+    }
+}
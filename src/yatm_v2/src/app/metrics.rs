@@ -0,0 +1,284 @@
+use crate::helpers::{
+    get_id_from_body, get_test_case_id, permutation_to_labels, project_version_to_label,
+};
+use crate::types::{Config, MetricsFormat, MetricsRow};
+use anyhow::{Context, Result};
+use common::types::{TestCase, TrackerIssue, TrackerIssueState};
+use std::collections::{BTreeMap, HashMap};
+
+/// The group value of the issues that no current test case produces.
+const NO_TEST_CASE: &str = "(no test case)";
+
+/// Get the metrics of the issues of the workspace version.
+///
+/// The first row counts all of the issues. The issues are then broken down by permutation label,
+/// by builder and by requirement, which come from the test case each issue was made from, and by
+/// the triage labels in the configuration. Only the issues with `label` are counted if it's given.
+pub fn get_metrics(
+    config: &Config,
+    test_cases: &[TestCase],
+    issues: &[TrackerIssue],
+    label: Option<&String>,
+) -> Vec<MetricsRow> {
+    let version_label = project_version_to_label(&config.workspace_version);
+    let issues: Vec<&TrackerIssue> = issues
+        .iter()
+        .filter(|i| {
+            !i.is_pull_request
+                && i.labels.contains(&version_label)
+                && label.is_none_or(|label| i.labels.contains(label))
+        })
+        .collect();
+    let test_cases_by_id: HashMap<String, &TestCase> = test_cases
+        .iter()
+        .map(|t| (get_test_case_id(t, &config.workspace_version), t))
+        .collect();
+
+    let mut counts: BTreeMap<(usize, String, String), (usize, usize)> = BTreeMap::new();
+    let mut count = |order: usize, group: &str, value: String, issue: &TrackerIssue| {
+        let (open, closed) = counts.entry((order, group.to_string(), value)).or_default();
+        match issue.state {
+            TrackerIssueState::Open => *open += 1,
+            TrackerIssueState::Closed => *closed += 1,
+        }
+    };
+    for issue in &issues {
+        count(0, "all", "all".to_string(), issue);
+        let test_case = issue
+            .body
+            .as_deref()
+            .and_then(get_id_from_body)
+            .and_then(|id| test_cases_by_id.get(&id));
+        match test_case {
+            Some(test_case) => {
+                for label in permutation_to_labels(&test_case.selected_permutation) {
+                    count(1, "permutation", label, issue);
+                }
+                count(2, "builder", test_case.builder_used.name.clone(), issue);
+                let requirement = test_case
+                    .requirement
+                    .shortname
+                    .clone()
+                    .unwrap_or(test_case.requirement.name.clone());
+                count(3, "requirement", requirement, issue);
+            }
+            None => {
+                count(2, "builder", NO_TEST_CASE.to_string(), issue);
+                count(3, "requirement", NO_TEST_CASE.to_string(), issue);
+            }
+        }
+    }
+
+    let mut rows: Vec<MetricsRow> = counts
+        .into_iter()
+        .map(|((_, group, value), (open, closed))| MetricsRow::new(&group, &value, open, closed))
+        .collect();
+    // The triage labels keep the order of the configuration and are listed even when unused
+    for triage_label in &config.labels {
+        let (open, closed) = issues
+            .iter()
+            .filter(|i| i.labels.contains(&triage_label.name))
+            .fold((0, 0), |(open, closed), i| match i.state {
+                TrackerIssueState::Open => (open + 1, closed),
+                TrackerIssueState::Closed => (open, closed + 1),
+            });
+        rows.push(MetricsRow::new("triage", &triage_label.name, open, closed));
+    }
+    rows
+}
+
+/// Format the metrics to print them.
+pub fn format_metrics(rows: &[MetricsRow], format: MetricsFormat) -> Result<String> {
+    match format {
+        MetricsFormat::Json => {
+            serde_json::to_string_pretty(rows).context("Failed to serialize the metrics")
+        }
+        MetricsFormat::Csv => {
+            let mut csv = "group,value,open,closed,total,percent_closed\n".to_string();
+            for row in rows {
+                csv.push_str(&format!(
+                    "{},{},{},{},{},{:.2}\n",
+                    escape_csv(&row.group),
+                    escape_csv(&row.value),
+                    row.open,
+                    row.closed,
+                    row.total,
+                    row.percent_closed
+                ));
+            }
+            Ok(csv)
+        }
+        MetricsFormat::Table => {
+            let header = ["Group", "Value", "Open", "Closed", "Total", "Closed %"];
+            let cells: Vec<[String; 6]> = rows
+                .iter()
+                .map(|row| {
+                    [
+                        row.group.clone(),
+                        row.value.clone(),
+                        row.open.to_string(),
+                        row.closed.to_string(),
+                        row.total.to_string(),
+                        format!("{:.2}%", row.percent_closed),
+                    ]
+                })
+                .collect();
+            let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+            for row in &cells {
+                for (width, cell) in widths.iter_mut().zip(row) {
+                    *width = (*width).max(cell.chars().count());
+                }
+            }
+
+            let format_line = |cells: Vec<&str>| {
+                cells
+                    .iter()
+                    .zip(&widths)
+                    .enumerate()
+                    .map(|(i, (cell, width))| match i {
+                        // Text is aligned left and the counts right
+                        0 | 1 => format!("{:<width$}", cell, width = width),
+                        _ => format!("{:>width$}", cell, width = width),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("  ")
+                    .trim_end()
+                    .to_string()
+            };
+            let mut table = format_line(header.to_vec()) + "\n";
+            table.push_str(&format_line(
+                widths
+                    .iter()
+                    .map(|w| "-".repeat(*w))
+                    .collect::<Vec<_>>()
+                    .iter()
+                    .map(|s| s.as_str())
+                    .collect(),
+            ));
+            table.push('\n');
+            for row in &cells {
+                table.push_str(&format_line(row.iter().map(|c| c.as_str()).collect()));
+                table.push('\n');
+            }
+            Ok(table)
+        }
+    }
+}
+
+/// Quote a CSV field if it has a separator, quote or line break in it.
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test_metrics {
+    use super::*;
+    use crate::app::init_workspace::init_workspace;
+    use crate::app::load_config::load_config;
+    use crate::helpers::{get_test_cases, test_case_to_markdown};
+    use crate::test_utils::make_tracker_issue;
+    use tempfile::tempdir;
+
+    fn get_issues(config: &Config, test_cases: &[TestCase]) -> Vec<TrackerIssue> {
+        test_cases
+            .iter()
+            .enumerate()
+            .map(|(i, test_case)| {
+                let issue =
+                    test_case_to_markdown(test_case.clone(), &config.workspace_version).unwrap();
+                make_tracker_issue(
+                    i as u64 + 1,
+                    &issue.title,
+                    Some(&issue.text_body),
+                    issue.labels,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn breaks_down_the_issues() {
+        let dir = tempdir().unwrap().path().to_path_buf();
+        init_workspace(&dir).unwrap();
+        let config = load_config(&dir).unwrap();
+        let test_cases = get_test_cases(&config).unwrap();
+        let mut issues = get_issues(&config, &test_cases);
+        issues[0].state = TrackerIssueState::Closed;
+        issues[0].labels.push("confirmed: bug".to_string());
+        // An issue of another version isn't counted, one without a test case is
+        let mut other_issue = issues[1].clone();
+        other_issue.labels = vec!["version: 0.0.0".to_string()];
+        issues.push(other_issue);
+        let mut orphaned_issue = issues[1].clone();
+        orphaned_issue.body = None;
+        issues.push(orphaned_issue);
+
+        let rows = get_metrics(&config, &test_cases, &issues, None);
+        let total = test_cases.len() + 1;
+        assert_eq!(rows[0], MetricsRow::new("all", "all", total - 1, 1));
+
+        let first_permutation = permutation_to_labels(&test_cases[0].selected_permutation);
+        for label in &first_permutation {
+            let row = rows
+                .iter()
+                .find(|r| r.group == "permutation" && &r.value == label)
+                .unwrap();
+            assert_eq!(row.closed, 1);
+        }
+        let builder_total: usize = rows
+            .iter()
+            .filter(|r| r.group == "builder")
+            .map(|r| r.total)
+            .sum();
+        assert_eq!(builder_total, total);
+        assert!(rows
+            .iter()
+            .any(|r| r.group == "requirement" && r.value == NO_TEST_CASE && r.total == 1));
+
+        let triage_rows: Vec<&MetricsRow> = rows.iter().filter(|r| r.group == "triage").collect();
+        assert_eq!(triage_rows.len(), config.labels.len());
+        let bug_row = triage_rows
+            .iter()
+            .find(|r| r.value == "confirmed: bug")
+            .unwrap();
+        assert_eq!((bug_row.open, bug_row.closed), (0, 1));
+
+        // Filtering by a label only counts the issues with it
+        let rows = get_metrics(
+            &config,
+            &test_cases,
+            &issues,
+            Some(&"confirmed: bug".to_string()),
+        );
+        assert_eq!(rows[0], MetricsRow::new("all", "all", 0, 1));
+    }
+
+    #[test]
+    fn formats_the_metrics() {
+        let rows = vec![
+            MetricsRow::new("all", "all", 3, 1),
+            MetricsRow::new("requirement", "Say \"hi\", then leave", 1, 0),
+        ];
+
+        let csv = format_metrics(&rows, MetricsFormat::Csv).unwrap();
+        assert_eq!(
+            csv,
+            "group,value,open,closed,total,percent_closed\nall,all,3,1,4,25.00\nrequirement,\"Say \"\"hi\"\", then leave\",1,0,1,0.00\n"
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&format_metrics(&rows, MetricsFormat::Json).unwrap()).unwrap();
+        assert_eq!(json[0]["percent_closed"], 25.0);
+        assert_eq!(json[1]["value"], "Say \"hi\", then leave");
+
+        let table = format_metrics(&rows, MetricsFormat::Table).unwrap();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("Group        Value"));
+        assert!(lines[2].ends_with("25.00%"));
+    }
+}
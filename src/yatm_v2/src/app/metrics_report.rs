@@ -0,0 +1,344 @@
+use crate::helpers::project_version_to_label;
+use crate::types::{Config, MetricsReportFormat, MetricsSnapshot};
+use anyhow::{Context, Result};
+use askama::Template;
+use chrono::{DateTime, Utc};
+use common::types::{TrackerIssue, TrackerIssueState};
+
+const CHART_WIDTH: f64 = 800.0;
+const CHART_HEIGHT: f64 = 400.0;
+const MARGIN_LEFT: f64 = 60.0;
+const MARGIN_RIGHT: f64 = 30.0;
+const MARGIN_TOP: f64 = 50.0;
+const MARGIN_BOTTOM: f64 = 70.0;
+
+/// A line of the chart: its name, its color and the count it plots.
+type Series = (&'static str, &'static str, fn(&BurndownPoint) -> usize);
+
+/// The progress of the workspace version at one point in time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BurndownPoint {
+    pub timestamp: DateTime<Utc>,
+    /// The number of issues the version has.
+    pub total: usize,
+    pub closed: usize,
+}
+
+impl BurndownPoint {
+    pub fn remaining(&self) -> usize {
+        self.total.saturating_sub(self.closed)
+    }
+}
+
+#[derive(Template)]
+#[template(path = "metrics_report.html")]
+struct MetricsReportTemplate {
+    title: String,
+    chart: String,
+    points: Vec<BurndownPoint>,
+}
+
+/// Get the progress of the workspace version over time.
+///
+/// The number of issues comes from the unfiltered snapshots of the version, since issues can be
+/// added or closed as obsolete during the release window, and from the issues themselves for
+/// `now`. The number of closed issues comes from when each issue was closed.
+pub fn get_burndown(
+    config: &Config,
+    snapshots: &[MetricsSnapshot],
+    issues: &[TrackerIssue],
+    now: DateTime<Utc>,
+) -> Result<Vec<BurndownPoint>> {
+    let version_label = project_version_to_label(&config.workspace_version);
+    let issues: Vec<&TrackerIssue> = issues
+        .iter()
+        .filter(|i| !i.is_pull_request && i.labels.contains(&version_label))
+        .collect();
+
+    let mut totals: Vec<(DateTime<Utc>, usize)> = snapshots
+        .iter()
+        .filter(|s| s.workspace_version == config.workspace_version && s.label.is_none())
+        .map(|s| {
+            let total = s
+                .rows
+                .iter()
+                .find(|r| r.group == "all")
+                .map(|r| r.total)
+                .unwrap_or_default();
+            Ok((parse_timestamp(&s.timestamp)?, total))
+        })
+        .collect::<Result<_>>()?;
+    totals.push((now, issues.len()));
+    totals.sort_by_key(|(timestamp, _)| *timestamp);
+
+    let mut closed_times: Vec<DateTime<Utc>> = issues
+        .iter()
+        .filter(|i| i.state == TrackerIssueState::Closed)
+        .filter_map(|i| i.closed_at.as_ref())
+        .map(|closed_at| parse_timestamp(closed_at))
+        .collect::<Result<_>>()?;
+    closed_times.sort();
+
+    let mut timestamps: Vec<DateTime<Utc>> = totals
+        .iter()
+        .map(|(timestamp, _)| *timestamp)
+        .chain(closed_times.iter().copied())
+        .filter(|timestamp| *timestamp <= now)
+        .collect();
+    timestamps.sort();
+    timestamps.dedup();
+
+    Ok(timestamps
+        .into_iter()
+        .map(|timestamp| {
+            // Before the first snapshot, the first known number of issues is the best guess
+            let total = totals
+                .iter()
+                .rev()
+                .find(|(t, _)| *t <= timestamp)
+                .unwrap_or(&totals[0])
+                .1;
+            let closed = closed_times.iter().filter(|t| **t <= timestamp).count();
+            BurndownPoint {
+                timestamp,
+                total,
+                closed,
+            }
+        })
+        .collect())
+}
+
+/// Render the burndown of the workspace version as an SVG chart or an HTML page.
+pub fn render_metrics_report(
+    config: &Config,
+    points: &[BurndownPoint],
+    format: MetricsReportFormat,
+) -> Result<String> {
+    let title = format!("Testing progress for version: {}", config.workspace_version);
+    let chart = render_burndown_svg(&title, points);
+    match format {
+        MetricsReportFormat::Svg => Ok(chart),
+        MetricsReportFormat::Html => MetricsReportTemplate {
+            title,
+            chart,
+            points: points.to_vec(),
+        }
+        .render()
+        .context("Failed to render the metrics report template"),
+    }
+}
+
+/// Draw the total, closed and remaining issues over time as step lines.
+fn render_burndown_svg(title: &str, points: &[BurndownPoint]) -> String {
+    let start = points.first().map(|p| p.timestamp).unwrap_or_default();
+    let end = points.last().map(|p| p.timestamp).unwrap_or_default();
+    let duration = ((end - start).num_seconds() as f64).max(1.0);
+    let max_count = points
+        .iter()
+        .map(|p| p.total)
+        .max()
+        .unwrap_or_default()
+        .max(1) as f64;
+    let plot_width = CHART_WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_height = CHART_HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let x = |timestamp: DateTime<Utc>| {
+        MARGIN_LEFT + (timestamp - start).num_seconds() as f64 / duration * plot_width
+    };
+    let y = |count: usize| MARGIN_TOP + plot_height - count as f64 / max_count * plot_height;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"12\">\n",
+        w = CHART_WIDTH,
+        h = CHART_HEIGHT
+    );
+    svg.push_str(&format!(
+        "<rect width=\"{}\" height=\"{}\" fill=\"white\"/>\n",
+        CHART_WIDTH, CHART_HEIGHT
+    ));
+    svg.push_str(&format!(
+        "<text x=\"{}\" y=\"30\" text-anchor=\"middle\" font-size=\"16\">{}</text>\n",
+        CHART_WIDTH / 2.0,
+        escape_xml(title)
+    ));
+
+    // The axes, with the count ticks on the left and the first and last day below
+    let bottom = MARGIN_TOP + plot_height;
+    svg.push_str(&format!(
+        "<path d=\"M{left},{top} V{bottom} H{right}\" fill=\"none\" stroke=\"#57606a\"/>\n",
+        left = MARGIN_LEFT,
+        top = MARGIN_TOP,
+        bottom = bottom,
+        right = MARGIN_LEFT + plot_width
+    ));
+    let mut ticks = vec![0, max_count as usize / 2, max_count as usize];
+    ticks.dedup();
+    for tick in ticks {
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\" dominant-baseline=\"middle\">{}</text>\n",
+            MARGIN_LEFT - 8.0,
+            y(tick),
+            tick
+        ));
+    }
+    if let (Some(first), Some(last)) = (points.first(), points.last()) {
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"start\">{}</text>\n",
+            MARGIN_LEFT,
+            bottom + 18.0,
+            first.timestamp.format("%Y-%m-%d")
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>\n",
+            MARGIN_LEFT + plot_width,
+            bottom + 18.0,
+            last.timestamp.format("%Y-%m-%d")
+        ));
+    }
+
+    let series: [Series; 3] = [
+        ("Total", "#57606a", |p| p.total),
+        ("Closed", "#1a7f37", |p| p.closed),
+        ("Remaining", "#cf222e", |p| p.remaining()),
+    ];
+    for (i, (name, color, get_count)) in series.iter().enumerate() {
+        if let Some(first) = points.first() {
+            let mut path = format!("M{:.1},{:.1}", x(first.timestamp), y(get_count(first)));
+            for point in &points[1..] {
+                path.push_str(&format!(
+                    " H{:.1} V{:.1}",
+                    x(point.timestamp),
+                    y(get_count(point))
+                ));
+            }
+            svg.push_str(&format!(
+                "<path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"><title>{}</title></path>\n",
+                path, color, name
+            ));
+        }
+        let legend_x = MARGIN_LEFT + i as f64 * 120.0;
+        let legend_y = CHART_HEIGHT - 20.0;
+        svg.push_str(&format!(
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"12\" height=\"12\" fill=\"{}\"/>\n",
+            legend_x,
+            legend_y - 10.0,
+            color
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{:.1}\" y=\"{:.1}\">{}</text>\n",
+            legend_x + 18.0,
+            legend_y,
+            name
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn parse_timestamp(timestamp: &str) -> Result<DateTime<Utc>> {
+    Ok(DateTime::parse_from_rfc3339(timestamp)
+        .context(format!("Failed to parse the timestamp: {}", timestamp))?
+        .with_timezone(&Utc))
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test_metrics_report {
+    use super::*;
+    use crate::test_utils::make_tracker_issue;
+    use crate::types::MetricsRow;
+
+    fn make_issue(number: u64, closed_at: Option<&str>, version_label: &str) -> TrackerIssue {
+        let mut issue = make_tracker_issue(
+            number,
+            &format!("Issue {}", number),
+            None,
+            vec![version_label.to_string()],
+        );
+        if let Some(closed_at) = closed_at {
+            issue.state = TrackerIssueState::Closed;
+            issue.closed_at = Some(closed_at.to_string());
+        }
+        issue
+    }
+
+    fn make_snapshot(timestamp: &str, workspace_version: &str, total: usize) -> MetricsSnapshot {
+        MetricsSnapshot {
+            timestamp: timestamp.to_string(),
+            workspace_version: workspace_version.to_string(),
+            label: None,
+            rows: vec![MetricsRow::new("all", "all", total, 0)],
+        }
+    }
+
+    fn get_points() -> (Config, Vec<BurndownPoint>) {
+        let config = Config::default();
+        let version_label = project_version_to_label(&config.workspace_version);
+        let snapshots = vec![
+            make_snapshot("2024-01-01T00:00:00Z", &config.workspace_version, 3),
+            // Snapshots of other versions don't count
+            make_snapshot("2024-01-02T00:00:00Z", "0.0.0", 10),
+            make_snapshot("2024-01-03T00:00:00Z", &config.workspace_version, 4),
+        ];
+        let issues = vec![
+            make_issue(1, Some("2024-01-02T12:00:00Z"), &version_label),
+            make_issue(2, Some("2024-01-04T00:00:00Z"), &version_label),
+            make_issue(3, None, &version_label),
+            make_issue(4, None, &version_label),
+            make_issue(5, None, "version: 0.0.0"),
+        ];
+        let now = parse_timestamp("2024-01-05T00:00:00Z").unwrap();
+        let points = get_burndown(&config, &snapshots, &issues, now).unwrap();
+        (config, points)
+    }
+
+    #[test]
+    fn gets_the_burndown() {
+        let (_, points) = get_points();
+        let counts: Vec<(String, usize, usize, usize)> = points
+            .iter()
+            .map(|p| {
+                (
+                    p.timestamp.format("%m-%d %H").to_string(),
+                    p.total,
+                    p.closed,
+                    p.remaining(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            counts,
+            vec![
+                ("01-01 00".to_string(), 3, 0, 3),
+                ("01-02 12".to_string(), 3, 1, 2),
+                ("01-03 00".to_string(), 4, 1, 3),
+                ("01-04 00".to_string(), 4, 2, 2),
+                ("01-05 00".to_string(), 4, 2, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_the_report() {
+        let (config, points) = get_points();
+
+        let svg = render_metrics_report(&config, &points, MetricsReportFormat::Svg).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(&format!(
+            "Testing progress for version: {}",
+            config.workspace_version
+        )));
+        assert_eq!(svg.matches("<title>").count(), 3);
+        assert!(svg.contains("2024-01-01"));
+        assert!(svg.contains("2024-01-05"));
+
+        let html = render_metrics_report(&config, &points, MetricsReportFormat::Html).unwrap();
+        assert!(html.contains(&svg));
+        assert!(html.contains("<td>2024-01-02 12:00</td><td>3</td><td>1</td><td>2</td>"));
+    }
+}
@@ -1,5 +1,14 @@
+mod assign;
+mod bulk;
 mod cli;
+mod coverage;
+mod get_issue_tracker;
 mod init_workspace;
 mod load_config;
+mod meta_issue;
+mod metrics;
+mod metrics_report;
+mod pull_results;
+mod upload;
 
 pub use cli::cli;
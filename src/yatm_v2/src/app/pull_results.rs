@@ -0,0 +1,360 @@
+use crate::helpers::{get_checklists, get_id_from_body, project_version_to_label};
+use crate::types::{Config, IssueResult, ResultSource, ResultsFile, StepPassRate, TestResult};
+use anyhow::Result;
+use common::issue_tracker::IssueTracker;
+use common::types::{
+    TrackerComment, TrackerIssue, TrackerIssueEvent, TrackerIssueEventKind, TrackerIssueState,
+};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Pull the comments, label changes and closers of every issue of the workspace version and
+/// classify each issue's result.
+pub async fn pull_results(config: &Config, tracker: &dyn IssueTracker) -> Result<ResultsFile> {
+    let version_label = project_version_to_label(&config.workspace_version);
+    let mut issues: Vec<(String, TrackerIssue)> = tracker
+        .get_issues(None)
+        .await?
+        .into_iter()
+        .filter(|i| !i.is_pull_request && i.labels.contains(&version_label))
+        .filter_map(|i| Some((i.body.as_deref().and_then(get_id_from_body)?, i)))
+        .collect();
+    issues.sort_by_key(|(_, i)| i.number);
+
+    let results: Vec<IssueResult> = stream::iter(issues)
+        .map(|(test_case_id, issue)| async move {
+            let comments = tracker.get_issue_comments(issue.number).await?;
+            let events = tracker.get_issue_events(issue.number).await?;
+            Ok::<_, anyhow::Error>(get_issue_result(
+                config,
+                test_case_id,
+                &issue,
+                &comments,
+                &events,
+            ))
+        })
+        .buffered(config.concurrency.max(1))
+        .try_collect()
+        .await?;
+
+    Ok(ResultsFile {
+        workspace_version: config.workspace_version.clone(),
+        repo: format!("{}/{}", config.repo_owner, config.repo_name),
+        pulled_at: chrono::Utc::now().to_rfc3339(),
+        step_pass_rates: get_step_pass_rates(&results),
+        results,
+    })
+}
+
+/// Get how often each checklist step of each requirement passed.
+///
+/// Only the issues that were reported on count, either with a result or by ticking a box, so that
+/// issues nobody has picked up yet don't drag the rates down.
+pub fn get_step_pass_rates(results: &[IssueResult]) -> Vec<StepPassRate> {
+    let mut counts: BTreeMap<(&String, usize), (usize, usize)> = BTreeMap::new();
+    for result in results {
+        let reported =
+            result.result != TestResult::Untested || result.checklist.iter().any(|c| c.checked > 0);
+        if !reported {
+            continue;
+        }
+        for checklist in result.checklist.iter() {
+            let (passed, reported) = counts.entry((&result.title, checklist.step)).or_default();
+            *reported += 1;
+            if checklist.passed() {
+                *passed += 1;
+            }
+        }
+    }
+    counts
+        .into_iter()
+        .map(|((requirement, step), (passed, reported))| StepPassRate {
+            requirement: requirement.clone(),
+            step,
+            passed,
+            reported,
+            percent_passed: passed as f32 / reported as f32 * 100.0,
+        })
+        .collect()
+}
+
+/// Classify the result of an issue.
+///
+/// The result labels on the issue come first, with the worst one winning, then the `Result:` line
+/// of the latest comment that has one. The comments from before the issue was last reopened are
+/// stale and left out. An issue that was closed without either passed, and any other issue is
+/// untested.
+pub fn get_issue_result(
+    config: &Config,
+    test_case_id: String,
+    issue: &TrackerIssue,
+    comments: &[TrackerComment],
+    events: &[TrackerIssueEvent],
+) -> IssueResult {
+    let labels = &config.result_labels;
+    let label_results = [
+        (TestResult::BadInstructions, &labels.bad_instructions),
+        (TestResult::Failed, &labels.failed),
+        (TestResult::Blocked, &labels.blocked),
+        (TestResult::Passed, &labels.passed),
+    ];
+    let label_result = label_results.iter().find_map(|(result, result_labels)| {
+        let label = result_labels.iter().find(|l| issue.labels.contains(l))?;
+        Some((*result, label))
+    });
+    let last_close = events
+        .iter()
+        .rev()
+        .find(|e| e.kind == TrackerIssueEventKind::Closed);
+    let last_reopen = events
+        .iter()
+        .rev()
+        .find(|e| e.kind == TrackerIssueEventKind::Reopened)
+        .and_then(|e| parse_timestamp(e.created_at.as_deref()));
+
+    let (result, source, reported_by, reported_at) = if let Some((result, label)) = label_result {
+        let labeled = events
+            .iter()
+            .rev()
+            .find(|e| e.kind == TrackerIssueEventKind::Labeled(label.clone()));
+        (
+            result,
+            ResultSource::Label,
+            labeled.and_then(|e| e.actor.clone()),
+            labeled.and_then(|e| e.created_at.clone()),
+        )
+    } else if let Some((result, comment)) = comments
+        .iter()
+        .rev()
+        .filter(|c| {
+            last_reopen.is_none_or(|reopened| {
+                parse_timestamp(c.created_at.as_deref()).is_none_or(|created| created > reopened)
+            })
+        })
+        .find_map(|c| Some((get_comment_result(&c.body)?, c)))
+    {
+        (
+            result,
+            ResultSource::Comment,
+            comment.author.clone(),
+            comment.created_at.clone(),
+        )
+    } else if issue.state == TrackerIssueState::Closed {
+        (
+            TestResult::Passed,
+            ResultSource::Closed,
+            last_close.and_then(|e| e.actor.clone()),
+            issue
+                .closed_at
+                .clone()
+                .or(last_close.and_then(|e| e.created_at.clone())),
+        )
+    } else {
+        (TestResult::Untested, ResultSource::None, None, None)
+    };
+
+    let participants: BTreeSet<String> = comments
+        .iter()
+        .filter_map(|c| c.author.clone())
+        .chain(events.iter().filter_map(|e| match e.kind {
+            TrackerIssueEventKind::Labeled(_) | TrackerIssueEventKind::Unlabeled(_) => {
+                e.actor.clone()
+            }
+            _ => None,
+        }))
+        .collect();
+
+    IssueResult {
+        test_case_id,
+        issue_number: issue.number,
+        title: issue.title.clone(),
+        url: issue.url.clone(),
+        state: issue.state,
+        result,
+        source,
+        reported_by,
+        reported_at,
+        closed_by: match issue.state {
+            TrackerIssueState::Closed => last_close.and_then(|e| e.actor.clone()),
+            TrackerIssueState::Open => None,
+        },
+        participants: participants.into_iter().collect(),
+        comment_count: comments.len(),
+        checklist: get_checklists(issue.body.as_deref().unwrap_or_default()),
+    }
+}
+
+fn parse_timestamp(timestamp: Option<&str>) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    chrono::DateTime::parse_from_rfc3339(timestamp?).ok()
+}
+
+/// Read the result from a `Result: <pass|fail|blocked|bad instructions>` line of a comment.
+fn get_comment_result(body: &str) -> Option<TestResult> {
+    body.lines().find_map(|line| {
+        let line = line.trim().trim_matches('*').to_lowercase();
+        let value = line.strip_prefix("result:")?;
+        let value =
+            value.trim_matches(|c: char| c.is_whitespace() || c == '*' || c.is_ascii_punctuation());
+        match value {
+            "pass" | "passed" | "works" | "works as expected" => Some(TestResult::Passed),
+            "fail" | "failed" | "bug" => Some(TestResult::Failed),
+            "blocked" => Some(TestResult::Blocked),
+            "bad instructions" => Some(TestResult::BadInstructions),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod test_pull_results {
+    use super::*;
+    use crate::test_utils::make_tracker_issue;
+    use common::fake_github::FakeGithub;
+    use common::github::Github;
+
+    fn make_comment(author: &str, body: &str) -> TrackerComment {
+        TrackerComment {
+            author: Some(author.to_string()),
+            body: body.to_string(),
+            created_at: Some("2024-01-01T00:00:00Z".to_string()),
+        }
+    }
+
+    #[test]
+    fn reads_results_from_comments() {
+        assert_eq!(get_comment_result("Result: pass"), Some(TestResult::Passed));
+        assert_eq!(
+            get_comment_result("Tried it on my laptop.\n**Result:** Bad instructions."),
+            Some(TestResult::BadInstructions)
+        );
+        assert_eq!(
+            get_comment_result("result: BLOCKED"),
+            Some(TestResult::Blocked)
+        );
+        assert_eq!(get_comment_result("It failed"), None);
+        assert_eq!(get_comment_result("Result: maybe"), None);
+    }
+
+    #[test]
+    fn classifies_issues() {
+        let config = Config::default();
+        let mut issue = make_tracker_issue(1, "Test", None, vec![]);
+        let get_result = |issue: &TrackerIssue, comments: &[TrackerComment]| {
+            let result = get_issue_result(&config, "id".to_string(), issue, comments, &[]);
+            (result.result, result.source)
+        };
+
+        assert_eq!(
+            get_result(&issue, &[]),
+            (TestResult::Untested, ResultSource::None)
+        );
+        // The latest comment with a result wins
+        let comments = vec![
+            make_comment("a", "Result: fail"),
+            make_comment("b", "Result: pass"),
+            make_comment("c", "Thanks!"),
+        ];
+        assert_eq!(
+            get_result(&issue, &comments),
+            (TestResult::Passed, ResultSource::Comment)
+        );
+        // A result label outranks the comments, and the worst label wins
+        issue.labels = vec![
+            "confirmed: works as expected".to_string(),
+            "confirmed: bug".to_string(),
+        ];
+        assert_eq!(
+            get_result(&issue, &comments),
+            (TestResult::Failed, ResultSource::Label)
+        );
+        // The comments from before the issue was reopened don't count
+        let reopened = TrackerIssueEvent {
+            kind: TrackerIssueEventKind::Reopened,
+            actor: None,
+            created_at: Some("2024-02-01T00:00:00Z".to_string()),
+        };
+        issue.labels = vec![];
+        let result = get_issue_result(&config, "id".to_string(), &issue, &comments, &[reopened]);
+        assert_eq!(result.result, TestResult::Untested);
+        issue.state = TrackerIssueState::Closed;
+        assert_eq!(
+            get_result(&issue, &[]),
+            (TestResult::Passed, ResultSource::Closed)
+        );
+    }
+
+    #[test]
+    fn gets_the_step_pass_rates() {
+        let config = Config::default();
+        let make_result = |body: &str, labels: &[&str]| {
+            let issue = make_tracker_issue(
+                1,
+                "Test",
+                Some(body),
+                labels.iter().map(|l| l.to_string()).collect(),
+            );
+            get_issue_result(&config, "id".to_string(), &issue, &[], &[])
+        };
+        let results = vec![
+            make_result("<!-- yatm-step: 1 -->\n- [x] a\n- [x] b", &[]),
+            make_result(
+                "<!-- yatm-step: 1 -->\n- [x] a\n- [ ] b",
+                &["confirmed: bug"],
+            ),
+            // Nobody reported on this one yet
+            make_result("<!-- yatm-step: 1 -->\n- [ ] a\n- [ ] b", &[]),
+            make_result("No checklist", &["confirmed: works as expected"]),
+        ];
+        assert_eq!(results[0].checklist.len(), 1);
+        assert_eq!(
+            get_step_pass_rates(&results),
+            vec![StepPassRate {
+                requirement: "Test".to_string(),
+                step: 1,
+                passed: 1,
+                reported: 2,
+                percent_passed: 50.0,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn pulls_results_from_github() {
+        let config = Config {
+            repo_owner: "owner".to_string(),
+            repo_name: "repo".to_string(),
+            ..Default::default()
+        };
+        let version_label = project_version_to_label(&config.workspace_version);
+        let fake_github = FakeGithub::start("owner", "repo").await.unwrap();
+        let first = fake_github.add_issue("First", "<!-- yatm-id: a -->", &[&version_label]);
+        let second = fake_github.add_issue("Second", "<!-- yatm-id: b -->", &[&version_label]);
+        // Issues without a test case or of another version are left out
+        fake_github.add_issue("Meta", "", &[&version_label]);
+        fake_github.add_issue("Old", "<!-- yatm-id: c -->", &["version: 0.0.0"]);
+        fake_github.add_comment(first, "tester", "Result: blocked\nThe server is down");
+        fake_github.label_issue(second, "tester", "confirmed: works as expected");
+        fake_github.close_issue(second, "lead");
+        let github = Github::new_with_token(
+            fake_github.url(),
+            &config.repo_owner,
+            &config.repo_name,
+            "token",
+        )
+        .unwrap();
+
+        let results = pull_results(&config, &github).await.unwrap();
+        assert_eq!(results.repo, "owner/repo");
+        assert_eq!(results.results.len(), 2);
+        let first = &results.results[0];
+        assert_eq!(first.test_case_id, "a");
+        assert_eq!(first.result, TestResult::Blocked);
+        assert_eq!(first.reported_by.as_deref(), Some("tester"));
+        assert_eq!(first.comment_count, 1);
+        let second = &results.results[1];
+        assert_eq!(second.result, TestResult::Passed);
+        assert_eq!(second.source, ResultSource::Label);
+        assert_eq!(second.closed_by.as_deref(), Some("lead"));
+        assert_eq!(second.participants, vec!["tester"]);
+    }
+}
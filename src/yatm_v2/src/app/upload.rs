@@ -0,0 +1,1245 @@
+use crate::constants::OBSOLETE_LABEL;
+use crate::helpers::{
+    append_upload_journal_entry, get_content_hash, get_github_labels, get_id_from_body,
+    get_issue_state_hash, get_label_changes, get_local_issues_matches,
+    get_orphan_version_from_body, get_orphaned_issues, get_project_fields, get_remote_state_hash,
+    get_test_case_id, get_test_cases, make_orphan_marker, project_version_to_label,
+    read_upload_journal, test_case_to_markdown, IssueMatchType, ManagedLabels,
+};
+use crate::types::{
+    Config, OrphanAction, UploadJournalEntry, UploadJournalStatus, UploadOperation, UploadOptions,
+    UploadPlan,
+};
+use anyhow::{Context, Result};
+use common::issue_tracker::IssueTracker;
+use common::types::{GithubLabel, TestCase, TrackerComment, TrackerIssue, TrackerIssueState};
+use futures::stream::{self, StreamExt};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+
+/// Make the plan to bring the issues on the issue tracker in line with the test cases.
+pub async fn make_upload_plan(
+    config: &Config,
+    tracker: &dyn IssueTracker,
+    options: &UploadOptions,
+    journal: &[UploadJournalEntry],
+) -> Result<UploadPlan> {
+    // Get the test cases
+    let test_cases = get_test_cases(config)?;
+    if test_cases.is_empty() {
+        anyhow::bail!("No test cases found");
+    }
+
+    // Convert the test cases to markdown
+    let local_issues = test_cases
+        .iter()
+        .map(|test_case| {
+            test_case_to_markdown(test_case.clone(), &config.workspace_version).context(format!(
+                "Failed to convert test case to markdown: {:?}",
+                test_case
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    println!("{} test cases total", local_issues.len());
+
+    // Get the labels and issues from Github
+    println!(
+        "Connecting to repository: {}/{}",
+        config.repo_owner, config.repo_name
+    );
+    let github_labels: Vec<String> = tracker
+        .get_labels()
+        .await?
+        .into_iter()
+        .map(|l| l.name)
+        .collect();
+    let github_issues = tracker.get_issues(None).await?;
+
+    let mut operations: Vec<UploadOperation> = vec![];
+
+    // Create the labels used by the test cases that don't exist on Github yet, comparing the names
+    // without case like Github does
+    let mut test_case_labels: BTreeMap<String, GithubLabel> = BTreeMap::new();
+    for test_case in &test_cases {
+        for label in get_github_labels(test_case, &config.workspace_version, &config.label_colors) {
+            test_case_labels
+                .entry(label.name.to_lowercase())
+                .or_insert(label);
+        }
+    }
+    let existing_labels: HashSet<String> = github_labels.iter().map(|l| l.to_lowercase()).collect();
+    let missing_labels: Vec<GithubLabel> = test_case_labels
+        .into_iter()
+        .filter(|(name, _)| !existing_labels.contains(name))
+        .map(|(_, label)| label)
+        .collect();
+    println!("{} labels missing on Github", missing_labels.len());
+    if !missing_labels.is_empty() {
+        operations.push(UploadOperation::CreateLabels {
+            labels: missing_labels,
+        });
+    }
+
+    // Create the milestone of the workspace version if it doesn't exist yet
+    let mut remote_milestones: Vec<String> = vec![];
+    let milestone = match &config.milestone {
+        Some(milestone) => {
+            let title = milestone
+                .title
+                .clone()
+                .unwrap_or(config.workspace_version.clone());
+            if let Some(due_on) = &milestone.due_on {
+                chrono::NaiveDate::parse_from_str(due_on, "%Y-%m-%d").context(format!(
+                    "Invalid milestone due date, expected YYYY-MM-DD: {}",
+                    due_on
+                ))?;
+            }
+            remote_milestones = tracker.get_milestones().await?;
+            remote_milestones.sort();
+            if !remote_milestones.contains(&title) {
+                println!("Milestone '{}' missing on Github", title);
+                operations.push(UploadOperation::CreateMilestone {
+                    title: title.clone(),
+                    due_on: milestone.due_on.clone(),
+                });
+            }
+            Some(title)
+        }
+        None => None,
+    };
+
+    // Issues created by earlier uploads, in case their identity key didn't make it to Github
+    let repo = format!("{}/{}", config.repo_owner, config.repo_name);
+    let journal_issue_numbers: HashMap<String, u64> = journal
+        .iter()
+        .filter(|e| e.repo == repo && e.status == UploadJournalStatus::Done)
+        .filter_map(|e| Some((e.test_case_id.clone()?, e.issue_number?)))
+        .collect();
+
+    let matched_issues =
+        get_local_issues_matches(&local_issues, &github_issues, &journal_issue_numbers);
+    for i in &matched_issues {
+        let url = i
+            .github_issue
+            .as_ref()
+            .map_or("No URL for issue".to_string(), |issue| issue.url.clone());
+        match i.match_type {
+            IssueMatchType::Match => println!("Matched: {} -- {}", i.local_issue.title, url),
+            IssueMatchType::MatchedWithDiff => {
+                println!("Changed: {} -- {}", i.local_issue.title, url)
+            }
+            IssueMatchType::EditedOnGithub => {
+                println!("Edited on Github: {} -- {}", i.local_issue.title, url)
+            }
+            IssueMatchType::Missing => println!("Missing: {}", i.local_issue.title),
+        }
+    }
+
+    // Find the issues of the previous version to carry over to the test cases without one
+    let triage_labels: Vec<String> = config.labels.iter().map(|l| l.name.clone()).collect();
+    let managed_labels = ManagedLabels::new(&test_cases, &triage_labels);
+    let mut previous_issues: HashMap<&String, &TrackerIssue> = HashMap::new();
+    if let Some(previous_version) = &options.carry_over_from {
+        if previous_version == &config.workspace_version {
+            anyhow::bail!(
+                "Can't carry over the issues of the current workspace version: {}",
+                previous_version
+            );
+        }
+        let github_issues_by_id: HashMap<String, &TrackerIssue> = github_issues
+            .iter()
+            .filter(|i| !i.is_pull_request)
+            .filter_map(|i| Some((i.body.as_deref().and_then(get_id_from_body)?, i)))
+            .collect();
+        for (m, test_case) in matched_issues.iter().zip(&test_cases) {
+            if m.match_type != IssueMatchType::Missing {
+                continue;
+            }
+            let previous_id = get_test_case_id(test_case, previous_version);
+            if let Some(issue) = github_issues_by_id.get(&previous_id) {
+                previous_issues.insert(&m.local_issue.id, issue);
+            }
+        }
+    }
+
+    // Create issues for the test cases that have neither an issue on Github nor one to carry over,
+    // and add them to the project board and milestone
+    let test_cases_by_id: HashMap<&String, &TestCase> = local_issues
+        .iter()
+        .map(|i| &i.id)
+        .zip(test_cases.iter())
+        .collect();
+    let mut created_issues_count = 0;
+    for m in &matched_issues {
+        if m.match_type != IssueMatchType::Missing
+            || previous_issues.contains_key(&m.local_issue.id)
+        {
+            continue;
+        }
+        created_issues_count += 1;
+        operations.push(UploadOperation::CreateIssue {
+            id: m.local_issue.id.clone(),
+            title: m.local_issue.title.clone(),
+            body: m.local_issue.text_body.clone(),
+            labels: m.local_issue.labels.clone(),
+        });
+        if let Some(project) = &config.github_project {
+            operations.push(UploadOperation::AddToProject {
+                id: m.local_issue.id.clone(),
+                title: m.local_issue.title.clone(),
+                fields: get_project_fields(project, test_cases_by_id[&m.local_issue.id]),
+            });
+        }
+        if let Some(milestone) = &milestone {
+            operations.push(UploadOperation::SetMilestone {
+                id: m.local_issue.id.clone(),
+                number: None,
+                title: m.local_issue.title.clone(),
+                milestone: milestone.clone(),
+            });
+        }
+    }
+    println!("{} test cases without issues", created_issues_count);
+
+    // Move the issues of the previous version over to the current one. The issues of the test
+    // cases whose content didn't change keep their result, and the others lose it and are reopened.
+    // The content is hashed as it is on Github now, so edits made there count as changes.
+    if let Some(previous_version) = &options.carry_over_from {
+        let result_labels = &config.result_labels;
+        let result_labels: Vec<&String> = result_labels
+            .passed
+            .iter()
+            .chain(&result_labels.failed)
+            .chain(&result_labels.blocked)
+            .chain(&result_labels.bad_instructions)
+            .collect();
+        let previous_version_label = project_version_to_label(previous_version);
+        let version_label = project_version_to_label(&config.workspace_version);
+        let mut changed_count = 0;
+        for m in &matched_issues {
+            let Some(issue) = previous_issues.get(&m.local_issue.id) else {
+                continue;
+            };
+            let is_changed = get_content_hash(&issue.title, issue.body.as_deref().unwrap_or(""))
+                != m.local_issue.content_hash;
+            println!(
+                "Carried over{}: {} -- {}",
+                if is_changed { " with changes" } else { "" },
+                m.local_issue.title,
+                issue.url
+            );
+            if is_changed {
+                changed_count += 1;
+            }
+            operations.push(UploadOperation::UpdateIssue {
+                number: issue.number,
+                title: m.local_issue.title.clone(),
+                body: m.local_issue.text_body.clone(),
+            });
+            let mut label_changes =
+                get_label_changes(&m.local_issue.labels, &issue.labels, &managed_labels);
+            if is_changed {
+                label_changes.to_remove.extend(
+                    issue
+                        .labels
+                        .iter()
+                        .filter(|l| result_labels.contains(l))
+                        .cloned(),
+                );
+            }
+            if !label_changes.is_empty() {
+                operations.push(UploadOperation::Relabel {
+                    number: issue.number,
+                    title: m.local_issue.title.clone(),
+                    add: label_changes.to_add,
+                    remove: label_changes.to_remove,
+                });
+            }
+            operations.push(UploadOperation::CarryOver {
+                id: m.local_issue.id.clone(),
+                number: issue.number,
+                title: m.local_issue.title.clone(),
+                body: match is_changed {
+                    true => format!(
+                        "This test case changed since `{}`. Please test it again for `{}`.",
+                        previous_version_label, version_label
+                    ),
+                    false => format!(
+                        "This test case didn't change since `{}`, so its result carries over to `{}`.",
+                        previous_version_label, version_label
+                    ),
+                },
+                reopen: is_changed && issue.state == TrackerIssueState::Closed,
+            });
+            if let Some(milestone) = milestone
+                .as_ref()
+                .filter(|&m| issue.milestone.as_ref() != Some(m))
+            {
+                operations.push(UploadOperation::SetMilestone {
+                    id: m.local_issue.id.clone(),
+                    number: Some(issue.number),
+                    title: m.local_issue.title.clone(),
+                    milestone: milestone.clone(),
+                });
+            }
+        }
+        println!(
+            "{} issues carried over from `{}`, {} of them changed",
+            previous_issues.len(),
+            previous_version_label,
+            changed_count
+        );
+    }
+
+    // Don't overwrite issues that someone edited on Github unless forced to
+    let edited_issues_count = matched_issues
+        .iter()
+        .filter(|m| m.match_type == IssueMatchType::EditedOnGithub)
+        .count();
+    if edited_issues_count > 0 && !options.force {
+        println!(
+            "{} issues were edited on Github since the last upload and will not be updated, use --force to overwrite them",
+            edited_issues_count
+        );
+    }
+
+    // Edit issues that don't exactly match
+    let mut changed_issues_count = 0;
+    for m in &matched_issues {
+        let Some(github_issue) = &m.github_issue else {
+            continue;
+        };
+        if m.match_type == IssueMatchType::MatchedWithDiff
+            || (options.force && m.match_type == IssueMatchType::EditedOnGithub)
+        {
+            changed_issues_count += 1;
+            operations.push(UploadOperation::UpdateIssue {
+                number: github_issue.number,
+                title: m.local_issue.title.clone(),
+                body: m.local_issue.text_body.clone(),
+            });
+        }
+    }
+    println!("{} test cases with changed issues", changed_issues_count);
+
+    // Sync the labels of the issues that already exist, leaving the labels added by hand alone
+    let mut relabeled_issues_count = 0;
+    for m in &matched_issues {
+        let Some(github_issue) = &m.github_issue else {
+            continue;
+        };
+        let github_issue_labels: Vec<String> = github_issue.labels.clone();
+        let label_changes =
+            get_label_changes(&m.local_issue.labels, &github_issue_labels, &managed_labels);
+        if label_changes.is_empty() {
+            continue;
+        }
+        relabeled_issues_count += 1;
+        operations.push(UploadOperation::Relabel {
+            number: github_issue.number,
+            title: m.local_issue.title.clone(),
+            add: label_changes.to_add,
+            remove: label_changes.to_remove,
+        });
+    }
+    println!("{} issues with changed labels", relabeled_issues_count);
+
+    // Assign the issues that already exist to the milestone
+    if let Some(milestone) = &milestone {
+        let mut unassigned_issues_count = 0;
+        for m in &matched_issues {
+            let Some(github_issue) = &m.github_issue else {
+                continue;
+            };
+            if github_issue.milestone.as_ref() == Some(milestone) {
+                continue;
+            }
+            unassigned_issues_count += 1;
+            operations.push(UploadOperation::SetMilestone {
+                id: m.local_issue.id.clone(),
+                number: Some(github_issue.number),
+                title: m.local_issue.title.clone(),
+                milestone: milestone.clone(),
+            });
+        }
+        println!("{} issues not in the milestone", unassigned_issues_count);
+    }
+
+    // Handle open issues for this version that no test case produces anymore
+    let orphaned_issues =
+        get_orphaned_issues(&matched_issues, &github_issues, &config.workspace_version);
+    println!("{} orphaned issues", orphaned_issues.len());
+    let version_label = project_version_to_label(&config.workspace_version);
+    let mut remote_comments: BTreeMap<u64, Vec<TrackerComment>> = BTreeMap::new();
+    for issue in orphaned_issues {
+        println!("Orphaned: {} -- {}", issue.title, issue.url);
+        match options.orphans {
+            OrphanAction::List => {}
+            OrphanAction::Close => operations.push(UploadOperation::CloseIssue {
+                number: issue.number,
+                title: issue.title.clone(),
+            }),
+            OrphanAction::Comment => {
+                let comments = tracker.get_issue_comments(issue.number).await?;
+                let is_commented = comments.iter().any(|c| {
+                    get_orphan_version_from_body(&c.body).as_ref()
+                        == Some(&config.workspace_version)
+                });
+                if !is_commented {
+                    remote_comments.insert(issue.number, comments);
+                    operations.push(UploadOperation::Comment {
+                        number: issue.number,
+                        title: issue.title.clone(),
+                        body: format!(
+                            "This test case is no longer generated for `{}`.\n\n{}",
+                            version_label,
+                            make_orphan_marker(&config.workspace_version)
+                        ),
+                    });
+                }
+            }
+            OrphanAction::Label => {
+                if !issue.labels.iter().any(|l| l == OBSOLETE_LABEL) {
+                    operations.push(UploadOperation::Relabel {
+                        number: issue.number,
+                        title: issue.title.clone(),
+                        add: vec![OBSOLETE_LABEL.to_string()],
+                        remove: vec![],
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(UploadPlan {
+        repo_owner: config.repo_owner.clone(),
+        repo_name: config.repo_name.clone(),
+        workspace_version: config.workspace_version.clone(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        remote_state_hash: get_remote_state_hash(&github_issues, &github_labels, &remote_comments),
+        remote_issue_hashes: github_issues
+            .iter()
+            .map(|i| {
+                (
+                    i.number,
+                    get_issue_state_hash(i, remote_comments.get(&i.number)),
+                )
+            })
+            .collect(),
+        remote_milestones,
+        operations,
+    })
+}
+
+/// Check that an upload plan was made for the repository and workspace version of the config.
+pub fn check_plan_config(config: &Config, plan: &UploadPlan) -> Result<()> {
+    if plan.repo_owner != config.repo_owner || plan.repo_name != config.repo_name {
+        anyhow::bail!(
+            "The upload plan is for {}/{} but the config is for {}/{}",
+            plan.repo_owner,
+            plan.repo_name,
+            config.repo_owner,
+            config.repo_name
+        );
+    }
+    if plan.workspace_version != config.workspace_version {
+        anyhow::bail!(
+            "The upload plan is for version {} but the config is for version {}, make a new plan",
+            plan.workspace_version,
+            config.workspace_version
+        );
+    }
+    Ok(())
+}
+
+/// Check that the issues, labels, comments and milestones on the issue tracker that the plan is
+/// based on haven't changed since the plan was made.
+///
+/// The comments are checked on the issues the plan comments on, and the milestones when the plan
+/// creates or sets one. When the journal records that the plan was partly applied already, only
+/// the issues that the journal doesn't mention are checked, since the plan itself changed the
+/// others and the labels, and the milestones once the plan created its own.
+pub async fn check_remote_state(
+    tracker: &dyn IssueTracker,
+    plan: &UploadPlan,
+    journal: &[UploadJournalEntry],
+) -> Result<()> {
+    let github_labels: Vec<String> = tracker
+        .get_labels()
+        .await?
+        .into_iter()
+        .map(|l| l.name)
+        .collect();
+    let github_issues = tracker.get_issues(None).await?;
+    let mut comments: BTreeMap<u64, Vec<TrackerComment>> = BTreeMap::new();
+    for operation in &plan.operations {
+        if let UploadOperation::Comment { number, .. } = operation {
+            comments.insert(*number, tracker.get_issue_comments(*number).await?);
+        }
+    }
+    let plan_entries: Vec<&UploadJournalEntry> =
+        journal.iter().filter(|e| e.is_of_plan(plan)).collect();
+
+    let milestone_operations: Vec<&UploadOperation> = plan
+        .operations
+        .iter()
+        .filter(|o| {
+            matches!(
+                o,
+                UploadOperation::CreateMilestone { .. } | UploadOperation::SetMilestone { .. }
+            )
+        })
+        .collect();
+    let is_milestone_created = milestone_operations.iter().any(|o| {
+        matches!(o, UploadOperation::CreateMilestone { .. })
+            && plan_entries
+                .iter()
+                .any(|e| e.operation_key == o.key() && e.status == UploadJournalStatus::Done)
+    });
+    if !milestone_operations.is_empty() && !is_milestone_created {
+        let mut milestones = tracker.get_milestones().await?;
+        milestones.sort();
+        if milestones != plan.remote_milestones {
+            anyhow::bail!(
+                "The milestones in {}/{} changed since the plan was made on {}, make a new plan",
+                plan.repo_owner,
+                plan.repo_name,
+                plan.created_at
+            );
+        }
+    }
+
+    let is_changed = if plan_entries.is_empty() {
+        get_remote_state_hash(&github_issues, &github_labels, &comments) != plan.remote_state_hash
+    } else {
+        let journal_numbers: HashSet<u64> =
+            plan_entries.iter().filter_map(|e| e.issue_number).collect();
+        let issue_hashes: BTreeMap<u64, String> = github_issues
+            .iter()
+            .filter(|i| !journal_numbers.contains(&i.number))
+            .map(|i| (i.number, get_issue_state_hash(i, comments.get(&i.number))))
+            .collect();
+        let planned_issue_hashes: BTreeMap<u64, String> = plan
+            .remote_issue_hashes
+            .iter()
+            .filter(|(number, _)| !journal_numbers.contains(number))
+            .map(|(number, hash)| (*number, hash.clone()))
+            .collect();
+        issue_hashes != planned_issue_hashes
+    };
+    if is_changed {
+        anyhow::bail!(
+            "The issues or labels in {}/{} changed since the plan was made on {}, make a new plan",
+            plan.repo_owner,
+            plan.repo_name,
+            plan.created_at
+        );
+    }
+    Ok(())
+}
+
+/// Apply the operations of an upload plan.
+///
+/// The labels are created first. The operations on different issues are then applied
+/// concurrently, while the operations on the same issue keep the order of the plan.
+///
+/// The outcome of every operation is appended to the upload journal. Operations that the journal
+/// already records as done for this plan are skipped, so an interrupted plan can be applied again.
+/// Issues are added to the project board and milestone once they're created, with the number the
+/// journal records for them when they were created by an earlier attempt.
+pub async fn apply_upload_plan(
+    tracker: &dyn IssueTracker,
+    plan: &UploadPlan,
+    journal_path: &PathBuf,
+    concurrency: usize,
+) -> Result<()> {
+    let done_entries: Vec<UploadJournalEntry> = read_upload_journal(journal_path)?
+        .into_iter()
+        .filter(|e| e.is_of_plan(plan) && e.status == UploadJournalStatus::Done)
+        .collect();
+    let done = DoneOperations {
+        keys: done_entries
+            .iter()
+            .map(|e| e.operation_key.clone())
+            .collect(),
+        created_issue_numbers: done_entries
+            .into_iter()
+            .filter_map(|e| Some((e.test_case_id?, e.issue_number?)))
+            .collect(),
+    };
+
+    // Group the operations by the issue they apply to, in the order of the plan
+    let mut setup_operations: Vec<&UploadOperation> = vec![];
+    let mut issue_operations: Vec<Vec<&UploadOperation>> = vec![];
+    let mut group_indexes: HashMap<String, usize> = HashMap::new();
+    for operation in &plan.operations {
+        let target = match operation {
+            UploadOperation::CreateLabels { .. } | UploadOperation::CreateMilestone { .. } => {
+                setup_operations.push(operation);
+                continue;
+            }
+            UploadOperation::SetMilestone {
+                number: Some(number),
+                ..
+            } => number.to_string(),
+            UploadOperation::CreateIssue { id, .. }
+            | UploadOperation::AddToProject { id, .. }
+            | UploadOperation::SetMilestone { id, .. } => id.clone(),
+            UploadOperation::CarryOver { number, .. }
+            | UploadOperation::UpdateIssue { number, .. }
+            | UploadOperation::Relabel { number, .. }
+            | UploadOperation::Comment { number, .. }
+            | UploadOperation::CloseIssue { number, .. } => number.to_string(),
+        };
+        let index = *group_indexes.entry(target).or_insert_with(|| {
+            issue_operations.push(vec![]);
+            issue_operations.len() - 1
+        });
+        issue_operations[index].push(operation);
+    }
+
+    // The issues can only be created and relabeled once the labels and milestone exist
+    apply_upload_operations(tracker, plan, journal_path, &done, setup_operations).await?;
+
+    // Let every issue finish so its outcome is journaled, then report the first failure
+    let results: Vec<Result<()>> = stream::iter(issue_operations)
+        .map(|operations| apply_upload_operations(tracker, plan, journal_path, &done, operations))
+        .buffer_unordered(concurrency.max(1))
+        .collect()
+        .await;
+    results.into_iter().collect()
+}
+
+/// What the journal records as done for a plan.
+struct DoneOperations {
+    /// The keys of the operations that are done.
+    keys: HashSet<String>,
+    /// The numbers of the issues created, by test case identity key.
+    created_issue_numbers: HashMap<String, u64>,
+}
+
+/// Apply operations in order, journaling the outcome of each and stopping at the first failure.
+async fn apply_upload_operations(
+    tracker: &dyn IssueTracker,
+    plan: &UploadPlan,
+    journal_path: &PathBuf,
+    done: &DoneOperations,
+    operations: Vec<&UploadOperation>,
+) -> Result<()> {
+    let repo = format!("{}/{}", plan.repo_owner, plan.repo_name);
+    let mut created_issue_numbers: HashMap<String, u64> = HashMap::new();
+    for operation in operations {
+        let operation_key = operation.key();
+        if done.keys.contains(&operation_key) {
+            println!("Already done: {}", operation);
+            continue;
+        }
+        println!("{}", operation);
+        let result = apply_upload_operation(tracker, operation, |id| {
+            created_issue_numbers
+                .get(id)
+                .or(done.created_issue_numbers.get(id))
+                .copied()
+        })
+        .await;
+        let (issue_number, status) = match &result {
+            Ok(issue_number) => (*issue_number, UploadJournalStatus::Done),
+            Err(error) => (
+                operation.issue_number(),
+                UploadJournalStatus::Failed(format!("{:#}", error)),
+            ),
+        };
+        append_upload_journal_entry(
+            journal_path,
+            &UploadJournalEntry {
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                repo: repo.clone(),
+                workspace_version: Some(plan.workspace_version.clone()),
+                plan_created_at: plan.created_at.clone(),
+                operation_key,
+                test_case_id: match operation {
+                    UploadOperation::CreateIssue { id, .. }
+                    | UploadOperation::AddToProject { id, .. }
+                    | UploadOperation::SetMilestone { id, .. }
+                    | UploadOperation::CarryOver { id, .. } => Some(id.clone()),
+                    _ => None,
+                },
+                issue_number,
+                status,
+            },
+        )?;
+        if let (UploadOperation::CreateIssue { id, .. }, Some(issue_number)) =
+            (operation, issue_number)
+        {
+            created_issue_numbers.insert(id.clone(), issue_number);
+        }
+        result?;
+    }
+    Ok(())
+}
+
+/// Apply a single operation, returning the number of the issue it was applied to.
+///
+/// The numbers of the issues created for the test cases are looked up with
+/// `get_created_issue_number`.
+async fn apply_upload_operation(
+    tracker: &dyn IssueTracker,
+    operation: &UploadOperation,
+    get_created_issue_number: impl Fn(&String) -> Option<u64>,
+) -> Result<Option<u64>> {
+    let issue_number = match operation {
+        UploadOperation::CreateLabels { labels } => {
+            tracker.create_labels(labels.clone()).await?;
+            None
+        }
+        UploadOperation::CreateIssue {
+            title,
+            body,
+            labels,
+            ..
+        } => Some(
+            tracker
+                .create_issue(title.clone(), body.clone(), labels.clone())
+                .await?,
+        ),
+        UploadOperation::AddToProject { id, fields, .. } => {
+            let number = get_created_issue_number(id).context(format!(
+                "The issue for test case {} wasn't created, can't add it to the project",
+                id
+            ))?;
+            tracker.add_issue_to_project(number, fields).await?;
+            Some(number)
+        }
+        UploadOperation::CreateMilestone { title, due_on } => {
+            tracker.create_milestone(title, due_on.as_deref()).await?;
+            None
+        }
+        UploadOperation::SetMilestone {
+            id,
+            number,
+            milestone,
+            ..
+        } => {
+            let number = number
+                .or_else(|| get_created_issue_number(id))
+                .context(format!(
+                    "The issue for test case {} wasn't created, can't assign it to the milestone",
+                    id
+                ))?;
+            tracker.set_issue_milestone(number, milestone).await?;
+            Some(number)
+        }
+        UploadOperation::CarryOver {
+            number,
+            body,
+            reopen,
+            ..
+        } => {
+            // Reopening first means only the comment, which can't be repeated safely, is left to
+            // do if the comment fails
+            if *reopen {
+                tracker.reopen_issue(*number).await?;
+            }
+            tracker.comment_on_issue(*number, body).await?;
+            Some(*number)
+        }
+        UploadOperation::UpdateIssue {
+            number,
+            title,
+            body,
+        } => {
+            tracker
+                .update_issue(*number, title.clone(), body.clone())
+                .await?;
+            Some(*number)
+        }
+        UploadOperation::Relabel {
+            number,
+            add,
+            remove,
+            ..
+        } => {
+            if !add.is_empty() {
+                tracker.add_labels_to_issue(*number, add).await?;
+            }
+            for label in remove {
+                tracker.remove_label_from_issue(*number, label).await?;
+            }
+            Some(*number)
+        }
+        UploadOperation::Comment { number, body, .. } => {
+            tracker.comment_on_issue(*number, body).await?;
+            Some(*number)
+        }
+        UploadOperation::CloseIssue { number, .. } => {
+            tracker.close_issue(*number).await?;
+            Some(*number)
+        }
+    };
+    Ok(issue_number)
+}
+
+#[cfg(test)]
+mod test_upload {
+    use super::*;
+    use crate::app::init_workspace::init_workspace;
+    use crate::app::load_config::load_config;
+    use crate::constants::UPLOAD_JOURNAL_FILE_NAME;
+    use crate::types::{GithubProject, VersionMilestone};
+    use common::fake_github::FakeGithub;
+    use common::github::Github;
+    use tempfile::tempdir;
+
+    /// A new workspace and the fake Github its test cases are uploaded to.
+    struct TestUpload {
+        config: Config,
+        journal_path: PathBuf,
+        fake_github: FakeGithub,
+        github: Github,
+    }
+
+    async fn start_test_upload() -> TestUpload {
+        let dir = tempdir().unwrap().path().to_path_buf();
+        init_workspace(&dir).unwrap();
+        let config = load_config(&dir).unwrap();
+        let journal_path = config.generated_files_dir.join(UPLOAD_JOURNAL_FILE_NAME);
+        let fake_github = FakeGithub::start(&config.repo_owner, &config.repo_name)
+            .await
+            .unwrap();
+        let github = connect_to_fake_github(&fake_github, &config);
+        TestUpload {
+            config,
+            journal_path,
+            fake_github,
+            github,
+        }
+    }
+
+    fn connect_to_fake_github(fake_github: &FakeGithub, config: &Config) -> Github {
+        Github::new_with_token(
+            fake_github.url(),
+            &config.repo_owner,
+            &config.repo_name,
+            "token",
+        )
+        .unwrap()
+    }
+
+    fn get_options(force: bool) -> UploadOptions {
+        UploadOptions {
+            force,
+            orphans: OrphanAction::List,
+            carry_over_from: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn uploads_and_matches_issues() {
+        let TestUpload {
+            config,
+            journal_path,
+            fake_github,
+            github,
+        } = start_test_upload().await;
+        let number_of_test_cases = get_test_cases(&config).unwrap().len();
+
+        // The first upload creates the labels and an issue per test case
+        let plan = make_upload_plan(&config, &github, &get_options(false), &[])
+            .await
+            .unwrap();
+        apply_upload_plan(&github, &plan, &journal_path, 2)
+            .await
+            .unwrap();
+        assert_eq!(fake_github.issues().len(), number_of_test_cases);
+        let version_label = project_version_to_label(&config.workspace_version);
+        assert!(fake_github.labels().iter().any(|l| l.name == version_label));
+
+        // Uploading again finds every issue and changes nothing
+        let journal = read_upload_journal(&journal_path).unwrap();
+        let plan = make_upload_plan(&config, &github, &get_options(false), &journal)
+            .await
+            .unwrap();
+        assert!(plan.operations.is_empty());
+
+        // Issues edited on Github are only overwritten when forced to
+        fake_github.edit_issue(1, |issue| {
+            issue.body = issue.body.as_ref().map(|b| format!("Edited\n\n{}", b));
+        });
+        let plan = make_upload_plan(&config, &github, &get_options(false), &journal)
+            .await
+            .unwrap();
+        assert!(plan.operations.is_empty());
+        let plan = make_upload_plan(&config, &github, &get_options(true), &journal)
+            .await
+            .unwrap();
+        assert_eq!(plan.operations.len(), 1);
+        assert_eq!(plan.operations[0].key(), "update-issue:1");
+
+        // Changes on Github between planning and applying are noticed
+        fake_github.add_issue("Someone else's issue", "", &[]);
+        assert!(check_remote_state(&github, &plan, &[]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn skips_labels_that_exist_with_another_case() {
+        let TestUpload {
+            config,
+            journal_path,
+            fake_github,
+            github,
+        } = start_test_upload().await;
+        let version_label = project_version_to_label(&config.workspace_version);
+        fake_github.add_label(&version_label.to_uppercase());
+
+        let plan = make_upload_plan(&config, &github, &get_options(false), &[])
+            .await
+            .unwrap();
+        let UploadOperation::CreateLabels { labels } = &plan.operations[0] else {
+            panic!("The plan doesn't start by creating the labels");
+        };
+        assert!(!labels.iter().any(|l| l.name == version_label));
+        apply_upload_plan(&github, &plan, &journal_path, 2)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn applies_a_saved_plan() {
+        let TestUpload {
+            config,
+            journal_path,
+            fake_github,
+            github,
+        } = start_test_upload().await;
+        let number_of_test_cases = get_test_cases(&config).unwrap().len();
+
+        // The plan is saved and read back the way `github plan` and `github apply` do
+        let plan = make_upload_plan(&config, &github, &get_options(false), &[])
+            .await
+            .unwrap();
+        let plan: UploadPlan =
+            serde_yaml::from_str(&serde_yaml::to_string(&plan).unwrap()).unwrap();
+        check_plan_config(&config, &plan).unwrap();
+        check_remote_state(&github, &plan, &[]).await.unwrap();
+        apply_upload_plan(&github, &plan, &journal_path, 2)
+            .await
+            .unwrap();
+        assert_eq!(fake_github.issues().len(), number_of_test_cases);
+    }
+
+    #[tokio::test]
+    async fn rejects_stale_plans() {
+        let TestUpload {
+            mut config,
+            fake_github,
+            github,
+            ..
+        } = start_test_upload().await;
+        let plan = make_upload_plan(&config, &github, &get_options(false), &[])
+            .await
+            .unwrap();
+
+        // The issues changed on Github since the plan was made
+        fake_github.add_issue("Someone else's issue", "", &[]);
+        let error = check_remote_state(&github, &plan, &[]).await.unwrap_err();
+        assert!(error.to_string().contains("make a new plan"));
+
+        // The workspace version was bumped since the plan was made
+        config.workspace_version = "2.0.0".to_string();
+        let error = check_plan_config(&config, &plan).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("the config is for version 2.0.0"));
+        config.repo_name = "other".to_string();
+        assert!(check_plan_config(&config, &plan).is_err());
+    }
+
+    #[tokio::test]
+    async fn checks_the_untouched_issues_when_resuming() {
+        let TestUpload {
+            config,
+            journal_path,
+            fake_github,
+            github,
+        } = start_test_upload().await;
+        let plan = make_upload_plan(&config, &github, &get_options(false), &[])
+            .await
+            .unwrap();
+        apply_upload_plan(&github, &plan, &journal_path, 2)
+            .await
+            .unwrap();
+
+        // Plan to overwrite two issues edited on Github, then only apply the first operation
+        for number in [1, 2] {
+            fake_github.edit_issue(number, |issue| {
+                issue.body = issue.body.as_ref().map(|b| format!("Edited\n\n{}", b));
+            });
+        }
+        let journal = read_upload_journal(&journal_path).unwrap();
+        let plan = make_upload_plan(&config, &github, &get_options(true), &journal)
+            .await
+            .unwrap();
+        assert_eq!(plan.operations.len(), 2);
+        let interrupted_plan = UploadPlan {
+            operations: vec![plan.operations[0].clone()],
+            ..plan.clone()
+        };
+        apply_upload_plan(&github, &interrupted_plan, &journal_path, 2)
+            .await
+            .unwrap();
+
+        // The issue the journal records is left out of the check, the others are still checked
+        let journal = read_upload_journal(&journal_path).unwrap();
+        assert!(check_remote_state(&github, &plan, &[]).await.is_err());
+        check_remote_state(&github, &plan, &journal).await.unwrap();
+        fake_github.edit_issue(2, |issue| issue.title = "Edited".to_string());
+        assert!(check_remote_state(&github, &plan, &journal).await.is_err());
+
+        // The journal of a plan for another workspace version doesn't count
+        let other_plan = UploadPlan {
+            workspace_version: "2.0.0".to_string(),
+            ..plan.clone()
+        };
+        assert!(!journal.iter().any(|e| e.is_of_plan(&other_plan)));
+    }
+
+    #[tokio::test]
+    async fn comments_on_orphaned_issues_once() {
+        let TestUpload {
+            config,
+            journal_path,
+            fake_github,
+            github,
+        } = start_test_upload().await;
+        let version_label = project_version_to_label(&config.workspace_version);
+        let orphan = fake_github.add_issue("Gone", "<!-- yatm-id: gone -->", &[&version_label]);
+        let mut options = get_options(false);
+        options.orphans = OrphanAction::Comment;
+
+        let plan = make_upload_plan(&config, &github, &options, &[])
+            .await
+            .unwrap();
+
+        // Someone commenting on the orphan after the plan was made is noticed
+        fake_github.add_comment(orphan, "tester", "Still needed?");
+        assert!(check_remote_state(&github, &plan, &[]).await.is_err());
+        let plan = make_upload_plan(&config, &github, &options, &[])
+            .await
+            .unwrap();
+        check_remote_state(&github, &plan, &[]).await.unwrap();
+        apply_upload_plan(&github, &plan, &journal_path, 2)
+            .await
+            .unwrap();
+        assert_eq!(fake_github.comments(orphan).len(), 2);
+
+        // The orphan already has the comment, so uploading again doesn't repeat it
+        let journal = read_upload_journal(&journal_path).unwrap();
+        let plan = make_upload_plan(&config, &github, &options, &journal)
+            .await
+            .unwrap();
+        assert!(plan.operations.is_empty());
+    }
+
+    #[tokio::test]
+    async fn adds_created_issues_to_the_project() {
+        let TestUpload {
+            mut config,
+            journal_path,
+            fake_github,
+            ..
+        } = start_test_upload().await;
+        config.github_project = Some(GithubProject {
+            owner: config.repo_owner.clone(),
+            number: 1,
+            builder_field: Some("Builder".to_string()),
+            permutation_fields: BTreeMap::from([(
+                "Operating System".to_string(),
+                "OS".to_string(),
+            )]),
+        });
+        let test_cases = get_test_cases(&config).unwrap();
+
+        fake_github.add_project_field("Builder", &[]);
+        let connect =
+            || connect_to_fake_github(&fake_github, &config).with_project(&config.repo_owner, 1);
+        let github = connect();
+
+        let plan = make_upload_plan(&config, &github, &get_options(false), &[])
+            .await
+            .unwrap();
+        let project_operations = plan
+            .operations
+            .iter()
+            .filter(|o| matches!(o, UploadOperation::AddToProject { .. }))
+            .count();
+        assert_eq!(project_operations, test_cases.len());
+
+        // The project is missing a field, so the issues are created but their fields can't be set
+        assert!(apply_upload_plan(&github, &plan, &journal_path, 2)
+            .await
+            .is_err());
+        assert_eq!(fake_github.issues().len(), test_cases.len());
+
+        // Applying the plan again, like running the upload again would, finishes adding the
+        // issues to the project
+        let github = connect();
+        let operating_systems: HashSet<&String> = test_cases
+            .iter()
+            .filter_map(|t| t.selected_permutation.get("Operating System"))
+            .collect();
+        let operating_systems: Vec<&str> = operating_systems.iter().map(|o| o.as_str()).collect();
+        fake_github.add_project_field("OS", &operating_systems);
+        apply_upload_plan(&github, &plan, &journal_path, 2)
+            .await
+            .unwrap();
+        assert_eq!(fake_github.issues().len(), test_cases.len());
+        let items = fake_github.project_items();
+        assert_eq!(items.len(), test_cases.len());
+        for item in items {
+            assert_eq!(
+                item.fields.keys().collect::<Vec<_>>(),
+                vec!["Builder", "OS"]
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn assigns_issues_to_the_version_milestone() {
+        let TestUpload {
+            mut config,
+            journal_path,
+            fake_github,
+            github,
+        } = start_test_upload().await;
+        config.milestone = Some(VersionMilestone {
+            title: None,
+            due_on: Some("2026-11-30".to_string()),
+        });
+        let number_of_test_cases = get_test_cases(&config).unwrap().len();
+
+        // The milestone is created and the new issues are assigned to it
+        let plan = make_upload_plan(&config, &github, &get_options(false), &[])
+            .await
+            .unwrap();
+        check_remote_state(&github, &plan, &[]).await.unwrap();
+        apply_upload_plan(&github, &plan, &journal_path, 2)
+            .await
+            .unwrap();
+        let milestones = fake_github.milestones();
+        assert_eq!(milestones.len(), 1);
+        assert_eq!(milestones[0].title, config.workspace_version);
+        assert_eq!(
+            milestones[0].due_on.as_deref(),
+            Some("2026-11-30T00:00:00Z")
+        );
+        let issues = fake_github.issues();
+        assert_eq!(issues.len(), number_of_test_cases);
+        assert!(issues
+            .iter()
+            .all(|i| i.milestone.as_ref() == Some(&config.workspace_version)));
+
+        // Existing issues taken out of the milestone are assigned to it again
+        fake_github.edit_issue(1, |issue| issue.milestone = None);
+        let journal = read_upload_journal(&journal_path).unwrap();
+        let plan = make_upload_plan(&config, &github, &get_options(false), &journal)
+            .await
+            .unwrap();
+        assert_eq!(plan.operations.len(), 1);
+        check_remote_state(&github, &plan, &journal).await.unwrap();
+
+        // A milestone created after the plan was made is noticed
+        github.create_milestone("Other", None).await.unwrap();
+        let error = check_remote_state(&github, &plan, &journal)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("The milestones in"));
+        assert_eq!(
+            plan.operations[0].to_string(),
+            format!(
+                "Set milestone of issue #1: {} ({})",
+                issues[0].title, config.workspace_version
+            )
+        );
+
+        config.milestone = Some(VersionMilestone {
+            title: Some("Release".to_string()),
+            due_on: Some("30/11/2026".to_string()),
+        });
+        let error = make_upload_plan(&config, &github, &get_options(false), &journal)
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", error).contains("Invalid milestone due date"));
+    }
+
+    #[tokio::test]
+    async fn reuses_the_issues_of_the_previous_version() {
+        let TestUpload {
+            mut config,
+            journal_path,
+            fake_github,
+            github,
+        } = start_test_upload().await;
+        let number_of_test_cases = get_test_cases(&config).unwrap().len();
+        let plan = make_upload_plan(&config, &github, &get_options(false), &[])
+            .await
+            .unwrap();
+        apply_upload_plan(&github, &plan, &journal_path, 2)
+            .await
+            .unwrap();
+
+        // Both issues passed, and the second one was edited on Github since
+        let passed_label = config.result_labels.passed[0].clone();
+        for number in [1, 2] {
+            fake_github.label_issue(number, "tester", &passed_label);
+            fake_github.close_issue(number, "tester");
+        }
+        fake_github.edit_issue(2, |issue| {
+            issue.body = issue
+                .body
+                .as_ref()
+                .map(|b| format!("Edited on Github.\n\n{}", b));
+        });
+
+        let previous_version = config.workspace_version.clone();
+        config.workspace_version = "2.0.0".to_string();
+        let mut options = get_options(false);
+        options.carry_over_from = Some(previous_version.clone());
+        let plan = make_upload_plan(&config, &github, &options, &[])
+            .await
+            .unwrap();
+        assert!(plan.changes_existing_issues());
+        apply_upload_plan(&github, &plan, &journal_path, 2)
+            .await
+            .unwrap();
+
+        // The issues are reused for the new version instead of creating new ones
+        let issues = fake_github.issues();
+        assert_eq!(issues.len(), number_of_test_cases);
+        let test_cases = get_test_cases(&config).unwrap();
+        let version_label = project_version_to_label(&config.workspace_version);
+        for issue in &issues {
+            assert!(test_cases.iter().any(|t| {
+                issue.body.as_deref().and_then(get_id_from_body)
+                    == Some(get_test_case_id(t, &config.workspace_version))
+            }));
+            assert!(issue.labels.contains(&version_label));
+            assert!(!issue
+                .labels
+                .contains(&project_version_to_label(&previous_version)));
+        }
+
+        // The unchanged issue keeps its result, the changed one is reopened to be tested again
+        assert_eq!(issues[0].state, TrackerIssueState::Closed);
+        assert!(issues[0].labels.contains(&passed_label));
+        assert!(fake_github.comments(1)[0].contains("its result carries over"));
+        assert_eq!(issues[1].state, TrackerIssueState::Open);
+        assert!(!issues[1].labels.contains(&passed_label));
+        assert!(fake_github.comments(2)[0].contains("Please test it again"));
+
+        // The new issues match the test cases of the new version
+        let plan = make_upload_plan(&config, &github, &options, &[])
+            .await
+            .unwrap();
+        assert!(plan.operations.is_empty());
+    }
+}
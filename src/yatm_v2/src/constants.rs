@@ -1 +1,5 @@
 pub const YAML_EXTENSIONS: [&str; 2] = ["yaml", "yml"];
+pub const OBSOLETE_LABEL: &str = "obsolete";
+pub const UPLOAD_JOURNAL_FILE_NAME: &str = "upload-journal.yaml";
+pub const LOCAL_TRACKER_DIR_NAME: &str = "local-tracker";
+pub const METRICS_HISTORY_FILE_NAME: &str = "metrics-history.yaml";
@@ -0,0 +1,78 @@
+use crate::helpers::issue_markers::{get_checkbox_state, get_step_from_marker};
+use crate::types::StepChecklist;
+
+/// Get the checklists of the steps in an issue body.
+///
+/// A checklist starts at the step marker that's rendered above it and runs until the next
+/// heading, so boxes that someone added elsewhere in the body aren't counted. Only the top level
+/// items are boxes, the indented lines are the content of an item.
+pub fn get_checklists(body: &str) -> Vec<StepChecklist> {
+    let mut checklists: Vec<StepChecklist> = vec![];
+    let mut in_checklist = false;
+    for line in body.lines() {
+        if let Some(step) = get_step_from_marker(line) {
+            checklists.push(StepChecklist {
+                step,
+                checked: 0,
+                total: 0,
+            });
+            in_checklist = true;
+        } else if line.starts_with('#') {
+            in_checklist = false;
+        } else if line.starts_with(char::is_whitespace) {
+            continue;
+        } else if let (true, Some(checked), Some(checklist)) = (
+            in_checklist,
+            get_checkbox_state(line),
+            checklists.last_mut(),
+        ) {
+            checklist.total += 1;
+            if checked {
+                checklist.checked += 1;
+            }
+        }
+    }
+    checklists
+}
+
+#[cfg(test)]
+mod test_get_checklists {
+    use super::*;
+    use crate::helpers::test_case_to_markdown;
+    use common::types::{Requirement, TestCase, TestCasesBuilder};
+    use std::collections::HashMap;
+
+    #[test]
+    fn reads_the_boxes_of_rendered_checklists() {
+        let mut requirement = Requirement::default();
+        let mut prose_step = requirement.steps[0].clone();
+        prose_step.checklist = false;
+        requirement.steps[0].checklist = true;
+        requirement.steps.push(prose_step);
+        let test_case = TestCase {
+            requirement,
+            builder_used: TestCasesBuilder::default(),
+            selected_permutation: HashMap::new(),
+        };
+        let issue = test_case_to_markdown(test_case, &"1.0.0".to_string()).unwrap();
+        assert_eq!(
+            get_checklists(&issue.text_body),
+            vec![StepChecklist {
+                step: 1,
+                checked: 0,
+                total: 5
+            }]
+        );
+
+        let ticked = issue
+            .text_body
+            .replacen("- [ ] expect", "- [x] expect", 1)
+            .replacen("- [ ] StdOut", "- [X] StdOut", 1);
+        let checklists = get_checklists(&ticked);
+        assert_eq!(checklists[0].checked, 2);
+        assert!(!checklists[0].passed());
+        // Boxes outside of a step's checklist aren't counted
+        let with_todo = format!("{}\n\n## Notes\n\n- [x] Ran it twice", ticked);
+        assert_eq!(get_checklists(&with_todo), checklists);
+    }
+}
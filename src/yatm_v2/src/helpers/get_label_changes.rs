@@ -0,0 +1,152 @@
+use crate::helpers::sanitize_label;
+use common::types::TestCase;
+use std::collections::HashSet;
+
+/// The labels to add to and remove from a github issue
+#[derive(Debug, Default, PartialEq)]
+pub struct LabelChanges {
+    pub to_add: Vec<String>,
+    pub to_remove: Vec<String>,
+}
+
+impl LabelChanges {
+    pub fn is_empty(&self) -> bool {
+        self.to_add.is_empty() && self.to_remove.is_empty()
+    }
+}
+
+/// The families of labels that yatm makes for test cases.
+///
+/// These are the `version: ` and `requirement: ` labels, the labels of the test cases builders and
+/// the labels of the permutation keys. Any other label, including the triage labels, was put on
+/// an issue by hand.
+pub struct ManagedLabels {
+    builder_labels: HashSet<String>,
+    permutation_prefixes: HashSet<String>,
+    triage_labels: HashSet<String>,
+}
+
+impl ManagedLabels {
+    pub fn new(test_cases: &[TestCase], triage_labels: &[String]) -> Self {
+        let builder_labels = test_cases
+            .iter()
+            .flat_map(|t| t.builder_used.labels.clone().unwrap_or_default())
+            .map(sanitize_label)
+            .collect();
+        let permutation_prefixes = test_cases
+            .iter()
+            .flat_map(|t| t.builder_used.permutations.keys())
+            .map(|key| sanitize_label(format!("{}: ", key)))
+            .collect();
+        Self {
+            builder_labels,
+            permutation_prefixes,
+            triage_labels: triage_labels.iter().cloned().collect(),
+        }
+    }
+
+    /// Whether yatm makes the label, so it can remove it when no test case needs it anymore.
+    pub fn contains(&self, label: &str) -> bool {
+        if self.triage_labels.contains(label) {
+            return false;
+        }
+        label.starts_with("version: ")
+            || label.starts_with("requirement: ")
+            || self.builder_labels.contains(label)
+            || self
+                .permutation_prefixes
+                .iter()
+                .any(|prefix| label.starts_with(prefix))
+    }
+}
+
+/// Get the label changes that bring a github issue in line with its local issue.
+///
+/// Only the labels that yatm manages are removed, so the labels people put on the issue by hand
+/// are left alone.
+pub fn get_label_changes(
+    local_labels: &[String],
+    github_labels: &[String],
+    managed_labels: &ManagedLabels,
+) -> LabelChanges {
+    let to_add = local_labels
+        .iter()
+        .filter(|label| !github_labels.contains(label))
+        .cloned()
+        .collect();
+    let to_remove = github_labels
+        .iter()
+        .filter(|label| !local_labels.contains(label) && managed_labels.contains(label))
+        .cloned()
+        .collect();
+    LabelChanges { to_add, to_remove }
+}
+
+#[cfg(test)]
+mod test_get_label_changes {
+    use super::*;
+    use crate::test_utils::make_test_case;
+    use std::collections::HashMap;
+
+    fn to_strings(labels: &[&str]) -> Vec<String> {
+        labels.iter().map(|l| l.to_string()).collect()
+    }
+
+    fn get_managed_labels(triage_labels: &[&str]) -> ManagedLabels {
+        let mut test_case = make_test_case("login", &[("OS", "Ubuntu 24.04")]);
+        test_case.builder_used.labels = Some(to_strings(&["nightly"]));
+        test_case.builder_used.permutations =
+            HashMap::from([("OS".to_string(), to_strings(&["Ubuntu 24.04"]))]);
+        ManagedLabels::new(&[test_case], &to_strings(triage_labels))
+    }
+
+    #[test]
+    fn no_changes() {
+        let labels = to_strings(&["OS: Ubuntu", "version: 1"]);
+        assert!(get_label_changes(&labels, &labels, &get_managed_labels(&[])).is_empty());
+    }
+
+    #[test]
+    fn adds_missing_and_removes_stale() {
+        let local = to_strings(&["OS: Ubuntu 24.04", "version: 2"]);
+        let github = to_strings(&[
+            "OS: Ubuntu 22.04",
+            "nightly",
+            "requirement: logout",
+            "version: 1",
+        ]);
+        assert_eq!(
+            get_label_changes(&local, &github, &get_managed_labels(&[])),
+            LabelChanges {
+                to_add: to_strings(&["OS: Ubuntu 24.04", "version: 2"]),
+                to_remove: to_strings(&[
+                    "OS: Ubuntu 22.04",
+                    "nightly",
+                    "requirement: logout",
+                    "version: 1"
+                ]),
+            }
+        );
+    }
+
+    #[test]
+    fn keeps_triage_labels() {
+        let local = to_strings(&["version: 1"]);
+        let github = to_strings(&["confirmed: bug", "version: 1"]);
+        let managed_labels = get_managed_labels(&["confirmed: bug"]);
+        assert!(get_label_changes(&local, &github, &managed_labels).is_empty());
+    }
+
+    #[test]
+    fn keeps_labels_added_by_hand() {
+        let local = to_strings(&["version: 1"]);
+        let github = to_strings(&[
+            "bug",
+            "help wanted",
+            "obsolete",
+            "RMW: FastRTPS",
+            "version: 1",
+        ]);
+        assert!(get_label_changes(&local, &github, &get_managed_labels(&[])).is_empty());
+    }
+}
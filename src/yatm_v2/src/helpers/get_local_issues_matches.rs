@@ -1,13 +1,16 @@
+use crate::helpers::issue_markers::{get_content_hash, get_hash_from_body, get_id_from_body};
 use crate::types::LocalIssue;
-use octocrab::models::issues::Issue as GithubIssue;
+use common::types::TrackerIssue as GithubIssue;
+use std::collections::{HashMap, HashSet};
 
 /// The types of matches between a local issue and a github issue
-/// Canonical Match is matching permutation and shortname
-#[derive(Eq, PartialEq)]
+/// Canonical Match is matching the identity key embedded in the issue body
+#[derive(Debug, Eq, PartialEq)]
 pub enum IssueMatchType {
     Missing,         // No equivalant GitHub Issue found
     Match,           // Matching github issue found
     MatchedWithDiff, // Matching GitHub Issue found but with some differences
+    EditedOnGithub,  // Matching GitHub Issue found but edited on GitHub since the last upload
 }
 
 pub struct GithubIssueMatches {
@@ -17,26 +20,61 @@ pub struct GithubIssueMatches {
 }
 
 /// Get local issues that match upstream
+///
+/// Issues are matched by the identity key in their body first, then by the issue numbers that
+/// the upload journal recorded for each identity key. Issues without a key were uploaded by an
+/// older version of yatm and fall back to matching by labels. Each github issue is matched to one
+/// local issue at most, and never to a local issue whose key differs from its own.
 pub fn get_local_issues_matches(
-    local_issues: &Vec<LocalIssue>,
-    github_issues: &Vec<GithubIssue>,
+    local_issues: &[LocalIssue],
+    github_issues: &[GithubIssue],
+    journal_issue_numbers: &HashMap<String, u64>,
 ) -> Vec<GithubIssueMatches> {
-    let mut results: Vec<GithubIssueMatches> = Vec::<GithubIssueMatches>::new();
-    for local_issue in local_issues {
-        let gh_issue = github_issues
-            .iter()
-            .find(|i| is_local_issue_match_github_issue(local_issue, i));
-
+    // Match by identity key before falling back, so the fallbacks can't take an issue that
+    // belongs to a later test case
+    let mut used_numbers: HashSet<u64> = HashSet::new();
+    let mut gh_issues: Vec<Option<&GithubIssue>> = local_issues
+        .iter()
+        .map(|local_issue| {
+            let gh_issue = github_issues
+                .iter()
+                .find(|i| is_local_issue_id_match_github_issue(local_issue, i))?;
+            used_numbers.insert(gh_issue.number);
+            Some(gh_issue)
+        })
+        .collect();
+    for (local_issue, gh_issue) in local_issues.iter().zip(gh_issues.iter_mut()) {
         if gh_issue.is_some() {
+            continue;
+        }
+        *gh_issue = journal_issue_numbers
+            .get(&local_issue.id)
+            .and_then(|number| {
+                github_issues.iter().find(|i| {
+                    &i.number == number
+                        && !used_numbers.contains(&i.number)
+                        && get_github_issue_id(i).is_none_or(|id| id == local_issue.id)
+                })
+            })
+            .or_else(|| {
+                github_issues.iter().find(|i| {
+                    !used_numbers.contains(&i.number)
+                        && get_github_issue_id(i).is_none()
+                        && is_local_issue_match_github_issue(local_issue, i)
+                })
+            });
+        if let Some(gh_issue) = gh_issue {
+            used_numbers.insert(gh_issue.number);
+        }
+    }
+
+    let mut results: Vec<GithubIssueMatches> = Vec::<GithubIssueMatches>::new();
+    for (local_issue, gh_issue) in local_issues.iter().zip(gh_issues) {
+        if let Some(gh_issue) = gh_issue {
             results.push(GithubIssueMatches {
                 local_issue: local_issue.clone(),
-                github_issue: gh_issue.clone().cloned(),
-                match_type: if is_local_issue_identical_github_issue(local_issue, gh_issue.unwrap())
-                {
-                    IssueMatchType::Match
-                } else {
-                    IssueMatchType::MatchedWithDiff
-                },
+                github_issue: Some(gh_issue.clone()),
+                match_type: get_match_type(local_issue, gh_issue),
             });
         } else {
             results.push(GithubIssueMatches {
@@ -49,40 +87,187 @@ pub fn get_local_issues_matches(
     results
 }
 
-// TODO(tfoote) Change this to a specific tag match
+/// Get the identity key embedded in a github issue body
+fn get_github_issue_id(github_issue: &GithubIssue) -> Option<String> {
+    github_issue.body.as_deref().and_then(get_id_from_body)
+}
+
+fn is_local_issue_id_match_github_issue(
+    local_issue: &LocalIssue,
+    github_issue: &GithubIssue,
+) -> bool {
+    get_github_issue_id(github_issue).as_ref() == Some(&local_issue.id)
+}
+
+/// Legacy match for issues uploaded without an identity key
 fn is_local_issue_match_github_issue(local_issue: &LocalIssue, github_issue: &GithubIssue) -> bool {
     for label in local_issue.labels.iter() {
-        // let github_issue_label_names = github_issue.labels.iter();
-        if !github_issue
-            .labels
-            .iter()
-            .any(|github_label| &github_label.name == label)
-        {
+        if !github_issue.labels.contains(label) {
             return false;
         }
     }
-    return true;
+    true
 }
 
-fn is_local_issue_identical_github_issue(
-    local_issue: &LocalIssue,
-    github_issue: &GithubIssue,
-) -> bool {
-    let title_match: bool = if local_issue.title == github_issue.title {
-        true
-    } else {
-        false
-    };
-
-    let body_match: bool =
-        if local_issue.text_body == github_issue.body.clone().unwrap_or("".to_string()) {
-            true
-        } else {
-            false
-        };
+/// Compare the content hashes of a local issue and the github issue it matched
+///
+/// The hash recorded in the github issue body is what was last uploaded, so a github issue whose
+/// current content doesn't hash to it has been edited since. Issues uploaded without a recorded
+/// hash are always updated so they get one.
+fn get_match_type(local_issue: &LocalIssue, github_issue: &GithubIssue) -> IssueMatchType {
+    let github_body = github_issue.body.clone().unwrap_or_default();
+    let github_hash = get_content_hash(&github_issue.title, &github_body);
+
+    match get_hash_from_body(&github_body) {
+        Some(uploaded_hash) if uploaded_hash != github_hash => IssueMatchType::EditedOnGithub,
+        Some(_) if github_hash == local_issue.content_hash => IssueMatchType::Match,
+        _ => IssueMatchType::MatchedWithDiff,
+    }
+}
+
+#[cfg(test)]
+mod test_get_match_type {
+    use super::*;
+    use crate::helpers::test_case_to_markdown;
+    use crate::test_utils::{make_test_case, make_tracker_issue};
 
-    //TODO (tfoote) check for missing labels
+    fn make_local_issue(description: &str) -> LocalIssue {
+        let mut test_case = make_test_case("login", &[("OS", "Ubuntu")]);
+        test_case.requirement.description = description.to_string();
+        test_case.requirement.steps[0].checklist = true;
+        test_case_to_markdown(test_case, &"1.0.0".to_string()).unwrap()
+    }
+
+    fn make_github_issue(local_issue: &LocalIssue, body: &str) -> GithubIssue {
+        make_tracker_issue(
+            1,
+            &local_issue.title,
+            Some(body),
+            local_issue.labels.clone(),
+        )
+    }
+
+    #[test]
+    fn unchanged_issues_match() {
+        let local_issue = make_local_issue("description");
+        let github_issue = make_github_issue(&local_issue, &local_issue.text_body);
+        assert_eq!(
+            get_match_type(&local_issue, &github_issue),
+            IssueMatchType::Match
+        );
+    }
+
+    #[test]
+    fn ticking_boxes_is_not_an_edit() {
+        let local_issue = make_local_issue("description");
+        assert!(local_issue.text_body.contains("- [ ] "));
+        let body = local_issue.text_body.replace("- [ ] ", "- [x] ");
+        let github_issue = make_github_issue(&local_issue, &body);
+        assert_eq!(
+            get_match_type(&local_issue, &github_issue),
+            IssueMatchType::Match
+        );
+    }
+
+    #[test]
+    fn edits_on_github_are_detected() {
+        let local_issue = make_local_issue("description");
+        let body = local_issue
+            .text_body
+            .replace("description", "edited description");
+        let github_issue = make_github_issue(&local_issue, &body);
+        assert_eq!(
+            get_match_type(&local_issue, &github_issue),
+            IssueMatchType::EditedOnGithub
+        );
+    }
+
+    #[test]
+    fn local_changes_and_issues_without_a_hash_need_updating() {
+        let uploaded = make_local_issue("description");
+        let github_issue = make_github_issue(&uploaded, &uploaded.text_body);
+        let local_issue = make_local_issue("new description");
+        assert_eq!(
+            get_match_type(&local_issue, &github_issue),
+            IssueMatchType::MatchedWithDiff
+        );
+
+        let body = uploaded
+            .text_body
+            .lines()
+            .filter(|line| !line.starts_with("<!-- yatm-hash:"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let github_issue = make_github_issue(&uploaded, &body);
+        assert_eq!(
+            get_match_type(&uploaded, &github_issue),
+            IssueMatchType::MatchedWithDiff
+        );
+    }
+}
 
-    // Full match
-    title_match && body_match
+#[cfg(test)]
+mod test_get_local_issues_matches {
+    use super::*;
+    use crate::helpers::test_case_to_markdown;
+    use crate::test_utils::{make_test_case, make_tracker_issue};
+
+    fn make_local_issue(name: &str) -> LocalIssue {
+        test_case_to_markdown(
+            make_test_case(name, &[("OS", "Ubuntu")]),
+            &"1.0.0".to_string(),
+        )
+        .unwrap()
+    }
+
+    fn get_matched_numbers(matches: &[GithubIssueMatches]) -> Vec<Option<u64>> {
+        matches
+            .iter()
+            .map(|m| m.github_issue.as_ref().map(|i| i.number))
+            .collect()
+    }
+
+    #[test]
+    fn journal_numbers_skip_issues_of_other_test_cases() {
+        let login = make_local_issue("login");
+        let logout = make_local_issue("logout");
+        let github_issues = vec![make_tracker_issue(
+            1,
+            &login.title,
+            Some(&login.text_body),
+            login.labels.clone(),
+        )];
+        let journal_issue_numbers = HashMap::from([(logout.id.clone(), 1)]);
+
+        let matches = get_local_issues_matches(&[logout], &github_issues, &journal_issue_numbers);
+        assert_eq!(get_matched_numbers(&matches), vec![None]);
+    }
+
+    #[test]
+    fn issues_are_matched_once() {
+        let login = make_local_issue("login");
+        let logout = make_local_issue("logout");
+        let mut labels = login.labels.clone();
+        labels.extend(logout.labels.clone());
+        let github_issues = vec![
+            make_tracker_issue(1, "Legacy issue", Some("No key"), labels),
+            make_tracker_issue(2, &login.title, Some(&login.text_body), vec![]),
+        ];
+        let journal_issue_numbers = HashMap::from([(logout.id.clone(), 2)]);
+
+        // the keyed issue goes to its test case, and the legacy issue to the first one to match
+        let matches = get_local_issues_matches(
+            &[logout.clone(), login.clone()],
+            &github_issues,
+            &journal_issue_numbers,
+        );
+        assert_eq!(get_matched_numbers(&matches), vec![Some(1), Some(2)]);
+
+        let matches = get_local_issues_matches(
+            &[make_local_issue("login"), logout],
+            &github_issues[..1],
+            &HashMap::new(),
+        );
+        assert_eq!(get_matched_numbers(&matches), vec![Some(1), None]);
+    }
 }
@@ -0,0 +1,72 @@
+use crate::helpers::{project_version_to_label, GithubIssueMatches};
+use common::types::{TrackerIssue, TrackerIssueState};
+use std::collections::HashSet;
+
+/// Get the open github issues for the workspace version that no local issue matched.
+///
+/// These are issues for test cases that are no longer generated, for example because their
+/// requirement was deleted or a permutation value was removed.
+pub fn get_orphaned_issues<'a>(
+    matched_issues: &[GithubIssueMatches],
+    github_issues: &'a [TrackerIssue],
+    workspace_version: &String,
+) -> Vec<&'a TrackerIssue> {
+    let version_label = project_version_to_label(workspace_version);
+    let matched_numbers: HashSet<u64> = matched_issues
+        .iter()
+        .filter_map(|m| m.github_issue.as_ref().map(|i| i.number))
+        .collect();
+    github_issues
+        .iter()
+        .filter(|i| {
+            i.state == TrackerIssueState::Open
+                && !i.is_pull_request
+                && i.labels.contains(&version_label)
+                && !matched_numbers.contains(&i.number)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test_get_orphaned_issues {
+    use super::*;
+    use crate::helpers::{test_case_to_markdown, IssueMatchType};
+    use crate::test_utils::{make_test_case, make_tracker_issue};
+
+    #[test]
+    fn finds_the_open_unmatched_issues_of_the_version() {
+        let version = "1.0.0".to_string();
+        let version_label = project_version_to_label(&version);
+        let make_issue = |number: u64, labels: &[&String]| {
+            make_tracker_issue(
+                number,
+                &format!("Issue {}", number),
+                None,
+                labels.iter().map(|l| l.to_string()).collect(),
+            )
+        };
+        let mut issues = vec![
+            make_issue(1, &[&version_label]),
+            make_issue(2, &[&version_label]),
+            make_issue(3, &[&version_label]),
+            make_issue(4, &[&version_label]),
+            make_issue(5, &[&project_version_to_label(&"0.1.0".to_string())]),
+            make_issue(6, &[]),
+        ];
+        issues[1].state = TrackerIssueState::Closed;
+        issues[2].is_pull_request = true;
+        let local_issue =
+            test_case_to_markdown(make_test_case("login", &[("OS", "Ubuntu")]), &version).unwrap();
+        let matched_issues = vec![GithubIssueMatches {
+            local_issue,
+            github_issue: Some(issues[3].clone()),
+            match_type: IssueMatchType::Match,
+        }];
+
+        let numbers: Vec<u64> = get_orphaned_issues(&matched_issues, &issues, &version)
+            .iter()
+            .map(|i| i.number)
+            .collect();
+        assert_eq!(numbers, vec![1]);
+    }
+}
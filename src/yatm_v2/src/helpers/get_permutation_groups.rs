@@ -0,0 +1,13 @@
+use crate::helpers::permutation_to_labels;
+use common::types::TestCase;
+use std::collections::BTreeSet;
+
+/// Get the labels of every permutation that the test cases are made for, once each and sorted.
+pub fn get_permutation_groups(test_cases: &[TestCase]) -> Vec<Vec<String>> {
+    test_cases
+        .iter()
+        .map(|test_case| permutation_to_labels(&test_case.selected_permutation))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
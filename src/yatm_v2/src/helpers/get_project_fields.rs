@@ -0,0 +1,61 @@
+use crate::types::GithubProject;
+use common::types::TestCase;
+use std::collections::BTreeMap;
+
+/// Get the values to give the project fields of a test case's issue, by field name.
+///
+/// Permutations without a field in the project configuration are left out, as are the ones the
+/// test case doesn't have.
+pub fn get_project_fields(
+    project: &GithubProject,
+    test_case: &TestCase,
+) -> BTreeMap<String, String> {
+    let mut fields: BTreeMap<String, String> = project
+        .permutation_fields
+        .iter()
+        .filter_map(|(permutation, field)| {
+            let value = test_case.selected_permutation.get(permutation)?;
+            Some((field.clone(), value.clone()))
+        })
+        .collect();
+    if let Some(field) = &project.builder_field {
+        fields.insert(field.clone(), test_case.builder_used.name.clone());
+    }
+    fields
+}
+
+#[cfg(test)]
+mod test_get_project_fields {
+    use super::*;
+    use common::types::{Requirement, TestCasesBuilder};
+    use std::collections::HashMap;
+
+    #[test]
+    fn maps_permutations_and_builder_to_fields() {
+        let test_case = TestCase {
+            requirement: Requirement::default(),
+            builder_used: TestCasesBuilder::default(),
+            selected_permutation: HashMap::from([
+                ("Operating System".to_string(), "Ubuntu 22.04".to_string()),
+                ("RMW".to_string(), "FastRTPS".to_string()),
+            ]),
+        };
+        let project = GithubProject {
+            owner: "owner".to_string(),
+            number: 1,
+            builder_field: Some("Builder".to_string()),
+            permutation_fields: BTreeMap::from([
+                ("Operating System".to_string(), "OS".to_string()),
+                ("Robot".to_string(), "Robot".to_string()),
+            ]),
+        };
+
+        assert_eq!(
+            get_project_fields(&project, &test_case),
+            BTreeMap::from([
+                ("Builder".to_string(), test_case.builder_used.name.clone()),
+                ("OS".to_string(), "Ubuntu 22.04".to_string()),
+            ])
+        );
+    }
+}
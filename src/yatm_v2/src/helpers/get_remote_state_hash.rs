@@ -0,0 +1,142 @@
+use crate::helpers::hash_to_hex;
+use common::types::{TrackerComment, TrackerIssue};
+use std::collections::BTreeMap;
+
+/// Get a hash of the issues and labels on the issue tracker, and of the comments on the issues
+/// that the plan read them from.
+///
+/// It covers everything an upload plan is based on, so a plan can check that nothing changed on
+/// the issue tracker between when it was made and when it is applied.
+pub fn get_remote_state_hash(
+    github_issues: &[TrackerIssue],
+    github_labels: &[String],
+    comments: &BTreeMap<u64, Vec<TrackerComment>>,
+) -> String {
+    let mut issues: Vec<&TrackerIssue> = github_issues.iter().collect();
+    issues.sort_by_key(|i| i.number);
+    let mut labels = github_labels.to_vec();
+    labels.sort();
+
+    let mut state = String::new();
+    for label in labels {
+        state.push_str(&format!("label: {}\n", label));
+    }
+    for issue in issues {
+        state.push_str(&format!(
+            "issue: {}\n",
+            get_issue_state(issue, comments.get(&issue.number))
+        ));
+    }
+    hash_to_hex(&state)
+}
+
+/// Get a hash of a single issue on the issue tracker and the comments read from it, to check it
+/// on its own.
+pub fn get_issue_state_hash(
+    issue: &TrackerIssue,
+    comments: Option<&Vec<TrackerComment>>,
+) -> String {
+    hash_to_hex(&get_issue_state(issue, comments))
+}
+
+fn get_issue_state(issue: &TrackerIssue, comments: Option<&Vec<TrackerComment>>) -> String {
+    let mut issue_labels: Vec<&String> = issue.labels.iter().collect();
+    issue_labels.sort();
+    let mut state = format!(
+        "{} {:?} {:?} {:?} {:?}",
+        issue.number, issue.state, issue.title, issue.body, issue_labels
+    );
+    for comment in comments.into_iter().flatten() {
+        state.push_str(&format!(
+            " comment: {:?} {:?} {:?}",
+            comment.author, comment.created_at, comment.body
+        ));
+    }
+    state
+}
+
+#[cfg(test)]
+mod test_get_remote_state_hash {
+    use super::*;
+    use crate::test_utils::make_tracker_issue;
+    use common::types::TrackerIssueState;
+
+    fn make_issues() -> Vec<TrackerIssue> {
+        vec![
+            make_tracker_issue(
+                1,
+                "First",
+                Some("body"),
+                vec!["a".to_string(), "b".to_string()],
+            ),
+            make_tracker_issue(2, "Second", None, vec![]),
+        ]
+    }
+
+    #[test]
+    fn ignores_the_order_of_issues_and_labels() {
+        let labels = vec!["a".to_string(), "b".to_string()];
+        let hash = get_remote_state_hash(&make_issues(), &labels, &BTreeMap::new());
+        assert_eq!(
+            get_remote_state_hash(&make_issues(), &labels, &BTreeMap::new()),
+            hash
+        );
+
+        let mut issues = make_issues();
+        issues.reverse();
+        issues[1].labels.reverse();
+        let reversed_labels = vec!["b".to_string(), "a".to_string()];
+        assert_eq!(
+            get_remote_state_hash(&issues, &reversed_labels, &BTreeMap::new()),
+            hash
+        );
+    }
+
+    #[test]
+    fn changes_with_the_issues_and_labels() {
+        let labels = vec!["a".to_string()];
+        let hash = get_remote_state_hash(&make_issues(), &labels, &BTreeMap::new());
+        assert_ne!(
+            get_remote_state_hash(&make_issues(), &["b".to_string()], &BTreeMap::new()),
+            hash
+        );
+        let changes: [fn(&mut TrackerIssue); 4] = [
+            |i| i.title.push('!'),
+            |i| i.body = Some("edited".to_string()),
+            |i| i.labels.clear(),
+            |i| i.state = TrackerIssueState::Closed,
+        ];
+        for change in changes {
+            let mut issues = make_issues();
+            change(&mut issues[0]);
+            assert_ne!(
+                get_remote_state_hash(&issues, &labels, &BTreeMap::new()),
+                hash
+            );
+        }
+    }
+
+    #[test]
+    fn changes_with_the_comments_read() {
+        let labels = vec!["a".to_string()];
+        let hash = get_remote_state_hash(&make_issues(), &labels, &BTreeMap::new());
+        let comment = TrackerComment {
+            author: Some("tester".to_string()),
+            body: "comment".to_string(),
+            created_at: None,
+        };
+        let comments = BTreeMap::from([(1, vec![comment.clone()])]);
+        assert_eq!(
+            get_remote_state_hash(&make_issues(), &labels, &BTreeMap::from([(1, vec![])])),
+            hash
+        );
+        assert_ne!(
+            get_remote_state_hash(&make_issues(), &labels, &comments),
+            hash
+        );
+        assert_ne!(
+            get_issue_state_hash(&make_issues()[0], comments.get(&1)),
+            get_issue_state_hash(&make_issues()[0], None)
+        );
+    }
+}
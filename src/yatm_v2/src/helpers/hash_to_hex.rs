@@ -0,0 +1,9 @@
+use sha2::{Digest, Sha256};
+
+/// Get the SHA-256 hash of the text as a hex string.
+pub fn hash_to_hex(text: &str) -> String {
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
@@ -0,0 +1,222 @@
+use crate::helpers::hash_to_hex;
+use common::types::TestCase;
+use std::collections::BTreeMap;
+
+const ID_MARKER_PREFIX: &str = "<!-- yatm-id: ";
+const HASH_MARKER_PREFIX: &str = "<!-- yatm-hash: ";
+const META_MARKER_PREFIX: &str = "<!-- yatm-meta: ";
+const STEP_MARKER_PREFIX: &str = "<!-- yatm-step: ";
+const ORPHAN_MARKER_PREFIX: &str = "<!-- yatm-orphaned: ";
+const MARKER_SUFFIX: &str = " -->";
+
+/// Get the identity key of a test case.
+///
+/// The key is a hash of the builder name, the requirement shortname, the sorted permutation and
+/// the workspace version, so it stays the same for a test case no matter how its content changes.
+pub fn get_test_case_id(test_case: &TestCase, workspace_version: &String) -> String {
+    let mut key = String::new();
+    key.push_str(&format!("builder: {}\n", test_case.builder_used.name));
+    key.push_str(&format!(
+        "requirement: {}\n",
+        test_case
+            .requirement
+            .shortname
+            .clone()
+            .unwrap_or(test_case.requirement.name.clone())
+    ));
+    let permutation: BTreeMap<&String, &String> = test_case.selected_permutation.iter().collect();
+    for (name, value) in permutation {
+        key.push_str(&format!("permutation: {}={}\n", name, value));
+    }
+    key.push_str(&format!("version: {}\n", workspace_version));
+    hash_to_hex(&key)
+}
+
+/// Make the hidden marker that is embedded in an issue body to identify the test case.
+pub fn make_id_marker(id: &str) -> String {
+    format!("{}{}{}", ID_MARKER_PREFIX, id, MARKER_SUFFIX)
+}
+
+/// Get the test case identity key from an issue body, if it has one.
+pub fn get_id_from_body(body: &str) -> Option<String> {
+    get_marker_value(body, ID_MARKER_PREFIX)
+}
+
+/// Make the hidden marker that records the content hash of an issue when it was uploaded.
+pub fn make_hash_marker(hash: &str) -> String {
+    format!("{}{}{}", HASH_MARKER_PREFIX, hash, MARKER_SUFFIX)
+}
+
+/// Get the content hash recorded in an issue body, if it has one.
+pub fn get_hash_from_body(body: &str) -> Option<String> {
+    get_marker_value(body, HASH_MARKER_PREFIX)
+}
+
+/// Make the hidden marker that identifies the tracking issue of a workspace version.
+pub fn make_meta_marker(workspace_version: &str) -> String {
+    format!(
+        "{}{}{}",
+        META_MARKER_PREFIX, workspace_version, MARKER_SUFFIX
+    )
+}
+
+/// Get the workspace version that an issue body tracks, if it's a tracking issue.
+pub fn get_meta_version_from_body(body: &str) -> Option<String> {
+    get_marker_value(body, META_MARKER_PREFIX)
+}
+
+/// Make the hidden marker that records in a comment that an issue is orphaned in a workspace
+/// version.
+pub fn make_orphan_marker(workspace_version: &str) -> String {
+    format!(
+        "{}{}{}",
+        ORPHAN_MARKER_PREFIX, workspace_version, MARKER_SUFFIX
+    )
+}
+
+/// Get the workspace version that a comment reports an issue as orphaned in, if it does.
+pub fn get_orphan_version_from_body(body: &str) -> Option<String> {
+    get_marker_value(body, ORPHAN_MARKER_PREFIX)
+}
+
+/// Get the step number from the hidden marker that starts the checklist of a step.
+pub fn get_step_from_marker(line: &str) -> Option<usize> {
+    get_marker_value(line, STEP_MARKER_PREFIX)?.parse().ok()
+}
+
+/// Get whether a line is a task list item, and whether it's ticked.
+pub fn get_checkbox_state(line: &str) -> Option<bool> {
+    let line = line.trim_start();
+    if line.starts_with("- [ ] ") || line == "- [ ]" {
+        Some(false)
+    } else if ["- [x]", "- [X]"].iter().any(|b| line.starts_with(b)) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Get the hash of an issue's title and body.
+///
+/// The content is normalised before hashing so that GitHub's line ending and trailing whitespace
+/// changes don't count as edits, and neither does ticking the boxes of a checklist. The yatm
+/// markers are left out of the hash.
+pub fn get_content_hash(title: &str, body: &str) -> String {
+    let body = body
+        .replace("\r\n", "\n")
+        .lines()
+        .filter(|line| !is_marker_line(line))
+        .map(|line| match get_checkbox_state(line) {
+            Some(true) => line
+                .trim_end()
+                .replacen("- [x]", "- [ ]", 1)
+                .replacen("- [X]", "- [ ]", 1),
+            _ => line.trim_end().to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    hash_to_hex(&format!("{}\n\n{}", title.trim(), body.trim()))
+}
+
+fn is_marker_line(line: &str) -> bool {
+    let line = line.trim();
+    [ID_MARKER_PREFIX, HASH_MARKER_PREFIX]
+        .iter()
+        .any(|prefix| line.starts_with(prefix) && line.ends_with(MARKER_SUFFIX))
+}
+
+fn get_marker_value(body: &str, prefix: &str) -> Option<String> {
+    body.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix(prefix)
+            .and_then(|rest| rest.strip_suffix(MARKER_SUFFIX))
+            .map(|value| value.trim().to_string())
+    })
+}
+
+#[cfg(test)]
+mod test_issue_markers {
+    use super::*;
+    use crate::test_utils::make_test_case;
+
+    #[test]
+    fn id_is_deterministic() {
+        let version = "1.0.0".to_string();
+        let a = make_test_case("Requirement", &[("OS", "Ubuntu"), ("RMW", "CycloneDDS")]);
+        let b = make_test_case("Requirement", &[("RMW", "CycloneDDS"), ("OS", "Ubuntu")]);
+        assert_eq!(
+            get_test_case_id(&a, &version),
+            get_test_case_id(&b, &version)
+        );
+    }
+
+    #[test]
+    fn id_changes_with_permutation_and_version() {
+        let a = make_test_case("Requirement", &[("OS", "Ubuntu")]);
+        let b = make_test_case("Requirement", &[("OS", "Windows")]);
+        let version = "1.0.0".to_string();
+        assert_ne!(
+            get_test_case_id(&a, &version),
+            get_test_case_id(&b, &version)
+        );
+        assert_ne!(
+            get_test_case_id(&a, &version),
+            get_test_case_id(&a, &"2.0.0".to_string())
+        );
+    }
+
+    #[test]
+    fn meta_version_round_trips_through_body() {
+        let body = format!("| table |\n\n{}\n", make_meta_marker("1.0.0"));
+        assert_eq!(get_meta_version_from_body(&body), Some("1.0.0".to_string()));
+        assert_eq!(get_id_from_body(&body), None);
+    }
+
+    #[test]
+    fn id_round_trips_through_body() {
+        let body = format!("Some text\n\n{}\n", make_id_marker("abc123"));
+        assert_eq!(get_id_from_body(&body), Some("abc123".to_string()));
+        assert_eq!(get_id_from_body("Some text"), None);
+    }
+
+    #[test]
+    fn content_hash_ignores_whitespace_and_markers() {
+        let body = "Line 1\n\nLine 2\n";
+        let github_body = format!(
+            "Line 1  \r\n\r\nLine 2\r\n\r\n{}\r\n{}",
+            make_id_marker("abc123"),
+            make_hash_marker("def456")
+        );
+        assert_eq!(
+            get_content_hash("Title", body),
+            get_content_hash("Title ", &github_body)
+        );
+        assert_eq!(get_hash_from_body(&github_body), Some("def456".to_string()));
+    }
+
+    #[test]
+    fn content_hash_ignores_ticked_boxes() {
+        let body = "<!-- yatm-step: 1 -->\n- [ ] Works\n- [ ] StdOut - terminal 1";
+        let ticked = "<!-- yatm-step: 1 -->\n- [x] Works\n- [X] StdOut - terminal 1";
+        assert_eq!(
+            get_content_hash("Title", body),
+            get_content_hash("Title", ticked)
+        );
+        assert_eq!(get_step_from_marker("<!-- yatm-step: 1 -->"), Some(1));
+        assert_eq!(get_checkbox_state("  - [x] Works"), Some(true));
+        assert_eq!(get_checkbox_state("- [ ] Works"), Some(false));
+        assert_eq!(get_checkbox_state("- Works"), None);
+    }
+
+    #[test]
+    fn content_hash_changes_with_content() {
+        assert_ne!(
+            get_content_hash("Title", "Line 1"),
+            get_content_hash("Title", "Line 2")
+        );
+        assert_ne!(
+            get_content_hash("Title 1", "Line 1"),
+            get_content_hash("Title 2", "Line 1")
+        );
+    }
+}
@@ -4,8 +4,8 @@ use common::types::{Filter, Requirement, SetSteps, TestCase, TestCasesBuilder};
 use itertools::Itertools;
 
 pub fn make_test_cases(
-    test_cases_builder: &Vec<TestCasesBuilder>,
-    requirements: &Vec<Requirement>,
+    test_cases_builder: &[TestCasesBuilder],
+    requirements: &[Requirement],
 ) -> Vec<TestCase> {
     let mut test_cases = Vec::new();
     for test_cases_builder in test_cases_builder.iter() {
@@ -20,7 +20,7 @@ pub fn make_test_cases(
 /// Returns a vector of test cases based on the test case builder and requirements
 fn make_test_cases_helper(
     test_cases_builder: &TestCasesBuilder,
-    requirements: &Vec<Requirement>,
+    requirements: &[Requirement],
 ) -> Vec<TestCase> {
     let permutations = get_cartesian_product(test_cases_builder.permutations.clone());
 
@@ -63,6 +63,7 @@ fn make_test_cases_helper(
 }
 
 #[cfg(test)]
+#[allow(clippy::ptr_arg)]
 mod test_make_test_cases {
     use super::make_test_cases_helper;
     use common::types::{Filter, Requirement, SetSteps, TestCase, TestCasesBuilder};
@@ -429,6 +430,7 @@ fn filter_matches_requirement(
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod test_filter_matches_requirements {
 
     use super::filter_matches_requirement;
@@ -0,0 +1,76 @@
+use crate::types::MetricsSnapshot;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Read the snapshots of the metrics history, if there is one.
+pub fn read_metrics_history(history_path: &PathBuf) -> Result<Vec<MetricsSnapshot>> {
+    if !history_path.is_file() {
+        return Ok(vec![]);
+    }
+    let history = std::fs::read_to_string(history_path).context(format!(
+        "Failed to read the metrics history: {:?}",
+        history_path
+    ))?;
+    serde_yaml::Deserializer::from_str(&history)
+        .map(|document| {
+            MetricsSnapshot::deserialize(document).context(format!(
+                "Failed to deserialize the metrics history: {:?}",
+                history_path
+            ))
+        })
+        .collect()
+}
+
+/// Append a snapshot to the metrics history.
+///
+/// Like the upload journal, each snapshot is its own YAML document.
+pub fn append_metrics_snapshot(history_path: &PathBuf, snapshot: &MetricsSnapshot) -> Result<()> {
+    let snapshot = serde_yaml::to_string(snapshot)
+        .context("Failed to turn the metrics snapshot into a string")?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(history_path)
+        .context(format!(
+            "Failed to open the metrics history: {:?}",
+            history_path
+        ))?;
+    file.write_all(format!("---\n{}", snapshot).as_bytes())
+        .context(format!(
+            "Failed to write to the metrics history: {:?}",
+            history_path
+        ))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_metrics_history {
+    use super::*;
+    use crate::types::MetricsRow;
+    use tempfile::tempdir;
+
+    #[test]
+    fn appended_snapshots_are_read_back() {
+        let dir = tempdir().unwrap();
+        let history_path = dir.path().join("metrics-history.yaml");
+        assert!(read_metrics_history(&history_path).unwrap().is_empty());
+
+        let snapshot = MetricsSnapshot {
+            timestamp: "2024-01-01T00:00:00+00:00".to_string(),
+            workspace_version: "1.0.0".to_string(),
+            label: None,
+            rows: vec![MetricsRow::new("all", "all", 3, 1)],
+        };
+        append_metrics_snapshot(&history_path, &snapshot).unwrap();
+        let labeled_snapshot = MetricsSnapshot {
+            label: Some("who: community tested".to_string()),
+            ..snapshot.clone()
+        };
+        append_metrics_snapshot(&history_path, &labeled_snapshot).unwrap();
+
+        let snapshots = read_metrics_history(&history_path).unwrap();
+        assert_eq!(snapshots, vec![snapshot, labeled_snapshot]);
+    }
+}
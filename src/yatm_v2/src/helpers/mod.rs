@@ -1,21 +1,51 @@
+mod get_checklists;
 mod get_files;
+mod get_label_changes;
 mod get_local_issues_matches;
+mod get_orphaned_issues;
+mod get_permutation_groups;
+mod get_project_fields;
+mod get_remote_state_hash;
 mod get_test_cases;
+mod hash_to_hex;
+mod issue_markers;
 mod make_test_cases;
+mod metrics_history;
+mod parse_issue_filter;
 mod requirements;
+mod results_file;
+mod roster;
 mod test_case_to_markdown;
 mod test_cases_builder;
+mod upload_journal;
 
+pub use get_checklists::get_checklists;
 pub use get_files::get_files;
+pub use get_label_changes::{get_label_changes, ManagedLabels};
 pub use get_local_issues_matches::get_local_issues_matches;
 pub use get_local_issues_matches::GithubIssueMatches;
 pub use get_local_issues_matches::IssueMatchType;
+pub use get_orphaned_issues::get_orphaned_issues;
+pub use get_permutation_groups::get_permutation_groups;
+pub use get_project_fields::get_project_fields;
+pub use get_remote_state_hash::{get_issue_state_hash, get_remote_state_hash};
 pub use get_test_cases::get_test_cases;
+pub use hash_to_hex::hash_to_hex;
+pub use issue_markers::{
+    get_content_hash, get_id_from_body, get_meta_version_from_body, get_orphan_version_from_body,
+    get_test_case_id, make_meta_marker, make_orphan_marker,
+};
 pub use make_test_cases::make_test_cases;
+pub use metrics_history::{append_metrics_snapshot, read_metrics_history};
+pub use parse_issue_filter::parse_issue_filter;
 pub use requirements::{
     get_requirements_from_file, validate_requirements_file, validate_requirements_files,
 };
+pub use results_file::{get_results_path, write_results_file};
+pub use roster::read_roster;
 pub use test_case_to_markdown::{
-    permutation_to_labels, project_version_to_label, test_case_to_markdown,
+    get_github_labels, permutation_to_labels, project_version_to_label, sanitize_label,
+    test_case_to_markdown,
 };
 pub use test_cases_builder::{get_test_cases_builders_from_file, validate_test_cases_builder_file};
+pub use upload_journal::{append_upload_journal_entry, read_upload_journal};
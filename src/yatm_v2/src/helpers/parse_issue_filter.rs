@@ -0,0 +1,151 @@
+use crate::types::{FilterKey, FilterTerm, IssueFilter};
+use anyhow::Result;
+
+/// Parse an issue filter such as `requirement=login,Operating System=Ubuntu 22.04|Windows 11`.
+///
+/// The terms are separated by commas and each one compares a key with one or more values
+/// separated by pipes, with `=` or `!=`. The keys are `requirement`, `builder`, `version` and
+/// `state`, and any other key is a permutation key. Keys and values can be quoted to include
+/// commas, pipes or equal signs.
+pub fn parse_issue_filter(expression: &str) -> Result<IssueFilter> {
+    let mut terms = vec![];
+    for term in split_unquoted(expression, ',')? {
+        if term.trim().is_empty() {
+            anyhow::bail!("Empty term in the issue filter: {}", expression);
+        }
+        let (key, values, is_negated) = match split_unquoted(&term, '=')?.as_slice() {
+            [key, values] => match key.trim_end().strip_suffix('!') {
+                Some(key) => (key.to_string(), values.clone(), true),
+                None => (key.clone(), values.clone(), false),
+            },
+            _ => anyhow::bail!(
+                "Expected a key=value or key!=value term in the issue filter: {}",
+                term.trim()
+            ),
+        };
+        let key = unquote(&key);
+        if key.is_empty() {
+            anyhow::bail!(
+                "Missing the key of a term in the issue filter: {}",
+                term.trim()
+            );
+        }
+        let values = split_unquoted(&values, '|')?
+            .iter()
+            .map(|v| unquote(v))
+            .collect::<Vec<_>>();
+        if values.iter().any(|v| v.is_empty()) {
+            anyhow::bail!(
+                "Missing a value of a term in the issue filter: {}",
+                term.trim()
+            );
+        }
+        let key = match key.to_lowercase().as_str() {
+            "requirement" => FilterKey::Requirement,
+            "builder" => FilterKey::Builder,
+            "version" => FilterKey::Version,
+            "state" => {
+                if let Some(value) = values
+                    .iter()
+                    .find(|v| !["open", "closed"].contains(&v.as_str()))
+                {
+                    anyhow::bail!("Expected the state to be open or closed: {}", value);
+                }
+                FilterKey::State
+            }
+            _ => FilterKey::Permutation(key),
+        };
+        terms.push(FilterTerm {
+            key,
+            values,
+            is_negated,
+        });
+    }
+    Ok(IssueFilter { terms })
+}
+
+/// Split on a separator that isn't between double quotes, keeping the quotes.
+fn split_unquoted(text: &str, separator: char) -> Result<Vec<String>> {
+    let mut parts = vec![String::new()];
+    let mut is_quoted = false;
+    for c in text.chars() {
+        match c {
+            '"' => {
+                is_quoted = !is_quoted;
+                parts.last_mut().unwrap().push(c);
+            }
+            c if c == separator && !is_quoted => parts.push(String::new()),
+            c => parts.last_mut().unwrap().push(c),
+        }
+    }
+    if is_quoted {
+        anyhow::bail!("Unclosed quote in the issue filter: {}", text);
+    }
+    Ok(parts)
+}
+
+fn unquote(text: &str) -> String {
+    let text = text.trim();
+    text.strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .unwrap_or(text)
+        .to_string()
+}
+
+#[cfg(test)]
+mod test_parse_issue_filter {
+    use super::*;
+
+    #[test]
+    fn parses_the_terms() {
+        let filter = parse_issue_filter(
+            "requirement=login, Operating System = Ubuntu 22.04|Windows 11,state!=closed,\"a,b\"=\"c|d\"",
+        )
+        .unwrap();
+        assert_eq!(
+            filter.terms,
+            vec![
+                FilterTerm {
+                    key: FilterKey::Requirement,
+                    values: vec!["login".to_string()],
+                    is_negated: false,
+                },
+                FilterTerm {
+                    key: FilterKey::Permutation("Operating System".to_string()),
+                    values: vec!["Ubuntu 22.04".to_string(), "Windows 11".to_string()],
+                    is_negated: false,
+                },
+                FilterTerm {
+                    key: FilterKey::State,
+                    values: vec!["closed".to_string()],
+                    is_negated: true,
+                },
+                FilterTerm {
+                    key: FilterKey::Permutation("a,b".to_string()),
+                    values: vec!["c|d".to_string()],
+                    is_negated: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_filters() {
+        for expression in [
+            "",
+            "requirement",
+            "requirement=",
+            "=login",
+            "requirement=login,",
+            "a=b=c",
+            "state=pending",
+            "requirement=\"login",
+        ] {
+            assert!(
+                parse_issue_filter(expression).is_err(),
+                "{} should be rejected",
+                expression
+            );
+        }
+    }
+}
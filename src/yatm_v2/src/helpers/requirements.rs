@@ -3,7 +3,6 @@ use crate::helpers::get_files;
 use anyhow::{Context, Result};
 use common::types::Requirement;
 use common::types::RequirementsFile;
-use serde_yaml;
 use std::path::PathBuf;
 
 /// Validate the requirements files.
@@ -19,6 +18,7 @@ pub fn validate_requirements_file(requirement_path: &PathBuf) -> Result<()> {
 }
 
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod test_validate_requirement {
     use super::*;
     use std::fs::File;
@@ -49,7 +49,7 @@ mod test_validate_requirement {
 
 /// Get the requirements from the files.
 pub fn get_requirements_from_files(requirement_dirs: &Vec<PathBuf>) -> Result<Vec<Requirement>> {
-    let requirement_files = get_files(&requirement_dirs, &YAML_EXTENSIONS).context(format!(
+    let requirement_files = get_files(requirement_dirs, &YAML_EXTENSIONS).context(format!(
         "Failed to get the requirement files: {:?}",
         requirement_dirs
     ))?;
@@ -66,7 +66,7 @@ pub fn get_requirements_from_files(requirement_dirs: &Vec<PathBuf>) -> Result<Ve
 
 /// Get the requirements from a file.
 pub fn get_requirements_from_file(requirement_path: &PathBuf) -> Result<Vec<Requirement>> {
-    let requirement = std::fs::read_to_string(&requirement_path).context(format!(
+    let requirement = std::fs::read_to_string(requirement_path).context(format!(
         "Failed to read the requirement file: {:?}",
         requirement_path
     ))?;
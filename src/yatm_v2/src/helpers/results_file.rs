@@ -0,0 +1,70 @@
+use crate::types::{ResultsFile, ResultsFormat};
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Get the path of the results of a workspace version.
+pub fn get_results_path(
+    results_dir: &Path,
+    workspace_version: &str,
+    format: ResultsFormat,
+) -> PathBuf {
+    results_dir.join(format!(
+        "results-{}.{}",
+        workspace_version,
+        format.extension()
+    ))
+}
+
+/// Write a results file, making sure its directory exists.
+pub fn write_results_file(
+    path: &PathBuf,
+    results: &ResultsFile,
+    format: ResultsFormat,
+) -> Result<()> {
+    let contents = match format {
+        ResultsFormat::Yaml => {
+            serde_yaml::to_string(results).context("Failed to serialize the results")?
+        }
+        ResultsFormat::Json => {
+            serde_json::to_string_pretty(results).context("Failed to serialize the results")?
+        }
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .context(format!("Failed to create the results dir: {:?}", dir))?;
+    }
+    std::fs::write(path, contents).context(format!("Failed to write the results: {:?}", path))
+}
+
+#[cfg(test)]
+mod test_results_file {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn written_results_are_read_back() {
+        let dir = tempdir().unwrap();
+        let results = ResultsFile {
+            workspace_version: "1.0.0".to_string(),
+            repo: "owner/repo".to_string(),
+            pulled_at: "2024-01-01T00:00:00+00:00".to_string(),
+            results: vec![],
+            step_pass_rates: vec![],
+        };
+        for format in [ResultsFormat::Yaml, ResultsFormat::Json] {
+            let path = get_results_path(&dir.path().join("results"), "1.0.0", format);
+            write_results_file(&path, &results, format).unwrap();
+            let contents = std::fs::read_to_string(&path).unwrap();
+            let read_results: ResultsFile = match format {
+                ResultsFormat::Yaml => serde_yaml::from_str(&contents).unwrap(),
+                ResultsFormat::Json => serde_json::from_str(&contents).unwrap(),
+            };
+            assert_eq!(read_results, results);
+        }
+        assert!(dir
+            .path()
+            .join("results")
+            .join("results-1.0.0.json")
+            .is_file());
+    }
+}
@@ -0,0 +1,49 @@
+use crate::types::Roster;
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// Read a roster of testers, making sure each tester is only listed once.
+pub fn read_roster(roster_path: &PathBuf) -> Result<Roster> {
+    let roster = std::fs::read_to_string(roster_path)
+        .context(format!("Failed to read the roster: {:?}", roster_path))?;
+    let roster: Roster = serde_yaml::from_str(&roster).context(format!(
+        "Failed to deserialize the roster: {:?}",
+        roster_path
+    ))?;
+    let mut handles = HashSet::new();
+    for tester in &roster.testers {
+        if !handles.insert(tester.handle.to_lowercase()) {
+            anyhow::bail!(
+                "Tester '{}' is listed more than once in the roster: {:?}",
+                tester.handle,
+                roster_path
+            );
+        }
+    }
+    Ok(roster)
+}
+
+#[cfg(test)]
+mod test_roster {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn reads_the_roster() {
+        let dir = tempdir().unwrap();
+        let roster_path = dir.path().join("roster.yaml");
+        std::fs::write(
+            &roster_path,
+            "max_issues_per_tester: 2\ntesters:\n- handle: alice\n  can_test:\n    OS: [Ubuntu]\n- handle: bob\n  max_issues: 5\n",
+        )
+        .unwrap();
+        let roster = read_roster(&roster_path).unwrap();
+        assert_eq!(roster.max_issues_per_tester, Some(2));
+        assert_eq!(roster.testers[0].can_test["OS"], vec!["Ubuntu"]);
+        assert_eq!(roster.testers[1].max_issues, Some(5));
+
+        std::fs::write(&roster_path, "testers:\n- handle: alice\n- handle: Alice\n").unwrap();
+        assert!(read_roster(&roster_path).is_err());
+    }
+}
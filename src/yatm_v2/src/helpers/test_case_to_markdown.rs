@@ -1,7 +1,10 @@
-use crate::types::LocalIssue;
+use crate::helpers::issue_markers::{
+    get_content_hash, get_test_case_id, make_hash_marker, make_id_marker,
+};
+use crate::types::{LabelColors, LocalIssue};
 use anyhow::{Context, Result};
 use askama::Template;
-use common::types::{Action, Expect, Link, Step, TestCase};
+use common::types::{Action, Expect, GithubLabel, Link, Step, TestCase};
 use std::collections::{BTreeMap, HashMap};
 
 #[derive(Template, Clone)]
@@ -19,6 +22,7 @@ pub fn test_case_to_markdown(
     workspace_version: &String,
 ) -> Result<LocalIssue> {
     let labels = get_labels(&test_case, workspace_version);
+    let id = get_test_case_id(&test_case, workspace_version);
 
     let template = GithubIssueTemplate {
         description: test_case.requirement.description,
@@ -29,50 +33,105 @@ pub fn test_case_to_markdown(
             as usize,
     };
     let text_body = template.render().context("Failed to render the template")?;
+    let title = test_case.requirement.name;
+    let content_hash = get_content_hash(&title, &text_body);
+    let text_body = format!(
+        "{}\n\n{}\n{}",
+        text_body.trim_end(),
+        make_id_marker(&id),
+        make_hash_marker(&content_hash)
+    );
 
     Ok(LocalIssue {
+        id,
         labels,
-        title: test_case.requirement.name,
+        title,
         text_body,
+        content_hash,
     })
 }
 
 fn get_labels(test_case: &TestCase, workspace_version: &String) -> Vec<String> {
-    let mut labels: Vec<String> = vec![];
+    let mut labels: Vec<String> =
+        get_github_labels(test_case, workspace_version, &LabelColors::default())
+            .into_iter()
+            .map(|label| label.name)
+            .collect();
+    labels.sort();
+    labels
+}
+
+/// Get the labels of a test case with the colors to create them with.
+pub fn get_github_labels(
+    test_case: &TestCase,
+    workspace_version: &String,
+    label_colors: &LabelColors,
+) -> Vec<GithubLabel> {
+    let mut labels: Vec<(String, String)> = vec![];
     if let Some(labels_) = test_case.builder_used.labels.clone() {
-        labels.extend(labels_.into_iter().map(sanitize_label));
+        labels.extend(
+            labels_
+                .into_iter()
+                .map(|l| (sanitize_label(l), label_colors.builder.clone())),
+        );
     }
     if let Some(labels_) = test_case.requirement.labels.clone() {
-        labels.extend(labels_.into_iter().map(sanitize_label));
+        labels.extend(
+            labels_
+                .into_iter()
+                .map(|l| (sanitize_label(l), label_colors.requirement.clone())),
+        );
     }
-    labels.push(sanitize_label(format!(
-        "requirement: {}",
-        test_case
-            .requirement
-            .shortname
-            .clone()
-            .unwrap_or(test_case.requirement.name.clone())
-    )));
-    labels.extend(permutation_to_labels(&test_case.selected_permutation));
-    labels.push(sanitize_label(project_version_to_label(workspace_version)));
-    labels.sort();
+    labels.push((
+        sanitize_label(format!(
+            "requirement: {}",
+            test_case
+                .requirement
+                .shortname
+                .clone()
+                .unwrap_or(test_case.requirement.name.clone())
+        )),
+        label_colors.requirement.clone(),
+    ));
+    for (key, value) in test_case.selected_permutation.iter() {
+        labels.push((
+            permutation_to_label(key, value),
+            label_colors.get_permutation_color(key),
+        ));
+    }
+    labels.push((
+        sanitize_label(project_version_to_label(workspace_version)),
+        label_colors.version.clone(),
+    ));
     labels
+        .into_iter()
+        .map(|(name, color)| GithubLabel {
+            name,
+            color,
+            description: None,
+        })
+        .collect()
 }
 
 pub fn permutation_to_labels(permutations: &HashMap<String, String>) -> Vec<String> {
     let mut labels: Vec<String> = vec![];
     for (key, value) in permutations.iter() {
-        labels.push(sanitize_label(format!("{}: {}", key, value)));
+        labels.push(permutation_to_label(key, value));
     }
     labels.sort();
     labels
 }
 
+fn permutation_to_label(key: &String, value: &String) -> String {
+    sanitize_label(format!("{}: {}", key, value))
+}
+
 pub fn project_version_to_label(workspace_version: &String) -> String {
     format!("version: {}", workspace_version)
 }
 
-fn sanitize_label(label: String) -> String {
+/// Make a label fit for Github, which doesn't allow some characters or long names.
+pub fn sanitize_label(label: String) -> String {
     let sanitized = label.replace(",", "").replace("`", "");
     if sanitized.len() > 50 {
         sanitized[..50].to_string()
@@ -80,3 +139,46 @@ fn sanitize_label(label: String) -> String {
         sanitized
     }
 }
+
+#[cfg(test)]
+mod test_get_github_labels {
+    use super::*;
+    use common::types::{Requirement, TestCasesBuilder};
+
+    #[test]
+    fn labels_are_colored_by_family() {
+        let mut label_colors = LabelColors::default();
+        label_colors
+            .permutations
+            .insert("RMW".to_string(), "000000".to_string());
+        let test_case = TestCase {
+            requirement: Requirement::default(),
+            builder_used: TestCasesBuilder::default(),
+            selected_permutation: HashMap::from([
+                ("RMW".to_string(), "CycloneDDS".to_string()),
+                ("Operating System".to_string(), "Ubuntu 22.04".to_string()),
+            ]),
+        };
+        let labels = get_github_labels(&test_case, &"1.0.0".to_string(), &label_colors);
+        let get_color = |name: &str| {
+            labels
+                .iter()
+                .find(|l| l.name == name)
+                .map(|l| l.color.clone())
+                .unwrap()
+        };
+
+        assert_eq!(get_color("Demo"), label_colors.builder);
+        assert_eq!(get_color("label"), label_colors.requirement);
+        assert_eq!(
+            get_color("requirement: shortname"),
+            label_colors.requirement
+        );
+        assert_eq!(get_color("RMW: CycloneDDS"), "000000");
+        assert_eq!(
+            get_color("Operating System: Ubuntu 22.04"),
+            label_colors.permutation
+        );
+        assert_eq!(get_color("version: 1.0.0"), label_colors.version);
+    }
+}
@@ -3,7 +3,6 @@ use crate::helpers::get_files;
 use anyhow::{Context, Result};
 use common::types::TestCasesBuilder;
 use common::types::TestCasesBuilderFile;
-use serde_yaml;
 use std::path::PathBuf;
 
 /// Validate the test cases builder file.
@@ -13,6 +12,7 @@ pub fn validate_test_cases_builder_file(test_cases_builder_path: &PathBuf) -> Re
 }
 
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod test_validate_test_cases_builder {
     use super::*;
     use std::fs::File;
@@ -61,7 +61,7 @@ pub fn get_test_cases_builders_from_file(
     test_cases_builder_path: &PathBuf,
 ) -> Result<Vec<TestCasesBuilder>> {
     let test_cases_builder_file =
-        std::fs::read_to_string(&test_cases_builder_path).context(format!(
+        std::fs::read_to_string(test_cases_builder_path).context(format!(
             "No test cases builder file found: {:?}",
             test_cases_builder_path
         ))?;
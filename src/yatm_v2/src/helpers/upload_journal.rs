@@ -0,0 +1,104 @@
+use crate::types::UploadJournalEntry;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Read the entries of the upload journal, if there is one.
+pub fn read_upload_journal(journal_path: &PathBuf) -> Result<Vec<UploadJournalEntry>> {
+    if !journal_path.is_file() {
+        return Ok(vec![]);
+    }
+    let journal = std::fs::read_to_string(journal_path).context(format!(
+        "Failed to read the upload journal: {:?}",
+        journal_path
+    ))?;
+    serde_yaml::Deserializer::from_str(&journal)
+        .map(|document| {
+            UploadJournalEntry::deserialize(document).context(format!(
+                "Failed to deserialize the upload journal: {:?}",
+                journal_path
+            ))
+        })
+        .collect()
+}
+
+/// Append an entry to the upload journal.
+///
+/// Each entry is its own YAML document so that the journal never has to be rewritten.
+pub fn append_upload_journal_entry(
+    journal_path: &PathBuf,
+    entry: &UploadJournalEntry,
+) -> Result<()> {
+    let entry =
+        serde_yaml::to_string(entry).context("Failed to turn the journal entry into a string")?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(journal_path)
+        .context(format!(
+            "Failed to open the upload journal: {:?}",
+            journal_path
+        ))?;
+    file.write_all(format!("---\n{}", entry).as_bytes())
+        .context(format!(
+            "Failed to write to the upload journal: {:?}",
+            journal_path
+        ))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_upload_journal {
+    use super::*;
+    use crate::types::UploadJournalStatus;
+    use tempfile::tempdir;
+
+    fn make_entry(operation_key: &str, status: UploadJournalStatus) -> UploadJournalEntry {
+        UploadJournalEntry {
+            timestamp: "2024-01-01T00:00:00+00:00".to_string(),
+            repo: "owner/repo".to_string(),
+            workspace_version: Some("1.0.0".to_string()),
+            plan_created_at: "2024-01-01T00:00:00+00:00".to_string(),
+            operation_key: operation_key.to_string(),
+            test_case_id: Some("abc123".to_string()),
+            issue_number: Some(1),
+            status,
+        }
+    }
+
+    #[test]
+    fn missing_journal_is_empty() {
+        let dir = tempdir().unwrap();
+        let journal_path = dir.path().join("journal.yaml");
+        assert!(read_upload_journal(&journal_path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn appended_entries_are_read_back() {
+        let dir = tempdir().unwrap();
+        let journal_path = dir.path().join("journal.yaml");
+        append_upload_journal_entry(
+            &journal_path,
+            &make_entry("create-issue:abc123", UploadJournalStatus::Done),
+        )
+        .unwrap();
+        append_upload_journal_entry(
+            &journal_path,
+            &make_entry(
+                "update-issue:1",
+                UploadJournalStatus::Failed("error".to_string()),
+            ),
+        )
+        .unwrap();
+
+        let entries = read_upload_journal(&journal_path).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].operation_key, "create-issue:abc123");
+        assert_eq!(entries[0].status, UploadJournalStatus::Done);
+        assert_eq!(
+            entries[1].status,
+            UploadJournalStatus::Failed("error".to_string())
+        );
+    }
+}
@@ -1,12 +1,13 @@
 mod app;
 mod constants;
 mod helpers;
+#[cfg(test)]
+mod test_utils;
 mod types;
 
 use anyhow::{Ok, Result};
 
 use crate::app::cli;
-use tokio;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -0,0 +1,58 @@
+use crate::helpers::{get_test_case_id, project_version_to_label};
+use common::types::{Requirement, TestCase, TestCasesBuilder, TrackerIssue, TrackerIssueState};
+
+/// Make an open issue that isn't a pull request, for tests to adjust as they need.
+pub fn make_tracker_issue(
+    number: u64,
+    title: &str,
+    body: Option<&str>,
+    labels: Vec<String>,
+) -> TrackerIssue {
+    TrackerIssue {
+        number,
+        title: title.to_string(),
+        body: body.map(|b| b.to_string()),
+        labels,
+        state: TrackerIssueState::Open,
+        url: String::new(),
+        is_pull_request: false,
+        milestone: None,
+        closed_at: None,
+        assignees: vec![],
+    }
+}
+
+/// Make a test case of the default builder for a requirement named `name`.
+pub fn make_test_case(name: &str, permutation: &[(&str, &str)]) -> TestCase {
+    TestCase {
+        requirement: Requirement {
+            name: name.to_string(),
+            shortname: Some(name.to_string()),
+            ..Default::default()
+        },
+        builder_used: TestCasesBuilder::default(),
+        selected_permutation: permutation
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+    }
+}
+
+/// Make the issue of a test case for a workspace version, with its identity marker and version
+/// label.
+pub fn make_test_case_issue(
+    number: u64,
+    test_case: &TestCase,
+    workspace_version: &str,
+) -> TrackerIssue {
+    let workspace_version = workspace_version.to_string();
+    make_tracker_issue(
+        number,
+        &format!("Issue {}", number),
+        Some(&format!(
+            "<!-- yatm-id: {} -->",
+            get_test_case_id(test_case, &workspace_version)
+        )),
+        vec![project_version_to_label(&workspace_version)],
+    )
+}
@@ -0,0 +1,115 @@
+use clap::Subcommand;
+use std::fmt;
+
+/// What a term of an issue filter compares.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterKey {
+    /// The name or shortname of the requirement of the issue's test case.
+    Requirement,
+    /// The name of the builder of the issue's test case.
+    Builder,
+    /// The workspace version in the issue's labels.
+    Version,
+    /// Whether the issue is `open` or `closed`.
+    State,
+    /// The value of a permutation key of the issue's test case.
+    Permutation(String),
+}
+
+/// A `key=value` or `key!=value` term of an issue filter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterTerm {
+    pub key: FilterKey,
+    /// The term matches when the issue has any of the values.
+    pub values: Vec<String>,
+    /// Whether the term matches when the issue has none of the values instead.
+    pub is_negated: bool,
+}
+
+/// Issues that match all of the terms.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IssueFilter {
+    pub terms: Vec<FilterTerm>,
+}
+
+/// What to do to each of the issues that match the filter.
+#[derive(Debug, Clone, PartialEq, Subcommand)]
+pub enum BulkAction {
+    /// Close the open issues
+    Close,
+    /// Reopen the closed issues
+    Reopen,
+    /// Add and remove labels
+    Relabel {
+        /// A label to add, can be given more than once
+        #[clap(long)]
+        add: Vec<String>,
+        /// A label to remove, can be given more than once
+        #[clap(long)]
+        remove: Vec<String>,
+    },
+    /// Comment on the issues
+    Comment {
+        /// The comment, in Markdown
+        #[clap(long)]
+        body: String,
+    },
+    /// Lock the conversation of the issues
+    Lock,
+}
+
+impl BulkAction {
+    /// Describe the action applied to a number of issues, to ask for confirmation.
+    pub fn describe(&self, count: usize) -> String {
+        let quote = |labels: &[String]| {
+            labels
+                .iter()
+                .map(|l| format!("'{}'", l))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            BulkAction::Close => format!("close {} issues", count),
+            BulkAction::Reopen => format!("reopen {} issues", count),
+            BulkAction::Relabel { add, remove } => match (add.is_empty(), remove.is_empty()) {
+                (false, true) => format!("add {} to {} issues", quote(add), count),
+                (true, false) => format!("remove {} from {} issues", quote(remove), count),
+                _ => format!(
+                    "add {} to and remove {} from {} issues",
+                    quote(add),
+                    quote(remove),
+                    count
+                ),
+            },
+            BulkAction::Comment { .. } => format!("comment on {} issues", count),
+            BulkAction::Lock => format!("lock {} issues", count),
+        }
+    }
+}
+
+/// An action to apply to a single issue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkOperation {
+    pub number: u64,
+    pub title: String,
+    /// The action, with only the label changes the issue needs when relabeling.
+    pub action: BulkAction,
+}
+
+impl fmt::Display for BulkOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.action {
+            BulkAction::Close => write!(f, "Close issue #{}: {}", self.number, self.title),
+            BulkAction::Reopen => write!(f, "Reopen issue #{}: {}", self.number, self.title),
+            BulkAction::Relabel { add, remove } => write!(
+                f,
+                "Relabel issue #{}: {} (add: {:?}, remove: {:?})",
+                self.number, self.title, add, remove
+            ),
+            BulkAction::Comment { .. } => {
+                write!(f, "Comment on issue #{}: {}", self.number, self.title)
+            }
+            BulkAction::Lock => write!(f, "Lock issue #{}: {}", self.number, self.title),
+        }
+    }
+}
@@ -1,15 +1,40 @@
-use common::types::GithubLabel;
+use crate::types::{GithubProject, IssueTrackerKind, LabelColors, ResultLabels, VersionMilestone};
+use common::types::{GithubAuth, GithubLabel};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Config {
+    /// The service that hosts the issues.
+    #[serde(default)]
+    pub issue_tracker: IssueTrackerKind,
+    /// The address of a self-hosted Gitlab instance, when the issues are on Gitlab.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gitlab_url: Option<String>,
+    /// The address of the Github API, for Github Enterprise Server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github_api_url: Option<String>,
+    /// The address of the Github website, for Github Enterprise Server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github_web_url: Option<String>,
+    /// How to authenticate with Github.
+    #[serde(default)]
+    pub github_auth: GithubAuth,
+    /// The project board to add the created issues to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github_project: Option<GithubProject>,
+    /// The directory to store the issues in, when they are kept locally.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_tracker_dir: Option<PathBuf>,
     /// The owner of the repository.
     pub repo_owner: String,
     /// The name of the repository.
     pub repo_name: String,
     /// The version of the workspace.
     pub workspace_version: String,
+    /// The milestone to assign the issues of the workspace version to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub milestone: Option<VersionMilestone>,
     /// The version of yatm_v2 to use.
     pub yatm_v2_version: String,
     /// The directories to search for requirements.
@@ -22,17 +47,45 @@ pub struct Config {
     pub new_test_cases_builder_dir: PathBuf,
     /// The directory to store generated files.
     pub generated_files_dir: PathBuf,
+    /// The directory to store the results pulled from the issue tracker, to keep with the workspace.
+    #[serde(default = "default_results_dir")]
+    pub results_dir: PathBuf,
     /// The labels to create in the repository.
     pub labels: Vec<GithubLabel>,
+    /// The colors of the labels created for the test cases on upload.
+    #[serde(default = "LabelColors::default")]
+    pub label_colors: LabelColors,
+    /// The triage labels that tell how testing went.
+    #[serde(default = "ResultLabels::default")]
+    pub result_labels: ResultLabels,
+    /// The number of requests sent to the issue tracker at the same time.
+    #[serde(default = "default_concurrency", alias = "github_concurrency")]
+    pub concurrency: usize,
 }
 
-impl Config {
-    pub fn default() -> Self {
+fn default_results_dir() -> PathBuf {
+    PathBuf::new().join("results")
+}
+
+fn default_concurrency() -> usize {
+    4
+}
+
+impl Default for Config {
+    fn default() -> Self {
         let version: &str = env!("CARGO_PKG_VERSION");
         let requirements_dir = "requirements";
         let test_cases_builder_dir = "test_cases_builders";
         Config {
+            issue_tracker: IssueTrackerKind::Github,
+            gitlab_url: None,
+            github_api_url: None,
+            github_web_url: None,
+            github_auth: GithubAuth::Env,
+            github_project: None,
+            local_tracker_dir: None,
             workspace_version: "0.0.1".to_string(),
+            milestone: None,
             yatm_v2_version: version.to_string(),
             repo_name: "repo_name".to_string(),
             repo_owner: "repo_owner".to_string(),
@@ -41,6 +94,7 @@ impl Config {
             test_cases_builders_dirs: vec![PathBuf::new().join(test_cases_builder_dir)],
             new_test_cases_builder_dir: PathBuf::new().join(test_cases_builder_dir),
             generated_files_dir: PathBuf::new().join("generated_files"),
+            results_dir: default_results_dir(),
             labels: vec![
                 GithubLabel {
                     name: "needs attention: bug".to_string(),
@@ -83,6 +137,9 @@ impl Config {
                     description: Some("A core team member has tested this".to_string()),
                 },
             ],
+            label_colors: LabelColors::default(),
+            result_labels: ResultLabels::default(),
+            concurrency: default_concurrency(),
         }
     }
 }
@@ -0,0 +1,41 @@
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// How a test case did, from the state and labels of its issue.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CoverageStatus {
+    /// The issue was closed or has one of the passed labels.
+    Passed,
+    /// There is no issue yet, or nobody has reported on it.
+    Untested,
+    /// The issue has one of the failed or bad instructions labels.
+    Failed,
+}
+
+impl CoverageStatus {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            CoverageStatus::Passed => "✅",
+            CoverageStatus::Untested => "⬜",
+            CoverageStatus::Failed => "❌",
+        }
+    }
+}
+
+/// How to render the coverage report.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum CoverageFormat {
+    /// A Markdown table, to paste in an issue or a wiki
+    Markdown,
+    /// An HTML page
+    Html,
+}
+
+impl CoverageFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            CoverageFormat::Markdown => "md",
+            CoverageFormat::Html => "html",
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A Github project board that the issues created on upload are added to.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct GithubProject {
+    /// The user or organization that owns the project.
+    pub owner: String,
+    /// The number of the project, as shown in its address.
+    pub number: u64,
+    /// The project field to set to the name of the test cases builder.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub builder_field: Option<String>,
+    /// The project fields to set to the value of each permutation, by permutation name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub permutation_fields: BTreeMap<String, String>,
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// The service that hosts the test case issues.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueTrackerKind {
+    #[default]
+    Github,
+    Gitlab,
+    /// Files in a directory, for testing and offline use
+    Local,
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LabelColors {
+    /// The color of the `requirement: ...` labels and the labels of requirements.
+    pub requirement: String,
+    /// The color of the labels from the test cases builders.
+    pub builder: String,
+    /// The color of the `version: ...` labels.
+    pub version: String,
+    /// The color of the permutation labels that don't have a color in `permutations`.
+    pub permutation: String,
+    /// The color of the permutation labels by permutation name.
+    #[serde(default)]
+    pub permutations: HashMap<String, String>,
+}
+
+impl Default for LabelColors {
+    fn default() -> Self {
+        LabelColors {
+            requirement: "c5def5".to_string(),
+            builder: "fef2c0".to_string(),
+            version: "5319e7".to_string(),
+            permutation: "bfd4f2".to_string(),
+            permutations: HashMap::new(),
+        }
+    }
+}
+
+impl LabelColors {
+    /// Get the color for the labels of a permutation.
+    pub fn get_permutation_color(&self, permutation_name: &str) -> String {
+        self.permutations
+            .get(permutation_name)
+            .unwrap_or(&self.permutation)
+            .clone()
+    }
+}
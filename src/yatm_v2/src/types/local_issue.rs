@@ -2,7 +2,11 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LocalIssue {
+    /// The identity key of the test case the issue was made from.
+    pub id: String,
     pub labels: Vec<String>,
     pub title: String,
     pub text_body: String,
+    /// The hash of the normalised title and body, recorded in the body on upload.
+    pub content_hash: String,
 }
@@ -0,0 +1,74 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// How to print the metrics.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum MetricsFormat {
+    /// A table to read in the terminal
+    Table,
+    /// A JSON array with an object per row
+    Json,
+    /// CSV with a header row
+    Csv,
+}
+
+/// How to render the metrics report.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum MetricsReportFormat {
+    /// An SVG image of the chart
+    Svg,
+    /// An HTML page with the chart and the data points
+    Html,
+}
+
+impl MetricsReportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            MetricsReportFormat::Svg => "svg",
+            MetricsReportFormat::Html => "html",
+        }
+    }
+}
+
+/// The metrics of a workspace version at one point in time.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MetricsSnapshot {
+    /// When the metrics were taken, as an RFC 3339 timestamp.
+    pub timestamp: String,
+    pub workspace_version: String,
+    /// The label the issues were filtered by, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub rows: Vec<MetricsRow>,
+}
+
+/// The counts of the issues in one group of the metrics breakdown.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MetricsRow {
+    /// What the issues are grouped by, such as `permutation` or `builder`.
+    pub group: String,
+    /// The value the issues of the group share, such as `OS: Ubuntu 22.04`.
+    pub value: String,
+    pub open: usize,
+    pub closed: usize,
+    pub total: usize,
+    /// The share of the issues that are closed, from 0 to 100.
+    pub percent_closed: f64,
+}
+
+impl MetricsRow {
+    pub fn new(group: &str, value: &str, open: usize, closed: usize) -> Self {
+        let total = open + closed;
+        MetricsRow {
+            group: group.to_string(),
+            value: value.to_string(),
+            open,
+            closed,
+            total,
+            percent_closed: match total {
+                0 => 0.0,
+                total => closed as f64 / total as f64 * 100.0,
+            },
+        }
+    }
+}
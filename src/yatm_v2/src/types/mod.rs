@@ -1,5 +1,30 @@
+mod bulk;
 mod config;
+mod coverage;
+mod github_project;
+mod issue_tracker_kind;
+mod label_colors;
 mod local_issue;
+mod metrics;
+mod results;
+mod roster;
+mod upload_journal;
+mod upload_plan;
+mod version_milestone;
 
+pub use bulk::{BulkAction, BulkOperation, FilterKey, FilterTerm, IssueFilter};
 pub use config::Config;
+pub use coverage::{CoverageFormat, CoverageStatus};
+pub use github_project::GithubProject;
+pub use issue_tracker_kind::IssueTrackerKind;
+pub use label_colors::LabelColors;
 pub use local_issue::LocalIssue;
+pub use metrics::{MetricsFormat, MetricsReportFormat, MetricsRow, MetricsSnapshot};
+pub use results::{
+    IssueResult, ResultLabels, ResultSource, ResultsFile, ResultsFormat, StepChecklist,
+    StepPassRate, TestResult,
+};
+pub use roster::{Assignment, AssignmentPlan, Roster, Tester, UnassignedIssue};
+pub use upload_journal::{UploadJournalEntry, UploadJournalStatus};
+pub use upload_plan::{OrphanAction, UploadOperation, UploadOptions, UploadPlan};
+pub use version_milestone::VersionMilestone;
@@ -0,0 +1,142 @@
+use clap::ValueEnum;
+use common::types::TrackerIssueState;
+use serde::{Deserialize, Serialize};
+
+/// The triage labels that tell how testing went.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ResultLabels {
+    /// The labels of issues that were tested and work.
+    pub passed: Vec<String>,
+    /// The labels of issues that were tested and found a bug.
+    pub failed: Vec<String>,
+    /// The labels of issues that couldn't be tested.
+    #[serde(default)]
+    pub blocked: Vec<String>,
+    /// The labels of issues whose instructions are wrong or incomplete.
+    pub bad_instructions: Vec<String>,
+}
+
+impl Default for ResultLabels {
+    fn default() -> Self {
+        ResultLabels {
+            passed: vec!["confirmed: works as expected".to_string()],
+            failed: vec![
+                "needs attention: bug".to_string(),
+                "confirmed: bug".to_string(),
+            ],
+            blocked: vec![],
+            bad_instructions: vec![
+                "needs attention: bad instructions".to_string(),
+                "confirmed: bad instructions".to_string(),
+            ],
+        }
+    }
+}
+
+/// The outcome of testing a test case.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TestResult {
+    Passed,
+    Failed,
+    Blocked,
+    BadInstructions,
+    Untested,
+}
+
+/// Where the result of an issue was read from.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultSource {
+    /// One of the result labels is on the issue.
+    Label,
+    /// A comment has a `Result:` line.
+    Comment,
+    /// The issue was closed without a result label or comment.
+    Closed,
+    /// Nothing was reported.
+    None,
+}
+
+/// The result of the issue of a test case.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct IssueResult {
+    /// The identity key of the test case the issue was made from.
+    pub test_case_id: String,
+    pub issue_number: u64,
+    pub title: String,
+    pub url: String,
+    pub state: TrackerIssueState,
+    pub result: TestResult,
+    pub source: ResultSource,
+    /// Who applied the label, wrote the comment or closed the issue that gave the result.
+    pub reported_by: Option<String>,
+    /// When the result was reported, as an RFC 3339 timestamp.
+    pub reported_at: Option<String>,
+    /// Who closed the issue, if it's closed.
+    pub closed_by: Option<String>,
+    /// Everyone who commented on or labeled the issue.
+    pub participants: Vec<String>,
+    pub comment_count: usize,
+    /// The ticked boxes of each step that renders its expected results as a checklist.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checklist: Vec<StepChecklist>,
+}
+
+/// The boxes of a step's checklist in an issue body.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct StepChecklist {
+    /// The number of the step, starting at 1.
+    pub step: usize,
+    pub checked: usize,
+    pub total: usize,
+}
+
+impl StepChecklist {
+    /// A step passed when every one of its expected results was ticked.
+    pub fn passed(&self) -> bool {
+        self.total > 0 && self.checked == self.total
+    }
+}
+
+/// How often a step of a requirement passed, across the issues that reported on it.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct StepPassRate {
+    /// The title of the issues, which is the requirement name.
+    pub requirement: String,
+    pub step: usize,
+    /// The number of issues where every box of the step is ticked.
+    pub passed: usize,
+    /// The number of issues with a result or at least one ticked box.
+    pub reported: usize,
+    pub percent_passed: f32,
+}
+
+/// The results of the issues of a workspace version, as they were when pulled.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ResultsFile {
+    pub workspace_version: String,
+    /// The repository the results were pulled from, as `owner/name`.
+    pub repo: String,
+    /// When the results were pulled, as an RFC 3339 timestamp.
+    pub pulled_at: String,
+    pub results: Vec<IssueResult>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub step_pass_rates: Vec<StepPassRate>,
+}
+
+/// How to store the results.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ResultsFormat {
+    Yaml,
+    Json,
+}
+
+impl ResultsFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ResultsFormat::Yaml => "yaml",
+            ResultsFormat::Json => "json",
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The testers that issues can be handed out to.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Roster {
+    /// The most open issues of the workspace version that a tester can be assigned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_issues_per_tester: Option<usize>,
+    pub testers: Vec<Tester>,
+}
+
+/// A tester and the permutations they can cover.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Tester {
+    /// The tester's username on the issue tracker.
+    pub handle: String,
+    /// The values each permutation key can take for this tester, such as the operating systems
+    /// they run. A tester can cover any value of the keys that aren't listed.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub can_test: BTreeMap<String, Vec<String>>,
+    /// Overrides the roster's cap for this tester.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_issues: Option<usize>,
+}
+
+impl Tester {
+    /// Whether the tester can cover every value of a test case's permutation.
+    pub fn can_test(&self, permutation: &BTreeMap<String, String>) -> bool {
+        permutation.iter().all(|(key, value)| {
+            self.can_test
+                .get(key)
+                .is_none_or(|values| values.contains(value))
+        })
+    }
+}
+
+/// An issue handed out to a tester.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment {
+    pub issue_number: u64,
+    pub title: String,
+    pub tester: String,
+}
+
+impl fmt::Display for Assignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Assign issue #{} to @{}: {}",
+            self.issue_number, self.tester, self.title
+        )
+    }
+}
+
+/// An open issue that can't be handed out, and why.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnassignedIssue {
+    pub issue_number: u64,
+    pub title: String,
+    pub reason: String,
+}
+
+impl fmt::Display for UnassignedIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Can't assign issue #{}: {} ({})",
+            self.issue_number, self.title, self.reason
+        )
+    }
+}
+
+/// The issues to hand out, and the ones nobody can take.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssignmentPlan {
+    pub assignments: Vec<Assignment>,
+    pub unassigned: Vec<UnassignedIssue>,
+    /// The number of open issues of the workspace version each tester has once the plan is
+    /// applied, by handle.
+    pub loads: BTreeMap<String, usize>,
+}
@@ -0,0 +1,39 @@
+use crate::types::UploadPlan;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UploadJournalEntry {
+    /// When the operation finished.
+    pub timestamp: String,
+    /// The repository the operation was applied to, as `owner/name`.
+    pub repo: String,
+    /// The workspace version of the plan the operation belongs to, which older journals don't
+    /// record.
+    #[serde(default)]
+    pub workspace_version: Option<String>,
+    /// When the plan the operation belongs to was made.
+    pub plan_created_at: String,
+    /// The key of the operation within its plan.
+    pub operation_key: String,
+    /// The identity key of the test case, for operations that create an issue.
+    pub test_case_id: Option<String>,
+    /// The Github issue number the operation was applied to.
+    pub issue_number: Option<u64>,
+    /// The outcome of the operation.
+    pub status: UploadJournalStatus,
+}
+
+impl UploadJournalEntry {
+    /// Whether the entry records an operation of the plan.
+    pub fn is_of_plan(&self, plan: &UploadPlan) -> bool {
+        self.repo == format!("{}/{}", plan.repo_owner, plan.repo_name)
+            && self.workspace_version.as_ref() == Some(&plan.workspace_version)
+            && self.plan_created_at == plan.created_at
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum UploadJournalStatus {
+    Done,
+    Failed(String),
+}
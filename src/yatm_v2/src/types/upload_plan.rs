@@ -0,0 +1,228 @@
+use clap::ValueEnum;
+use common::types::GithubLabel;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UploadPlan {
+    /// The owner of the repository the plan was made for.
+    pub repo_owner: String,
+    /// The name of the repository the plan was made for.
+    pub repo_name: String,
+    /// The version of the workspace the plan was made for.
+    pub workspace_version: String,
+    /// When the plan was made.
+    pub created_at: String,
+    /// The hash of the issues, labels and comments on Github when the plan was made.
+    pub remote_state_hash: String,
+    /// The hash of each issue on Github when the plan was made, by issue number.
+    #[serde(default)]
+    pub remote_issue_hashes: BTreeMap<u64, String>,
+    /// The milestones on Github when the plan was made, when the config sets one.
+    #[serde(default)]
+    pub remote_milestones: Vec<String>,
+    /// The operations to apply, in order.
+    pub operations: Vec<UploadOperation>,
+}
+
+impl UploadPlan {
+    /// Whether the plan changes issues that already exist, rather than only adding new ones.
+    pub fn changes_existing_issues(&self) -> bool {
+        self.operations.iter().any(|o| o.issue_number().is_some())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum UploadOperation {
+    /// Create labels in the repository
+    CreateLabels { labels: Vec<GithubLabel> },
+    /// Create a milestone in the repository
+    CreateMilestone {
+        title: String,
+        due_on: Option<String>,
+    },
+    /// Create an issue for a test case
+    CreateIssue {
+        id: String,
+        title: String,
+        body: String,
+        labels: Vec<String>,
+    },
+    /// Add the issue created for a test case to the project board and set its fields
+    AddToProject {
+        id: String,
+        title: String,
+        fields: BTreeMap<String, String>,
+    },
+    /// Assign the issue of a test case to a milestone, the issue created for it if no number is given
+    SetMilestone {
+        id: String,
+        number: Option<u64>,
+        title: String,
+        milestone: String,
+    },
+    /// Comment on the issue of the previous version that a test case takes over, reopening it
+    /// when the test case changed since
+    CarryOver {
+        id: String,
+        number: u64,
+        title: String,
+        body: String,
+        reopen: bool,
+    },
+    /// Update the title and body of an issue
+    UpdateIssue {
+        number: u64,
+        title: String,
+        body: String,
+    },
+    /// Add and remove labels on an issue
+    Relabel {
+        number: u64,
+        title: String,
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Comment on an issue
+    Comment {
+        number: u64,
+        title: String,
+        body: String,
+    },
+    /// Close an issue
+    CloseIssue { number: u64, title: String },
+}
+
+impl UploadOperation {
+    /// The number of the existing issue the operation applies to, if it applies to one.
+    pub fn issue_number(&self) -> Option<u64> {
+        match self {
+            UploadOperation::SetMilestone { number, .. } => *number,
+            UploadOperation::CarryOver { number, .. }
+            | UploadOperation::UpdateIssue { number, .. }
+            | UploadOperation::Relabel { number, .. }
+            | UploadOperation::Comment { number, .. }
+            | UploadOperation::CloseIssue { number, .. } => Some(*number),
+            UploadOperation::CreateLabels { .. }
+            | UploadOperation::CreateMilestone { .. }
+            | UploadOperation::CreateIssue { .. }
+            | UploadOperation::AddToProject { .. } => None,
+        }
+    }
+
+    /// Get a key that identifies the operation within its plan.
+    pub fn key(&self) -> String {
+        match self {
+            UploadOperation::CreateLabels { .. } => "create-labels".to_string(),
+            UploadOperation::CreateMilestone { title, .. } => {
+                format!("create-milestone:{}", title)
+            }
+            UploadOperation::CreateIssue { id, .. } => format!("create-issue:{}", id),
+            UploadOperation::SetMilestone { id, .. } => format!("set-milestone:{}", id),
+            UploadOperation::AddToProject { id, .. } => format!("add-to-project:{}", id),
+            UploadOperation::CarryOver { id, .. } => format!("carry-over:{}", id),
+            UploadOperation::UpdateIssue { number, .. } => format!("update-issue:{}", number),
+            UploadOperation::Relabel { number, .. } => format!("relabel:{}", number),
+            UploadOperation::Comment { number, .. } => format!("comment:{}", number),
+            UploadOperation::CloseIssue { number, .. } => format!("close-issue:{}", number),
+        }
+    }
+}
+
+impl fmt::Display for UploadOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UploadOperation::CreateLabels { labels } => write!(
+                f,
+                "Create labels: {}",
+                labels
+                    .iter()
+                    .map(|l| format!("`{}`", l.name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            UploadOperation::CreateMilestone { title, due_on } => match due_on {
+                Some(due_on) => write!(f, "Create milestone: {} (due on {})", title, due_on),
+                None => write!(f, "Create milestone: {}", title),
+            },
+            UploadOperation::CreateIssue { title, .. } => write!(f, "Create issue: {}", title),
+            UploadOperation::SetMilestone {
+                number,
+                title,
+                milestone,
+                ..
+            } => match number {
+                Some(number) => write!(
+                    f,
+                    "Set milestone of issue #{}: {} ({})",
+                    number, title, milestone
+                ),
+                None => write!(f, "Set milestone of new issue: {} ({})", title, milestone),
+            },
+            UploadOperation::AddToProject { title, fields, .. } => write!(
+                f,
+                "Add issue to project: {} ({})",
+                title,
+                fields
+                    .iter()
+                    .map(|(name, value)| format!("{}: {}", name, value))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            UploadOperation::CarryOver {
+                number,
+                title,
+                reopen,
+                ..
+            } => write!(
+                f,
+                "Carry over issue #{}{}: {}",
+                number,
+                if *reopen { " and reopen it" } else { "" },
+                title
+            ),
+            UploadOperation::UpdateIssue { number, title, .. } => {
+                write!(f, "Update issue #{}: {}", number, title)
+            }
+            UploadOperation::Relabel {
+                number,
+                title,
+                add,
+                remove,
+            } => write!(
+                f,
+                "Relabel issue #{}: {} (add: {:?}, remove: {:?})",
+                number, title, add, remove
+            ),
+            UploadOperation::Comment { number, title, .. } => {
+                write!(f, "Comment on issue #{}: {}", number, title)
+            }
+            UploadOperation::CloseIssue { number, title } => {
+                write!(f, "Close issue #{}: {}", number, title)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OrphanAction {
+    /// Only list the orphaned issues
+    List,
+    /// Close the orphaned issues
+    Close,
+    /// Comment on the orphaned issues
+    Comment,
+    /// Add the obsolete label to the orphaned issues
+    Label,
+}
+
+pub struct UploadOptions {
+    /// Overwrite issues that were edited on Github since the last upload.
+    pub force: bool,
+    /// What to do with open issues that no test case produces anymore.
+    pub orphans: OrphanAction,
+    /// The workspace version to move the issues over from, keeping the results of the test cases
+    /// that didn't change.
+    pub carry_over_from: Option<String>,
+}
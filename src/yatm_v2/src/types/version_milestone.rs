@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// The milestone that the issues of the workspace version are assigned to.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct VersionMilestone {
+    /// The title of the milestone, the workspace version if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// When the milestone is due, as `YYYY-MM-DD`, used when it's created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_on: Option<String>,
+}